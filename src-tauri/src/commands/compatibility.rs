@@ -0,0 +1,60 @@
+use crate::commands::ingredient::{load_dictionary, resolve_ingredient_name};
+use crate::models::{CompatibilityReport, Ingredient, IngredientPairing};
+use crate::services::compatibility;
+use tauri::AppHandle;
+
+/// Which of the given ingredients are synergistic, fine or cautionary together, each pair with its
+/// rationale and source. Names may be INCI, Korean or any alias the dictionary knows.
+#[tauri::command]
+pub async fn check_ingredient_compatibility(
+    app: AppHandle,
+    ingredients: Vec<String>,
+) -> Result<CompatibilityReport, String> {
+    let names: Vec<&str> = ingredients.iter().map(|n| n.trim()).filter(|n| !n.is_empty()).collect();
+    if names.len() < 2 {
+        return Err("성분을 두 개 이상 입력해주세요.".to_string());
+    }
+
+    let dictionary = load_dictionary(&app)?;
+    let mut resolved: Vec<Ingredient> = Vec::new();
+    let mut unresolved = Vec::new();
+    for name in names {
+        let Some(ingredient) = dictionary.resolve(name) else {
+            unresolved.push(name.to_string());
+            continue;
+        };
+        if !resolved.iter().any(|i| i.id == ingredient.id) {
+            resolved.push(ingredient.clone());
+        }
+    }
+
+    Ok(CompatibilityReport {
+        pairings: compatibility::engine().pairings(&resolved),
+        ingredients: resolved,
+        unresolved,
+    })
+}
+
+/// Everything the pairing rules say about one ingredient with others
+#[tauri::command]
+pub async fn get_ingredient_pairings(
+    app: AppHandle,
+    ingredient_name: String,
+) -> Result<Vec<IngredientPairing>, String> {
+    let Some(ingredient) = resolve_ingredient_name(&app, &ingredient_name) else {
+        return Err(format!("성분 사전에서 찾을 수 없습니다: {}", ingredient_name.trim()));
+    };
+    let dictionary = load_dictionary(&app)?;
+    Ok(compatibility::engine().partners(&ingredient, &dictionary))
+}
+
+/// Pairing notes for a keyword that names an ingredient, for content planning prompts
+pub(crate) fn pairing_context(app: &AppHandle, keyword: &str) -> Option<String> {
+    let ingredient = resolve_ingredient_name(app, keyword)?;
+    let dictionary = load_dictionary(app).ok()?;
+    let pairings = compatibility::engine().partners(&ingredient, &dictionary);
+    if pairings.is_empty() {
+        return None;
+    }
+    Some(compatibility::prompt_lines(&pairings).join("\n"))
+}
@@ -1,9 +1,52 @@
+use crate::commands::compatibility::pairing_context;
+use crate::commands::settings::get_settings;
 use crate::models::{CharacterPersona, ContentGenerationRequest, ContentPlanItem};
-use crate::services::anthropic::AnthropicService;
-use crate::services::google::GoogleService;
-use crate::services::openai::OpenAIService;
+use crate::services::llm::LlmRegistry;
+use crate::services::stream::ArrayItemScanner;
+use crate::services::structured;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Window};
 use uuid::Uuid;
 
+// 스트리밍 중 호출한 창으로 보내는 이벤트 이름
+pub const EVENT_LLM_TOKEN: &str = "llm:token";
+pub const EVENT_CONTENT_PLAN_ITEM: &str = "content-plan:item";
+pub const EVENT_CONTENT_PLAN_REPLACE: &str = "content-plan:replace";
+
+/// Partial text of a streamed LLM reply (`llm:token`)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LlmTokenEvent<'a> {
+    stream_id: &'a str,
+    delta: &'a str,
+}
+
+/// A content plan item whose JSON object has finished streaming (`content-plan:item`)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ContentPlanItemEvent<'a> {
+    stream_id: &'a str,
+    index: usize,
+    item: &'a ContentPlanItem,
+}
+
+/// The final plan when it differs from the streamed items (`content-plan:replace`), e.g. after the reply
+/// had to be repaired. It replaces every `content-plan:item` of the stream.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ContentPlanReplaceEvent<'a> {
+    stream_id: &'a str,
+    items: &'a [ContentPlanItem],
+}
+
+/// Send a streaming event to the window that invoked the command only
+fn emit_to_window<S: Serialize + Clone>(window: &Window, event: &str, payload: S) {
+    if let Err(e) = window.emit_to(window.label(), event, payload) {
+        eprintln!("스트리밍 이벤트 전송 실패 ({}): {}", event, e);
+    }
+}
+
 /// Create a character persona name from keyword
 fn extract_character_name(keyword: &str) -> String {
     let cleaned: String = keyword
@@ -40,20 +83,23 @@ pub async fn create_persona(keyword: String) -> Result<CharacterPersona, String>
     })
 }
 
+/// Streams the plan to the calling window: `llm:token` for raw tokens and `content-plan:item`
+/// for each item as soon as its JSON object is complete. The returned list is authoritative; when it
+/// differs from what was streamed (the reply had to be repaired), `content-plan:replace` carries it too.
 #[tauri::command]
 pub async fn generate_content_plan(
+    app: AppHandle,
+    window: Window,
     request: ContentGenerationRequest,
+    stream_id: Option<String>,
 ) -> Result<Vec<ContentPlanItem>, String> {
     if request.keyword.trim().is_empty() {
         return Err("키워드를 입력해주세요.".to_string());
     }
 
-    let api_key = request
-        .api_key
-        .clone()
-        .ok_or_else(|| "API 키가 설정되지 않았습니다.".to_string())?;
-
-    let provider = request.llm_provider.clone().unwrap_or_else(|| "openai".to_string());
+    let settings = get_settings(app.clone()).await?;
+    let llm = LlmRegistry::from_settings(&settings)
+        .resolve(request.llm_provider.as_deref(), request.api_key.as_deref())?;
     let character_name = extract_character_name(&request.keyword);
     let count = request.count.min(20).max(1);
 
@@ -66,146 +112,142 @@ pub async fn generate_content_plan(
 캐릭터: {} (성분을 의인화한 귀여운 캐릭터)
 형식: {}의 연구일지
 
-각 콘텐츠는 다음 JSON 배열 형식으로 작성하세요:
-[
-  {{
-    "title": "매력적인 제목",
-    "content": "50자 내외의 핵심 내용 (이모지 사용 가능)",
-    "image_concept": "이미지 생성을 위한 상세한 컨셉 설명"
-  }},
-  ...
-]
+다음 JSON 형식으로 작성하세요:
+{{
+  "items": [
+    {{
+      "title": "매력적인 제목",
+      "content": "50자 내외의 핵심 내용 (이모지 사용 가능)",
+      "image_concept": "이미지 생성을 위한 상세한 컨셉 설명"
+    }},
+    ...
+  ]
+}}
 
 주의사항:
 - 과학적 근거에 기반하되 쉽게 설명
 - 임산부/아기에게 안전한 정보 중심
 - 긍정적이고 따뜻한 톤
-- JSON 배열만 출력하세요"#,
+- 근거가 없는 효능은 지어내지 마세요
+- JSON만 출력하세요"#,
         character_name, character_name
     );
 
-    let prompt = format!(
+    let mut prompt = format!(
         "'{}'에 대한 {}개의 인스타그램 캐러셀 콘텐츠를 기획해주세요.\n\n추가 정보:\n{}",
         request.keyword,
         count,
         request.research_data.clone().unwrap_or_default()
     );
 
-    // Call LLM
-    let response = match provider.as_str() {
-        "anthropic" => {
-            let service = AnthropicService::new(&api_key);
-            service.generate_text(&prompt, Some(&system_prompt)).await?
-        }
-        "google" => {
-            let service = GoogleService::new(&api_key);
-            service.generate_text(&prompt, Some(&system_prompt)).await?
-        }
-        _ => {
-            let service = OpenAIService::new(&api_key);
-            service.generate_text(&prompt, Some(&system_prompt)).await?
+    // 성분 조합(함께 쓰면 좋은 성분, 주의할 조합)은 근거가 있는 규칙만 알려줍니다
+    if let Some(pairings) = pairing_context(&app, &request.keyword) {
+        prompt.push_str(&format!(
+            "\n\n성분 조합 정보 (조합을 다룰 때는 이 목록에 있는 내용만 사용하세요):\n{}",
+            pairings
+        ));
+    }
+
+    let stream_id = stream_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+    let mut scanner = ArrayItemScanner::new();
+    let mut streamed: Vec<ContentPlanItem> = Vec::new();
+
+    let mut on_token = |delta: &str| {
+        emit_to_window(&window, EVENT_LLM_TOKEN, LlmTokenEvent { stream_id: &stream_id, delta });
+
+        for raw in scanner.push(delta) {
+            // 필드가 빠진 항목은 최종 검증/복구 결과에서만 전달됩니다
+            let Ok(planned) = serde_json::from_str::<PlannedContent>(&raw) else {
+                continue;
+            };
+            let item = to_content_item(planned, &character_name, streamed.len(), None);
+            emit_to_window(
+                &window,
+                EVENT_CONTENT_PLAN_ITEM,
+                ContentPlanItemEvent {
+                    stream_id: &stream_id,
+                    index: streamed.len(),
+                    item: &item,
+                },
+            );
+            streamed.push(item);
         }
     };
 
-    // Parse response
-    let items = parse_content_plan(&response, &character_name, &request.keyword)?;
-    Ok(items)
-}
+    // Call LLM
+    let plan: PlannedContentList =
+        structured::generate_streaming(llm.as_ref(), &prompt, Some(&system_prompt), &mut on_token).await?;
 
-fn parse_content_plan(
-    response: &str,
-    character_name: &str,
-    keyword: &str,
-) -> Result<Vec<ContentPlanItem>, String> {
-    // Extract JSON array from response
-    let json_str = extract_json_array(response);
-
-    #[derive(serde::Deserialize)]
-    struct LLMContent {
-        title: String,
-        content: String,
-        image_concept: String,
+    let items = to_content_plan(plan, &character_name, &streamed);
+    let unchanged = items.len() == streamed.len() && items.iter().zip(&streamed).all(|(a, b)| a.id == b.id);
+    if !unchanged {
+        emit_to_window(
+            &window,
+            EVENT_CONTENT_PLAN_REPLACE,
+            ContentPlanReplaceEvent { stream_id: &stream_id, items: &items },
+        );
     }
 
-    let parsed: Vec<LLMContent> = serde_json::from_str(&json_str).unwrap_or_else(|_| {
-        // Fallback to default content if parsing fails
-        generate_fallback_content(keyword, 10)
-            .into_iter()
-            .map(|f| LLMContent {
-                title: f.title,
-                content: f.content,
-                image_concept: f.image_concept,
-            })
-            .collect()
-    });
-
-    let items: Vec<ContentPlanItem> = parsed
-        .into_iter()
-        .enumerate()
-        .map(|(i, c)| ContentPlanItem {
-            id: Uuid::new_v4().to_string(),
-            title: c.title,
-            character_name: character_name.to_string(),
-            journal_number: (i + 1) as u32,
-            content: c.content,
-            image_concept: c.image_concept,
-            status: "pending".to_string(),
-        })
-        .collect();
-
     Ok(items)
 }
 
-fn extract_json_array(text: &str) -> String {
-    // Find JSON array in the response
-    if let Some(start) = text.find('[') {
-        if let Some(end) = text.rfind(']') {
-            return text[start..=end].to_string();
-        }
-    }
-    "[]".to_string()
+/// Shape the LLM must return for a content plan (also the source of its JSON schema)
+#[derive(Deserialize, JsonSchema)]
+struct PlannedContentList {
+    #[schemars(length(min = 1, max = 20))]
+    items: Vec<PlannedContent>,
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
-struct FallbackContent {
+#[derive(Deserialize, JsonSchema)]
+struct PlannedContent {
+    #[schemars(length(min = 1))]
     title: String,
+    #[schemars(length(min = 1))]
     content: String,
+    #[schemars(length(min = 1))]
     image_concept: String,
 }
 
-fn generate_fallback_content(keyword: &str, count: usize) -> Vec<FallbackContent> {
-    let topics = vec![
-        ("기초 효능", "피부에 미치는 기본적인 효과를 알아봐요 ✨"),
-        ("보습 메커니즘", "피부 속 수분을 어떻게 지켜줄까요? 💧"),
-        ("장벽 강화", "피부 장벽을 튼튼하게 만드는 비결 🛡️"),
-        ("진정 효과", "민감해진 피부를 달래주는 방법 🌿"),
-        ("아기 피부", "연약한 아기 피부에도 안전해요 👶"),
-        ("임산부 안전성", "임산부도 안심하고 사용할 수 있어요 🤰"),
-        ("EWG 등급", "안전성 등급이 의미하는 것 📊"),
-        ("적정 농도", "얼마나 들어있으면 효과적일까요? 🧪"),
-        ("함께 쓰면 좋은 성분", "시너지를 내는 조합 💪"),
-        ("제형별 특징", "크림, 세럼, 에센스의 차이 🧴"),
-    ];
-
-    topics
+/// Final plan. An item keeps the id already sent in a `content-plan:item` event only if it is the same item;
+/// a repaired reply can put different items at the same positions.
+fn to_content_plan(
+    plan: PlannedContentList,
+    character_name: &str,
+    streamed: &[ContentPlanItem],
+) -> Vec<ContentPlanItem> {
+    plan.items
         .into_iter()
-        .take(count)
-        .map(|(title, content)| FallbackContent {
-            title: format!("{} - {}", keyword, title),
-            content: content.to_string(),
-            image_concept: format!(
-                "귀여운 캐릭터가 연구실에서 {}을(를) 분석하며 {} 포인트를 설명하는 일러스트",
-                keyword, title
-            ),
+        .enumerate()
+        .map(|(i, c)| {
+            let same = streamed
+                .get(i)
+                .filter(|s| s.title == c.title && s.content == c.content && s.image_concept == c.image_concept);
+            to_content_item(c, character_name, i, same.map(|s| s.id.clone()))
         })
         .collect()
 }
 
+fn to_content_item(c: PlannedContent, character_name: &str, index: usize, id: Option<String>) -> ContentPlanItem {
+    ContentPlanItem {
+        id: id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+        title: c.title,
+        character_name: character_name.to_string(),
+        journal_number: (index + 1) as u32,
+        content: c.content,
+        image_concept: c.image_concept,
+        status: "pending".to_string(),
+    }
+}
+
+/// Tokens are streamed to the calling window as `llm:token` events when `stream_id` is given
 #[tauri::command]
 pub async fn translate_to_korean(
+    app: AppHandle,
+    window: Window,
     text: String,
-    api_key: String,
-    provider: String,
+    api_key: Option<String>,
+    provider: Option<String>,
+    stream_id: Option<String>,
 ) -> Result<String, String> {
     if text.trim().is_empty() {
         return Ok(text);
@@ -214,20 +256,48 @@ pub async fn translate_to_korean(
     let system_prompt = "You are a professional translator. Translate the given English text to Korean. Only output the translated text, nothing else. Keep the translation natural and accurate.";
     let prompt = format!("Translate the following text to Korean:\n\n{}", text);
 
-    let response = match provider.as_str() {
-        "anthropic" => {
-            let service = AnthropicService::new(&api_key);
-            service.generate_text(&prompt, Some(system_prompt)).await?
-        }
-        "google" => {
-            let service = GoogleService::new(&api_key);
-            service.generate_text(&prompt, Some(system_prompt)).await?
-        }
-        _ => {
-            let service = OpenAIService::new(&api_key);
-            service.generate_text(&prompt, Some(system_prompt)).await?
+    let settings = get_settings(app).await?;
+    let llm = LlmRegistry::from_settings(&settings).resolve(provider.as_deref(), api_key.as_deref())?;
+    let response = match stream_id {
+        Some(stream_id) => {
+            let mut on_token = |delta: &str| {
+                emit_to_window(&window, EVENT_LLM_TOKEN, LlmTokenEvent { stream_id: &stream_id, delta });
+            };
+            llm.stream_text(&prompt, Some(system_prompt), &mut on_token).await?
         }
+        None => llm.generate_text(&prompt, Some(system_prompt)).await?,
     };
 
     Ok(response.trim().to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn planned(title: &str) -> PlannedContent {
+        PlannedContent {
+            title: title.to_string(),
+            content: format!("{} 내용", title),
+            image_concept: format!("{} 이미지", title),
+        }
+    }
+
+    #[test]
+    fn keeps_streamed_ids_only_for_unchanged_items() {
+        let streamed: Vec<ContentPlanItem> = ["하나", "둘"]
+            .iter()
+            .enumerate()
+            .map(|(i, title)| to_content_item(planned(title), "연구원", i, None))
+            .collect();
+
+        // The repaired reply changed the second item and added a third
+        let repaired = PlannedContentList { items: vec![planned("하나"), planned("새 둘"), planned("셋")] };
+        let plan = to_content_plan(repaired, "연구원", &streamed);
+
+        assert_eq!(plan[0].id, streamed[0].id);
+        assert_ne!(plan[1].id, streamed[1].id);
+        assert_eq!(plan[1].title, "새 둘");
+        assert_eq!(plan.iter().map(|p| p.journal_number).collect::<Vec<_>>(), [1, 2, 3]);
+    }
+}
@@ -1,13 +1,21 @@
-use crate::models::{GeneratedImage, ImageGenerationRequest};
+use crate::jobs::{self, FailedJobItem, ImageJobInput, JobControl, JobItemEvent, JobManager, JobStatus};
+use crate::commands::settings::get_settings;
+use crate::models::project::{ProjectContentGroup, ProjectContentItem};
+use crate::models::{AppSettings, GeneratedImage, ImageGenerationRequest, LayoutSettings};
+use crate::render::{self, SlideText};
 use crate::services::google::GoogleService;
+use crate::services::scheduler;
 use crate::services::openai::OpenAIService;
 use base64::{engine::general_purpose::STANDARD, Engine};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State, Window};
 use uuid::Uuid;
 
 #[tauri::command]
 pub async fn generate_image(
+    app: AppHandle,
     request: ImageGenerationRequest,
     api_key: Option<String>,
     provider: Option<String>,
@@ -21,7 +29,10 @@ pub async fn generate_image(
 
     let api_key = api_key.ok_or_else(|| "API 키가 설정되지 않았습니다.".to_string())?;
     let provider = provider.unwrap_or_else(|| "google".to_string());
-    let google_model = model.unwrap_or_else(|| "imagen-4.0-generate-001".to_string());
+    let models = get_settings(app).await?.model_settings;
+    let google_model = model
+        .or_else(|| models.google.image.clone())
+        .unwrap_or_else(|| "imagen-4.0-generate-001".to_string());
     let img_aspect_ratio = aspect_ratio.unwrap_or_else(|| "1:1".to_string());
 
     // Combine style prompt with image concept for optimal image generation
@@ -63,7 +74,7 @@ pub async fn generate_image(
         }
         _ => {
             // Default to OpenAI DALL-E
-            let service = OpenAIService::new(&api_key);
+            let service = OpenAIService::with_models(&api_key, models.openai);
             service.generate_image(&final_prompt, openai_size).await?
         }
     };
@@ -88,54 +99,184 @@ pub async fn generate_image(
     })
 }
 
+/// Queue a batch of images as a background job and return its id right away.
+/// Progress is reported through the `job:*` events.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn generate_batch_images(
+    app: AppHandle,
+    window: Window,
+    jobs: State<'_, JobManager>,
     requests: Vec<ImageGenerationRequest>,
     api_key: Option<String>,
     provider: Option<String>,
     model: Option<String>,
     aspect_ratio: Option<String>,
     negative_prompt: Option<String>,
-) -> Result<Vec<GeneratedImage>, String> {
-    let mut results = Vec::new();
-    let total = requests.len();
-
-    for (index, request) in requests.into_iter().enumerate() {
-        println!("이미지 생성 중: {}/{}", index + 1, total);
-
-        match generate_image(
-            request,
-            api_key.clone(),
-            provider.clone(),
-            model.clone(),
-            aspect_ratio.clone(),
-            negative_prompt.clone(),
-        ).await {
-            Ok(image) => results.push(image),
+) -> Result<String, String> {
+    if requests.is_empty() {
+        return Err("생성할 이미지가 없습니다.".to_string());
+    }
+
+    let input = ImageJobInput {
+        requests,
+        api_key,
+        provider,
+        model,
+        aspect_ratio,
+        negative_prompt,
+    };
+    start_image_job(app, window, &jobs, input, None)
+}
+
+/// Re-queue failed items of a finished job (all of them, or only `content_ids`) as a new job
+#[tauri::command]
+pub async fn retry_failed_images(
+    app: AppHandle,
+    window: Window,
+    jobs: State<'_, JobManager>,
+    job_id: String,
+    content_ids: Option<Vec<String>>,
+    api_key: Option<String>,
+) -> Result<String, String> {
+    let input = jobs.retry_input(&job_id, content_ids.as_deref(), api_key)?;
+    start_image_job(app, window, &jobs, input, Some(job_id))
+}
+
+fn start_image_job(
+    app: AppHandle,
+    window: Window,
+    jobs: &JobManager,
+    input: ImageJobInput,
+    retry_of: Option<String>,
+) -> Result<String, String> {
+    let (job_id, control) = jobs.create(input.clone(), retry_of)?;
+    tauri::async_runtime::spawn(run_image_job(app, window, job_id.clone(), control, input));
+    Ok(job_id)
+}
+
+async fn run_image_job(app: AppHandle, window: Window, job_id: String, control: Arc<JobControl>, input: ImageJobInput) {
+    let jobs = app.state::<JobManager>();
+    let total = input.requests.len();
+
+    let _ = jobs.update(&job_id, |job| {
+        if job.status == JobStatus::Queued {
+            job.status = JobStatus::Running;
+        }
+    });
+
+    for (index, request) in input.requests.iter().enumerate() {
+        if !control.proceed().await {
+            break;
+        }
+
+        let content_id = request.content_id.clone();
+        let event = |processed: usize| JobItemEvent {
+            job_id: job_id.clone(),
+            content_id: content_id.clone(),
+            index,
+            total,
+            processed,
+            image: None,
+            error: None,
+        };
+        let processed = jobs.get(&job_id).map(|j| j.processed).unwrap_or(index);
+        jobs::emit_event(&window, jobs::EVENT_PROGRESS, event(processed));
+
+        let result = tokio::select! {
+            result = generate_image(
+                app.clone(),
+                request.clone(),
+                input.api_key.clone(),
+                input.provider.clone(),
+                input.model.clone(),
+                input.aspect_ratio.clone(),
+                input.negative_prompt.clone(),
+            ) => result,
+            _ = control.cancelled() => break,
+        };
+
+        match result {
+            Ok(image) => {
+                let snapshot = jobs.update(&job_id, |job| {
+                    job.processed += 1;
+                    job.results.push(image.clone());
+                });
+                let processed = snapshot.map(|j| j.processed).unwrap_or(index + 1);
+                jobs::emit_event(
+                    &window,
+                    jobs::EVENT_ITEM_COMPLETED,
+                    JobItemEvent {
+                        image: Some(image),
+                        ..event(processed)
+                    },
+                );
+            }
             Err(e) => {
                 eprintln!("이미지 {} 생성 실패: {}", index + 1, e);
-                // Continue with next image instead of failing completely
+                let snapshot = jobs.update(&job_id, |job| {
+                    job.processed += 1;
+                    job.failed.push(FailedJobItem {
+                        content_id: content_id.clone(),
+                        error: e.clone(),
+                    });
+                });
+                let processed = snapshot.map(|j| j.processed).unwrap_or(index + 1);
+                jobs::emit_event(
+                    &window,
+                    jobs::EVENT_ITEM_FAILED,
+                    JobItemEvent {
+                        error: Some(e),
+                        ..event(processed)
+                    },
+                );
             }
         }
+    }
 
-        // Add small delay to avoid rate limiting
-        if index < total - 1 {
-            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+    let cancelled = control.is_cancelled();
+    let finished = jobs.finish(&job_id, |job| {
+        if cancelled {
+            // Items that never ran are reported as failed so they can be retried
+            let done: Vec<String> = job
+                .results
+                .iter()
+                .map(|r| r.content_id.clone())
+                .chain(job.failed.iter().map(|f| f.content_id.clone()))
+                .collect();
+            for request in &input.requests {
+                if !done.contains(&request.content_id) {
+                    job.failed.push(FailedJobItem {
+                        content_id: request.content_id.clone(),
+                        error: "작업이 취소되었습니다.".to_string(),
+                    });
+                }
+            }
         }
-    }
 
-    if results.is_empty() {
-        return Err("모든 이미지 생성에 실패했습니다.".to_string());
+        job.status = if cancelled {
+            JobStatus::Cancelled
+        } else if job.results.is_empty() {
+            JobStatus::Failed
+        } else {
+            JobStatus::Completed
+        };
+        job.finished_at = Some(chrono::Utc::now().to_rfc3339());
+    });
+
+    if let Ok(snapshot) = finished {
+        jobs::emit_event(&window, jobs::EVENT_FINISHED, snapshot);
     }
-
-    Ok(results)
 }
 
 #[tauri::command]
 pub async fn download_image(
+    app: AppHandle,
     image_url: String,
     save_path: String,
-    _with_text: Option<bool>,
+    with_text: Option<bool>,
+    content: Option<ProjectContentItem>,
+    layout_template_id: Option<String>,
 ) -> Result<String, String> {
     if image_url.trim().is_empty() {
         return Err("이미지 URL을 입력해주세요.".to_string());
@@ -150,49 +291,28 @@ pub async fn download_image(
         fs::create_dir_all(parent).map_err(|e| format!("디렉토리 생성 실패: {}", e))?;
     }
 
-    // Handle base64 data URLs
-    if image_url.starts_with("data:image/") {
-        let base64_data = image_url
-            .split(',')
-            .nth(1)
-            .ok_or_else(|| "잘못된 base64 이미지 형식".to_string())?;
-
-        let image_bytes = STANDARD
-            .decode(base64_data)
-            .map_err(|e| format!("Base64 디코딩 실패: {}", e))?;
-
-        fs::write(&save_path, image_bytes).map_err(|e| format!("파일 저장 실패: {}", e))?;
+    let image_bytes = fetch_image_bytes(&image_url).await?;
 
+    if with_text.unwrap_or(false) {
+        let content = content.ok_or_else(|| "텍스트를 합성할 콘텐츠가 없습니다.".to_string())?;
+        let settings = get_settings(app).await?;
+        let layout = settings.resolve_layout(layout_template_id.as_deref(), "body");
+        render_slide_file(&settings, layout, &content, image_bytes, PathBuf::from(&save_path)).await?;
         return Ok(save_path);
     }
 
-    // Download from URL
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&image_url)
-        .send()
-        .await
-        .map_err(|e| format!("이미지 다운로드 실패: {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!("이미지 다운로드 실패: HTTP {}", response.status()));
-    }
-
-    let bytes = response
-        .bytes()
-        .await
-        .map_err(|e| format!("이미지 데이터 읽기 실패: {}", e))?;
-
-    fs::write(&save_path, bytes).map_err(|e| format!("파일 저장 실패: {}", e))?;
+    fs::write(&save_path, image_bytes).map_err(|e| format!("파일 저장 실패: {}", e))?;
 
     Ok(save_path)
 }
 
 #[tauri::command]
 pub async fn download_all_images(
+    app: AppHandle,
     images: Vec<GeneratedImage>,
     base_path: String,
     with_text: Option<bool>,
+    content_group: Option<ProjectContentGroup>,
 ) -> Result<Vec<String>, String> {
     if images.is_empty() {
         return Err("다운로드할 이미지가 없습니다.".to_string());
@@ -201,8 +321,14 @@ pub async fn download_all_images(
     // Create base directory
     fs::create_dir_all(&base_path).map_err(|e| format!("디렉토리 생성 실패: {}", e))?;
 
+    // Slide text and layouts come from the group; without it the slides would silently lose their text
+    let content_group = match (with_text.unwrap_or(false), content_group) {
+        (true, None) => return Err("텍스트를 합성하려면 콘텐츠 그룹이 필요합니다.".to_string()),
+        (true, group) => group,
+        (false, _) => None,
+    };
+
     let mut saved_paths = Vec::new();
-    let with_text = with_text.unwrap_or(false);
     let total = images.len();
 
     for (index, image) in images.iter().enumerate() {
@@ -211,7 +337,15 @@ pub async fn download_all_images(
 
         println!("이미지 다운로드 중: {}/{}", index + 1, total);
 
-        match download_image(image.url.clone(), path.clone(), Some(with_text)).await {
+        let result = match content_group.as_ref() {
+            Some(group) => {
+                let image_url = image.local_path.as_deref().unwrap_or(&image.url);
+                download_group_slide(&app, group, &image.content_id, image_url, &path).await
+            }
+            None => download_image(app.clone(), image.url.clone(), path.clone(), Some(false), None, None).await,
+        };
+
+        match result {
             Ok(saved_path) => saved_paths.push(saved_path),
             Err(e) => eprintln!("이미지 {} 다운로드 실패: {}", index + 1, e),
         }
@@ -223,3 +357,90 @@ pub async fn download_all_images(
 
     Ok(saved_paths)
 }
+
+/// Render one item of a content group with the template chosen for it (or its slide role)
+async fn download_group_slide(
+    app: &AppHandle,
+    group: &ProjectContentGroup,
+    content_id: &str,
+    image_url: &str,
+    save_path: &str,
+) -> Result<String, String> {
+    let content = group
+        .contents
+        .iter()
+        .find(|c| c.id == content_id)
+        .ok_or_else(|| "텍스트를 합성할 콘텐츠가 없습니다.".to_string())?;
+
+    if let Some(parent) = Path::new(save_path).parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("디렉토리 생성 실패: {}", e))?;
+    }
+
+    let image_bytes = fetch_image_bytes(image_url).await?;
+    let settings = get_settings(app.clone()).await?;
+    let (template_id, role) = group.layout_for_item(content_id);
+    let layout = settings.resolve_layout(template_id, role);
+    render_slide_file(&settings, layout, content, image_bytes, PathBuf::from(save_path)).await?;
+
+    Ok(save_path.to_string())
+}
+
+/// Composite and save a slide on a blocking thread (decoding/resizing is CPU heavy)
+async fn render_slide_file(
+    settings: &AppSettings,
+    layout: &LayoutSettings,
+    content: &ProjectContentItem,
+    image_bytes: Vec<u8>,
+    save_path: PathBuf,
+) -> Result<(), String> {
+    let preset = settings.size_preset_for(layout);
+    let layout = layout.clone();
+    let text = SlideText::from_content(content);
+
+    tokio::task::spawn_blocking(move || {
+        let image = render::decode_image(&image_bytes)?;
+        let slide = render::render_slide(&layout, &preset, &text, Some(&image))?;
+        render::save_png(&slide, &save_path)
+    })
+    .await
+    .map_err(|e| format!("슬라이드 렌더링 실패: {}", e))?
+}
+
+/// Load image bytes from a base64 data URL, a local file path or a remote URL
+pub(crate) async fn fetch_image_bytes(image_url: &str) -> Result<Vec<u8>, String> {
+    // Handle base64 data URLs
+    if image_url.starts_with("data:image/") {
+        let base64_data = image_url
+            .split(',')
+            .nth(1)
+            .ok_or_else(|| "잘못된 base64 이미지 형식".to_string())?;
+
+        return STANDARD
+            .decode(base64_data)
+            .map_err(|e| format!("Base64 디코딩 실패: {}", e));
+    }
+
+    // Local files (previously downloaded images)
+    if Path::new(image_url).is_file() {
+        return fs::read(image_url).map_err(|e| format!("이미지 파일을 읽을 수 없습니다: {}", e));
+    }
+
+    // Download from URL
+    let client = scheduler::http_client();
+    let response = client
+        .get(image_url)
+        .send()
+        .await
+        .map_err(|e| format!("이미지 다운로드 실패: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("이미지 다운로드 실패: HTTP {}", response.status()));
+    }
+
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("이미지 데이터 읽기 실패: {}", e))?;
+
+    Ok(bytes.to_vec())
+}
@@ -0,0 +1,96 @@
+use crate::models::{Ingredient, IngredientDataset, IngredientImportSummary, IngredientMatch};
+use crate::services::dataset::{self, Dataset};
+use crate::services::ingredients::{self, IngredientDictionary};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+fn get_user_dictionary_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("앱 데이터 디렉토리를 찾을 수 없습니다: {}", e))?;
+    fs::create_dir_all(&app_data_dir).map_err(|e| format!("앱 데이터 디렉토리 생성 실패: {}", e))?;
+    Ok(app_data_dir.join("ingredient_dictionary.json"))
+}
+
+fn load_user_entries(app: &AppHandle) -> Result<IngredientDataset, String> {
+    dataset::read_user_data(&get_user_dictionary_path(app)?)
+}
+
+/// The dictionary with the user's imported entries; also makes keyword matching see them
+pub(crate) fn load_dictionary(app: &AppHandle) -> Result<Arc<IngredientDictionary>, String> {
+    ingredients::active().load(|| load_user_entries(app))
+}
+
+/// Canonical entry for a free-text ingredient name, if the dictionary knows it
+pub(crate) fn resolve_ingredient_name(app: &AppHandle, name: &str) -> Option<Ingredient> {
+    let dictionary = match load_dictionary(app) {
+        Ok(dictionary) => dictionary,
+        Err(e) => {
+            eprintln!("성분 사전 불러오기 실패: {}", e);
+            ingredients::dictionary()
+        }
+    };
+    dictionary.resolve(name).cloned()
+}
+
+/// Fuzzy search by INCI name, Korean name, alias, CAS or EC number
+#[tauri::command]
+pub async fn lookup_ingredient(
+    app: AppHandle,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<IngredientMatch>, String> {
+    let dictionary = load_dictionary(&app)?;
+    Ok(dictionary.lookup(&query, limit.unwrap_or(10)))
+}
+
+/// Ingredients whose name starts with what the user has typed so far
+#[tauri::command]
+pub async fn autocomplete_ingredient(
+    app: AppHandle,
+    prefix: String,
+    limit: Option<usize>,
+) -> Result<Vec<IngredientMatch>, String> {
+    let dictionary = load_dictionary(&app)?;
+    Ok(dictionary.autocomplete(&prefix, limit.unwrap_or(8)))
+}
+
+#[tauri::command]
+pub async fn resolve_ingredient(app: AppHandle, name: String) -> Result<Option<Ingredient>, String> {
+    let dictionary = load_dictionary(&app)?;
+    Ok(dictionary.resolve(&name).cloned())
+}
+
+/// Merge a JSON dataset (same format as the bundled seed data) into the user's dictionary.
+/// Entries with the id or INCI name of an existing ingredient replace it.
+#[tauri::command]
+pub async fn import_ingredient_dataset(app: AppHandle, path: String) -> Result<IngredientImportSummary, String> {
+    let json = fs::read_to_string(&path).map_err(|e| format!("성분 데이터 파일 읽기 실패: {}", e))?;
+    let incoming = IngredientDataset::parse(&json)?;
+    if incoming.ingredients.is_empty() {
+        return Err("가져올 성분이 없습니다.".to_string());
+    }
+
+    let _guard = ingredients::active().lock_store().await;
+    let current = load_dictionary(&app)?;
+    let updated = incoming
+        .ingredients
+        .iter()
+        .filter(|i| ingredients::find_entry(current.entries(), i).is_some())
+        .count();
+    let added = incoming.ingredients.len() - updated;
+
+    let mut user_entries = load_user_entries(&app)?;
+    user_entries.merge(incoming);
+    dataset::write_user_data(&get_user_dictionary_path(&app)?, &user_entries)?;
+    let dictionary = ingredients::active().install(user_entries);
+
+    Ok(IngredientImportSummary {
+        added,
+        updated,
+        total: dictionary.entries().len(),
+    })
+}
@@ -0,0 +1,27 @@
+use crate::jobs::{JobManager, JobSnapshot};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_job(jobs: State<'_, JobManager>, job_id: String) -> Result<JobSnapshot, String> {
+    jobs.get(&job_id)
+}
+
+#[tauri::command]
+pub async fn list_jobs(jobs: State<'_, JobManager>) -> Result<Vec<JobSnapshot>, String> {
+    jobs.list()
+}
+
+#[tauri::command]
+pub async fn pause_job(jobs: State<'_, JobManager>, job_id: String) -> Result<JobSnapshot, String> {
+    jobs.pause(&job_id)
+}
+
+#[tauri::command]
+pub async fn resume_job(jobs: State<'_, JobManager>, job_id: String) -> Result<JobSnapshot, String> {
+    jobs.resume(&job_id)
+}
+
+#[tauri::command]
+pub async fn cancel_job(jobs: State<'_, JobManager>, job_id: String) -> Result<JobSnapshot, String> {
+    jobs.cancel(&job_id)
+}
@@ -1,48 +1,73 @@
+use crate::commands::project::{list_projects, load_project};
+use crate::commands::settings::get_settings;
+use crate::commands::trend::load_trend_store;
 use crate::models::KeywordSuggestion;
-use uuid::Uuid;
+use crate::services::suggest::{SourceDocument, SuggestionEngine};
+use crate::services::trend;
+use chrono::Utc;
+use tauri::AppHandle;
 
-#[tauri::command]
-pub async fn suggest_keywords(keyword: String) -> Result<Vec<KeywordSuggestion>, String> {
-    // TODO: Implement actual API call to Google Search or trends API
-    // For now, return mock data
+const DEFAULT_SUGGESTION_LIMIT: usize = 10;
 
+/// Suggestions from autocomplete, terms co-occurring in PubMed/CrossRef/news results and past
+/// research prompts (of `project_id`, or of every project when omitted)
+#[tauri::command]
+pub async fn suggest_keywords(
+    app: AppHandle,
+    keyword: String,
+    project_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<KeywordSuggestion>, String> {
     if keyword.trim().is_empty() {
         return Ok(vec![]);
     }
 
-    // Mock suggestions based on keyword
-    let suggestions = vec![
-        KeywordSuggestion {
-            id: Uuid::new_v4().to_string(),
-            keyword: format!("{} 효능", keyword),
-            trend: "hot".to_string(),
-            source: "google".to_string(),
-        },
-        KeywordSuggestion {
-            id: Uuid::new_v4().to_string(),
-            keyword: format!("{} 부작용", keyword),
-            trend: "rising".to_string(),
-            source: "google".to_string(),
-        },
-        KeywordSuggestion {
-            id: Uuid::new_v4().to_string(),
-            keyword: format!("{} 화장품", keyword),
-            trend: "stable".to_string(),
-            source: "google".to_string(),
-        },
-        KeywordSuggestion {
-            id: Uuid::new_v4().to_string(),
-            keyword: format!("{} 아기 피부", keyword),
-            trend: "rising".to_string(),
-            source: "google".to_string(),
-        },
-        KeywordSuggestion {
-            id: Uuid::new_v4().to_string(),
-            keyword: format!("{} 임산부", keyword),
-            trend: "hot".to_string(),
-            source: "google".to_string(),
-        },
-    ];
+    let settings = get_settings(app.clone()).await?;
+    let history = past_research_prompts(&app, project_id).await;
+    let engine = SuggestionEngine::with_default_sources(&settings, history);
+
+    let mut suggestions = engine
+        .suggest(&keyword, limit.unwrap_or(DEFAULT_SUGGESTION_LIMIT))
+        .await;
+
+    // 기록된 시계열이 있는 키워드는 그 분류를 우선합니다
+    let store = load_trend_store(&app).unwrap_or_default();
+    let today = Utc::now().date_naive();
+    for suggestion in &mut suggestions {
+        if let Some(history) = store.keywords.get(&trend::trend_key(&suggestion.keyword)) {
+            let (label, growth) = trend::classify(history, today);
+            if growth.is_some() {
+                suggestion.trend = label.to_string();
+            }
+        }
+    }
 
     Ok(suggestions)
 }
+
+/// Research prompts with their creation dates; unreadable projects are skipped
+async fn past_research_prompts(app: &AppHandle, project_id: Option<String>) -> Vec<SourceDocument> {
+    let project_ids = match project_id {
+        Some(id) => vec![id],
+        None => list_projects(app.clone())
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|p| p.id)
+            .collect(),
+    };
+
+    let mut prompts = Vec::new();
+    for id in project_ids {
+        let Ok(project) = load_project(id, app.clone()).await else {
+            continue;
+        };
+        prompts.extend(project.research_items.into_iter().map(|item| SourceDocument {
+            date: chrono::DateTime::parse_from_rfc3339(&item.created_at)
+                .ok()
+                .map(|d| d.date_naive()),
+            text: item.prompt,
+        }));
+    }
+    prompts
+}
@@ -0,0 +1,189 @@
+use crate::commands::ingredient::load_dictionary;
+use crate::commands::project::save_research_item;
+use crate::commands::regulation::load_database;
+use crate::commands::settings::{get_settings, read_image_base64};
+use crate::models::project::ProjectResearchItem;
+use crate::models::{LabelOcrResult, LabelReport};
+use crate::services::compatibility;
+use crate::services::ingredients::IngredientDictionary;
+use crate::services::label::{self, LabelOcrOutput};
+use crate::services::llm::{LlmProvider, LlmRegistry};
+use crate::services::structured;
+use tauri::AppHandle;
+
+/// Check a whole product's ingredient list (전성분) as pasted from its packaging, in Korean or English:
+/// every entry resolved to a dictionary ingredient and flagged for fragrance, allergens, regulatory
+/// limits and pregnancy/infant cautions, plus combinations on the label that are a concern
+#[tauri::command]
+pub async fn analyze_ingredient_label(
+    app: AppHandle,
+    label_text: String,
+    product_name: Option<String>,
+) -> Result<LabelReport, String> {
+    if label::split_label(&label_text).is_empty() {
+        return Err("전성분 목록을 입력해주세요.".to_string());
+    }
+
+    let dictionary = load_dictionary(&app)?;
+    let database = load_database(&app)?;
+    let product_name = product_name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+
+    Ok(label::analyze(&label_text, product_name, &dictionary, &database, &compatibility::engine()))
+}
+
+/// Save a label report to a project as a research item and return the item
+#[tauri::command]
+pub async fn save_label_research(
+    app: AppHandle,
+    project_id: String,
+    report: LabelReport,
+) -> Result<ProjectResearchItem, String> {
+    let research = label::to_research_item(&report);
+    save_research_item(project_id, research.clone(), app).await?;
+    Ok(research)
+}
+
+/// Read the ingredient list from a photo of product packaging with the provider's vision model.
+/// Entries come back in label order with the model's confidence and where they are in the photo.
+#[tauri::command]
+pub async fn extract_ingredient_label(
+    app: AppHandle,
+    image_path: String,
+    api_key: Option<String>,
+    provider: Option<String>,
+) -> Result<LabelOcrResult, String> {
+    if image_path.trim().is_empty() {
+        return Err("이미지 경로가 비어있습니다.".to_string());
+    }
+
+    let settings = get_settings(app.clone()).await?;
+    let llm = LlmRegistry::from_settings(&settings).resolve(provider.as_deref(), api_key.as_deref())?;
+    let (base64_image, mime_type) = read_image_base64(&image_path)?;
+    let image_size = image::image_dimensions(&image_path).ok();
+    let dictionary = load_dictionary(&app)?;
+
+    read_label_photo(llm.as_ref(), &base64_image, mime_type, image_size, &dictionary).await
+}
+
+/// The vision request and its mapping onto label entries, given an already chosen provider
+async fn read_label_photo(
+    llm: &dyn LlmProvider,
+    base64_image: &str,
+    mime_type: &str,
+    image_size: Option<(u32, u32)>,
+    dictionary: &IngredientDictionary,
+) -> Result<LabelOcrResult, String> {
+    let system_prompt = r#"당신은 화장품 포장의 전성분 표시를 읽는 전문가입니다. 사진에서 전성분(Ingredients) 목록만 찾아 표시된 순서대로 한 성분씩 옮겨 적으세요.
+- 각 성분은 인쇄된 그대로 적고, 번역하거나 맞춤법을 고치지 마세요.
+- confidence에는 글자를 얼마나 확실하게 읽었는지 0에서 1 사이로 적으세요. 가려지거나 흐리거나 잘린 글자는 낮게 주세요.
+- bounding_box에는 그 성분이 있는 위치를 이미지 너비와 높이에 대한 비율(0-1)로, 왼쪽 위 모서리(x, y)와 크기(width, height)로 적으세요. 알 수 없으면 null로 두세요.
+- 제품명, 사용법, 주의사항 등 전성분이 아닌 글자는 넣지 마세요.
+- 전성분 목록이 보이지 않으면 entries를 빈 배열로 두세요. 읽을 수 없는 성분을 추측해서 지어내지 마세요."#;
+
+    let user_prompt = "이 제품 사진에서 전성분 목록을 읽어주세요.";
+
+    let output: LabelOcrOutput =
+        structured::analyze_image(llm, base64_image, mime_type, system_prompt, user_prompt).await?;
+    if output.is_empty() {
+        return Err("사진에서 전성분 목록을 찾지 못했습니다.".to_string());
+    }
+
+    Ok(label::read_ocr_output(output, image_size, dictionary))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::ingredients;
+    use crate::services::llm::ScriptedLlm;
+
+    const VALID: &str = include_str!("../../tests/fixtures/label_ocr/valid.json");
+    const PIXEL_BOXES: &str = include_str!("../../tests/fixtures/label_ocr/pixel_boxes.json");
+    const MALFORMED: &str = include_str!("../../tests/fixtures/label_ocr/malformed.txt");
+    const NO_JSON: &str = include_str!("../../tests/fixtures/label_ocr/no_json.txt");
+
+    async fn read(llm: &ScriptedLlm, image_size: Option<(u32, u32)>) -> Result<LabelOcrResult, String> {
+        read_label_photo(llm, "aW1hZ2U=", "image/png", image_size, &ingredients::dictionary()).await
+    }
+
+    #[tokio::test]
+    async fn maps_a_valid_reading() {
+        let llm = ScriptedLlm::new([Ok(VALID)]);
+        let result = read(&llm, None).await.unwrap();
+
+        let names: Vec<&str> = result.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["정제수", "나이아신아마이드", "부틸렌글라이콜"]);
+        assert_eq!(result.label_text, "정제수, 나이아신아마이드, 부틸렌글라이콜");
+        assert_eq!(result.entries[1].text, "나이아신아마이드(2%)");
+        assert_eq!(result.entries[1].ingredient.as_ref().map(|i| i.id.as_str()), Some("niacinamide"));
+        assert_eq!(llm.prompts.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn flags_low_confidence_and_unknown_entries_for_review() {
+        let llm = ScriptedLlm::new([Ok(VALID)]);
+        let result = read(&llm, None).await.unwrap();
+
+        // 정제수는 사전에 없고, 부틸렌글라이콜은 흐리게 읽혔습니다
+        let review: Vec<bool> = result.entries.iter().map(|e| e.needs_review).collect();
+        assert_eq!(review, [true, false, true]);
+        assert_eq!(result.entries[2].confidence, 0.55);
+        assert!(result.entries[2].bounding_box.is_none());
+
+        let b = result.entries[1].bounding_box.unwrap();
+        assert_eq!((b.x, b.y, b.width, b.height), (0.3, 0.2, 0.25, 0.04));
+    }
+
+    #[tokio::test]
+    async fn turns_pixel_boxes_into_fractions_of_the_image() {
+        let llm = ScriptedLlm::new([Ok(PIXEL_BOXES)]);
+        let result = read(&llm, Some((1000, 500))).await.unwrap();
+
+        // 한 항목에 두 성분이 읽히면 나눠서 같은 위치와 신뢰도를 씁니다
+        let (glycerin, niacinamide) = (&result.entries[0], &result.entries[1]);
+        assert_eq!((glycerin.name.as_str(), niacinamide.name.as_str()), ("Glycerin", "Niacinamide"));
+        assert_eq!(glycerin.confidence, niacinamide.confidence);
+        let b = glycerin.bounding_box.unwrap();
+        assert_eq!((b.x, b.y, b.width, b.height), (0.1, 0.1, 0.3, 0.08));
+        assert_eq!(niacinamide.bounding_box.map(|b| b.x), Some(0.1));
+
+        // 오른쪽 끝을 넘는 상자는 이미지 안으로 자르고, 너비가 0인 상자는 버립니다
+        let clipped = result.entries[2].bounding_box.unwrap();
+        assert_eq!((clipped.x, clipped.width), (0.9, 1.0 - 0.9));
+        assert!(result.entries[3].bounding_box.is_none());
+        assert!(result.entries[3].ingredient.is_none() && result.entries[3].needs_review);
+    }
+
+    #[tokio::test]
+    async fn drops_pixel_boxes_when_the_image_size_is_unknown() {
+        let llm = ScriptedLlm::new([Ok(PIXEL_BOXES)]);
+        let result = read(&llm, None).await.unwrap();
+        assert!(result.entries.iter().all(|e| e.bounding_box.is_none()));
+    }
+
+    #[tokio::test]
+    async fn repairs_a_malformed_reading() {
+        let llm = ScriptedLlm::new([Ok(MALFORMED), Ok(VALID)]);
+        let result = read(&llm, None).await.unwrap();
+        assert_eq!(result.entries.len(), 3);
+
+        let prompts = llm.prompts.lock().unwrap();
+        assert_eq!(prompts.len(), 2);
+        assert!(prompts[1].contains("$.entries[0].confidence"), "{}", prompts[1]);
+        assert!(prompts[1].contains("\"confidence\": \"high\""));
+    }
+
+    #[tokio::test]
+    async fn fails_when_the_repair_is_still_malformed() {
+        let llm = ScriptedLlm::new([Ok(MALFORMED), Ok(NO_JSON)]);
+        let error = read(&llm, None).await.unwrap_err();
+        assert!(error.starts_with("AI 응답 형식이 올바르지 않습니다"), "{error}");
+        assert_eq!(llm.prompts.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn reports_a_photo_without_an_ingredient_list() {
+        let llm = ScriptedLlm::new([Ok(r#"{"entries": []}"#)]);
+        assert_eq!(read(&llm, None).await.unwrap_err(), "사진에서 전성분 목록을 찾지 못했습니다.");
+    }
+}
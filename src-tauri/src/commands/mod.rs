@@ -1,6 +1,12 @@
+pub mod compatibility;
 pub mod content;
 pub mod image;
+pub mod ingredient;
+pub mod job;
 pub mod keyword;
+pub mod label;
 pub mod project;
+pub mod regulation;
 pub mod research;
 pub mod settings;
+pub mod trend;
@@ -21,7 +21,7 @@ fn get_projects_base_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, Strin
 }
 
 /// Get project directory path
-fn get_project_dir(app_handle: &tauri::AppHandle, project_id: &str) -> Result<PathBuf, String> {
+pub(crate) fn get_project_dir(app_handle: &tauri::AppHandle, project_id: &str) -> Result<PathBuf, String> {
     let base_dir = get_projects_base_dir(app_handle)?;
     Ok(base_dir.join(project_id))
 }
@@ -227,6 +227,43 @@ pub async fn save_content_group(
     Ok(())
 }
 
+/// Record which layout template a content item uses (None clears it back to the slide-role default)
+#[tauri::command]
+pub async fn assign_content_layout(
+    project_id: String,
+    group_id: String,
+    content_id: String,
+    template_id: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<ProjectContentGroup, String> {
+    let mut project = load_project(project_id.clone(), app_handle.clone()).await?;
+
+    let group = project
+        .content_groups
+        .iter_mut()
+        .find(|g| g.id == group_id)
+        .ok_or_else(|| "콘텐츠 그룹을 찾을 수 없습니다".to_string())?;
+
+    if !group.contents.iter().any(|c| c.id == content_id) {
+        return Err("콘텐츠를 찾을 수 없습니다".to_string());
+    }
+
+    match template_id {
+        Some(id) => {
+            group.item_layout_templates.insert(content_id, id);
+        }
+        None => {
+            group.item_layout_templates.remove(&content_id);
+        }
+    }
+
+    let group = group.clone();
+    project.updated_at = Utc::now().to_rfc3339();
+    save_project(project, app_handle).await?;
+
+    Ok(group)
+}
+
 #[tauri::command]
 pub async fn get_project_images_dir(
     project_id: String,
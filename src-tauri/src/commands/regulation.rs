@@ -0,0 +1,80 @@
+use crate::commands::ingredient::{load_dictionary, resolve_ingredient_name};
+use crate::models::{Ingredient, RegulatoryDataset, RegulatoryFact, RegulatoryImportSummary, RegulatoryReport};
+use crate::services::dataset::{self, Dataset};
+use crate::services::regulation::{self, RegulatoryDatabase};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+fn get_user_data_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("앱 데이터 디렉토리를 찾을 수 없습니다: {}", e))?;
+    fs::create_dir_all(&app_data_dir).map_err(|e| format!("앱 데이터 디렉토리 생성 실패: {}", e))?;
+    Ok(app_data_dir.join("regulatory_data.json"))
+}
+
+fn load_user_data(app: &AppHandle) -> Result<RegulatoryDataset, String> {
+    dataset::read_user_data(&get_user_data_path(app)?)
+}
+
+pub(crate) fn load_database(app: &AppHandle) -> Result<Arc<RegulatoryDatabase>, String> {
+    regulation::active().load(|| load_user_data(app))
+}
+
+/// Sourced regulatory facts for a dictionary ingredient
+pub(crate) fn regulatory_facts(app: &AppHandle, ingredient: &Ingredient) -> Vec<RegulatoryFact> {
+    match load_database(app) {
+        Ok(database) => database.facts(&ingredient.id),
+        Err(e) => {
+            eprintln!("규제 데이터 불러오기 실패: {}", e);
+            regulation::database().facts(&ingredient.id)
+        }
+    }
+}
+
+/// MFDS, EU and pregnancy/infant list entries for an ingredient, each with its source
+#[tauri::command]
+pub async fn get_regulatory_status(app: AppHandle, ingredient_name: String) -> Result<RegulatoryReport, String> {
+    let ingredient = resolve_ingredient_name(&app, &ingredient_name);
+    let facts = match &ingredient {
+        Some(ingredient) => load_database(&app)?.facts(&ingredient.id),
+        None => vec![],
+    };
+
+    Ok(RegulatoryReport { ingredient, facts })
+}
+
+/// Merge a JSON dataset (same format as the bundled data) into the user's regulatory data.
+/// Its rules replace existing rules of the same ingredient and list.
+#[tauri::command]
+pub async fn import_regulatory_dataset(app: AppHandle, path: String) -> Result<RegulatoryImportSummary, String> {
+    let json = fs::read_to_string(&path).map_err(|e| format!("규제 데이터 파일 읽기 실패: {}", e))?;
+    let mut incoming = regulation::parse_dataset(&json)?;
+    if incoming.rules.is_empty() && incoming.sources.is_empty() {
+        return Err("가져올 규제 정보가 없습니다.".to_string());
+    }
+
+    let _guard = regulation::active().lock_store().await;
+    let mut user_data = load_user_data(&app)?;
+
+    // 기본 데이터나 이전에 가져온 출처는 다시 정의하지 않아도 됩니다
+    let seed_sources = dataset::seed::<RegulatoryDataset>().sources;
+    let known_sources: Vec<_> = seed_sources.into_iter().chain(user_data.sources.clone()).collect();
+    dataset::check_sources(&incoming.rules, &incoming.sources, &known_sources)?;
+    let unmatched_ingredients = regulation::link_ingredients(&mut incoming, &*load_dictionary(&app)?);
+
+    let summary = RegulatoryImportSummary {
+        sources: incoming.sources.len(),
+        rules: incoming.rules.len(),
+        unmatched_ingredients,
+    };
+
+    user_data.merge(incoming);
+    dataset::write_user_data(&get_user_data_path(&app)?, &user_data)?;
+    regulation::active().install(user_data);
+
+    Ok(summary)
+}
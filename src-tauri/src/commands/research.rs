@@ -1,45 +1,93 @@
-use crate::models::{IngredientAnalysis, PaperResult};
-use crate::services::anthropic::AnthropicService;
+use crate::commands::ingredient::resolve_ingredient_name;
+use crate::commands::regulation::regulatory_facts;
+use crate::commands::settings::get_settings;
+use crate::commands::trend::record_news_results;
+use crate::models::{
+    BenefitEvidence, EvidenceAssessment, Ingredient, IngredientAnalysis, LiteratureSearchResult, NewsSearchFilters, PaperResult,
+    PaperSearchFilters,
+};
+use crate::services::citation::{self, ArticleIds, CitationService};
 use crate::services::crossref::CrossRefService;
+use crate::services::evidence;
 use crate::services::google::GoogleService;
+use crate::services::ingredients;
+use crate::services::llm::LlmRegistry;
 use crate::services::news::NewsService;
-use crate::services::openai::OpenAIService;
-use crate::services::pubmed::PubMedService;
+use crate::services::literature::{self, LiteratureSearch};
+use crate::services::pubmed::{PubMedQuery, PubMedService};
+use crate::services::structured;
+use chrono::{DateTime, FixedOffset};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+use tauri::AppHandle;
 
+/// PubMed search for an ingredient; `filters` narrow it by year, article type, population,
+/// language and order, and page through results with `offset`
 #[tauri::command]
-pub async fn search_papers(keyword: String, limit: Option<u32>) -> Result<Vec<PaperResult>, String> {
+pub async fn search_papers(
+    app: AppHandle,
+    keyword: String,
+    limit: Option<u32>,
+    filters: Option<PaperSearchFilters>,
+) -> Result<Vec<PaperResult>, String> {
     if keyword.trim().is_empty() {
         return Ok(vec![]);
     }
 
     let limit = limit.unwrap_or(10);
-    let pubmed_service = PubMedService::new();
+    let settings = get_settings(app.clone()).await?;
+    let pubmed_service = PubMedService::from_settings(&settings);
 
-    // Search for ingredient-related papers
-    let papers = pubmed_service.search_ingredient(&keyword, limit).await?;
+    // Search for ingredient-related papers, under all English names when the dictionary knows it
+    let query = PubMedQuery::ingredient_names(&search_names(&app, &keyword))
+        .page(0, limit)
+        .filters(&filters.unwrap_or_default());
+    let papers = pubmed_service.search_query(&query).await?;
 
     // Convert to PaperResult format
-    let results: Vec<PaperResult> = papers
+    let mut results: Vec<PaperResult> = papers
         .into_iter()
-        .map(|p| PaperResult {
-            id: Uuid::new_v4().to_string(),
-            title: p.title,
-            authors: p.authors,
-            abstract_text: p.abstract_text,
-            publication_date: p.year,
-            source: "PubMed".to_string(),
-            citation_count: None,
-            doi: Some(format!("https://pubmed.ncbi.nlm.nih.gov/{}/", p.pmid)),
-        })
+        .map(literature::from_pubmed)
         .collect();
 
+    // 인용 수와 무료 원문 링크를 채웁니다
+    literature::enrich_papers(&CitationService::from_settings(&settings), &mut results).await;
+
     Ok(results)
 }
 
+/// PubMed, CrossRef and Google CSE (when a key and `google_cx` are available) searched at once,
+/// de-duplicated and ranked by relevance, evidence strength and recency. `sources` picks a
+/// subset ("pubmed", "crossref", "web"); sources that fail are listed in `failures`.
+#[tauri::command]
+pub async fn search_literature(
+    app: AppHandle,
+    keyword: String,
+    limit: Option<u32>,
+    sources: Option<Vec<String>>,
+    filters: Option<PaperSearchFilters>,
+    google_cx: Option<String>,
+) -> Result<LiteratureSearchResult, String> {
+    if keyword.trim().is_empty() {
+        return Ok(LiteratureSearchResult {
+            records: vec![],
+            searched: vec![],
+            failures: vec![],
+        });
+    }
+
+    let settings = get_settings(app).await?;
+    let search = LiteratureSearch::from_settings(&settings, google_cx.as_deref());
+    let sources = sources.unwrap_or_else(|| search.enabled_sources());
+
+    Ok(search
+        .search(&keyword, limit.unwrap_or(10), &filters.unwrap_or_default(), &sources)
+        .await)
+}
+
 #[tauri::command]
 pub async fn analyze_ingredient(
+    app: AppHandle,
     ingredient_name: String,
     api_key: Option<String>,
     llm_provider: Option<String>,
@@ -48,20 +96,32 @@ pub async fn analyze_ingredient(
         return Err("성분명을 입력해주세요.".to_string());
     }
 
-    let api_key = api_key.ok_or_else(|| "API 키가 설정되지 않았습니다.".to_string())?;
-    let provider = llm_provider.unwrap_or_else(|| "openai".to_string());
+    let settings = get_settings(app.clone()).await?;
+    let llm = LlmRegistry::from_settings(&settings).resolve(llm_provider.as_deref(), api_key.as_deref())?;
+    let ingredient = resolve_ingredient_name(&app, &ingredient_name);
 
     // First, search for papers about this ingredient
-    let pubmed_service = PubMedService::new();
+    let pubmed_service = PubMedService::from_settings(&settings);
     let papers = pubmed_service
-        .search_ingredient(&ingredient_name, 5)
+        .search_ingredient(&search_names(&app, &ingredient_name), 5)
         .await
         .unwrap_or_default();
 
-    // Prepare paper summaries for LLM analysis
+    let papers: Vec<PaperResult> = papers.into_iter().map(literature::from_pubmed).collect();
+
+    // Prepare paper summaries for LLM analysis, numbered so benefits can cite them
     let paper_summaries: Vec<String> = papers
         .iter()
-        .map(|p| format!("제목: {}\n초록: {}", p.title, p.abstract_text))
+        .enumerate()
+        .map(|(i, p)| {
+            format!(
+                "[{}] 제목: {}\n근거 수준: {}\n초록: {}",
+                i + 1,
+                p.title,
+                evidence_summary(p.evidence.as_ref()),
+                p.abstract_text
+            )
+        })
         .collect();
 
     let papers_context = if paper_summaries.is_empty() {
@@ -75,95 +135,145 @@ pub async fn analyze_ingredient(
 응답은 반드시 다음 JSON 형식으로만 작성하세요:
 {
   "ewg_score": 1-10 사이 숫자 또는 null,
-  "benefits": ["효능1", "효능2", ...],
+  "benefits": [{"benefit": "효능1", "papers": [근거 논문 번호, ...]}, ...],
   "cautions": ["주의사항1", "주의사항2", ...],
   "recommended_concentration": "권장 농도 (예: 1-5%)" 또는 null
 }
+각 효능의 papers에는 그 효능을 직접 뒷받침하는 논문 번호만 넣고, 없으면 빈 배열로 두세요.
 다른 설명 없이 JSON만 응답해주세요."#;
 
     let prompt = format!(
         "성분명: {}\n\n관련 논문 정보:\n{}\n\n위 정보를 바탕으로 이 성분을 분석해주세요.",
-        ingredient_name, papers_context
+        ingredient_label(&ingredient_name, ingredient.as_ref()),
+        papers_context
     );
 
     // Call appropriate LLM
-    let response = match provider.as_str() {
-        "anthropic" => {
-            let service = AnthropicService::new(&api_key);
-            service.generate_text(&prompt, Some(system_prompt)).await?
-        }
-        "google" => {
-            let service = GoogleService::new(&api_key);
-            service.generate_text(&prompt, Some(system_prompt)).await?
-        }
-        _ => {
-            let service = OpenAIService::new(&api_key);
-            service.generate_text(&prompt, Some(system_prompt)).await?
-        }
-    };
+    let response: IngredientAnalysisOutput =
+        structured::generate(llm.as_ref(), &prompt, Some(system_prompt)).await?;
+
+    let facts = ingredient
+        .as_ref()
+        .map(|i| regulatory_facts(&app, i))
+        .unwrap_or_default();
 
-    // Parse LLM response
-    let analysis = parse_ingredient_analysis(&ingredient_name, &response, papers)?;
-    Ok(analysis)
+    Ok(IngredientAnalysis {
+        regulatory_facts: facts,
+        ..to_ingredient_analysis(&ingredient_name, ingredient, response, papers)
+    })
 }
 
-fn parse_ingredient_analysis(
-    ingredient_name: &str,
-    response: &str,
-    papers: Vec<crate::services::pubmed::PaperInfo>,
-) -> Result<IngredientAnalysis, String> {
-    // Try to parse JSON from response
-    let json_str = extract_json(response);
-
-    #[derive(serde::Deserialize)]
-    struct LLMResponse {
-        ewg_score: Option<u8>,
-        benefits: Option<Vec<String>>,
-        cautions: Option<Vec<String>>,
-        recommended_concentration: Option<String>,
+/// English names to search PubMed with: the dictionary's names, else the input as typed
+fn search_names(app: &AppHandle, name: &str) -> Vec<String> {
+    resolve_ingredient_name(app, name)
+        .map(|ingredient| ingredients::search_names(&ingredient))
+        .filter(|names| !names.is_empty())
+        .unwrap_or_else(|| vec![name.trim().to_string()])
+}
+
+/// "나이아신아마이드 (INCI: Niacinamide, CAS: 98-92-0)" for the analysis prompt
+fn ingredient_label(input: &str, ingredient: Option<&Ingredient>) -> String {
+    let Some(ingredient) = ingredient else {
+        return input.to_string();
+    };
+    let mut details = vec![format!("INCI: {}", ingredient.inci_name)];
+    if !ingredient.cas_numbers.is_empty() {
+        details.push(format!("CAS: {}", ingredient.cas_numbers.join(", ")));
     }
+    let name = if ingredient.korean_name.is_empty() { input } else { &ingredient.korean_name };
+    format!("{} ({})", name, details.join(", "))
+}
 
-    let parsed: LLMResponse = serde_json::from_str(&json_str).unwrap_or_else(|_| LLMResponse {
-        ewg_score: None,
-        benefits: Some(vec!["피부 보습 효과".to_string()]),
-        cautions: Some(vec!["특별한 주의사항 없음".to_string()]),
-        recommended_concentration: None,
-    });
+/// Shape the LLM must return for an ingredient analysis (also the source of its JSON schema)
+#[derive(Deserialize, JsonSchema)]
+struct IngredientAnalysisOutput {
+    #[schemars(range(min = 1, max = 10))]
+    ewg_score: Option<u8>,
+    benefits: Vec<BenefitClaimOutput>,
+    cautions: Vec<String>,
+    recommended_concentration: Option<String>,
+}
 
-    let related_papers: Vec<PaperResult> = papers
-        .into_iter()
-        .take(3)
-        .map(|p| PaperResult {
-            id: Uuid::new_v4().to_string(),
-            title: p.title,
-            authors: p.authors,
-            abstract_text: p.abstract_text,
-            publication_date: p.year,
-            source: "PubMed".to_string(),
-            citation_count: None,
-            doi: Some(format!("https://pubmed.ncbi.nlm.nih.gov/{}/", p.pmid)),
+#[derive(Deserialize, JsonSchema)]
+struct BenefitClaimOutput {
+    benefit: String,
+    /// 1-based numbers of the papers in the prompt that support the benefit
+    #[serde(default)]
+    papers: Vec<usize>,
+}
+
+/// "B (clinical_trial, human, n=32, 2021)" for the analysis prompt
+fn evidence_summary(evidence: Option<&EvidenceAssessment>) -> String {
+    let Some(e) = evidence else {
+        return "미상".to_string();
+    };
+    let mut details = vec![e.study_design.clone(), e.subject.clone()];
+    if let Some(n) = e.sample_size {
+        details.push(format!("n={}", n));
+    }
+    if let Some(year) = e.year {
+        details.push(year.to_string());
+    }
+    format!("{} ({})", e.grade, details.join(", "))
+}
+
+fn to_ingredient_analysis(
+    ingredient_name: &str,
+    ingredient: Option<Ingredient>,
+    parsed: IngredientAnalysisOutput,
+    papers: Vec<PaperResult>,
+) -> IngredientAnalysis {
+    let grade_of = |p: &PaperResult| p.evidence.as_ref().map(|e| e.grade.clone());
+
+    let benefit_evidence: Vec<BenefitEvidence> = parsed
+        .benefits
+        .iter()
+        .map(|claim| {
+            // 범위를 벗어난 번호는 무시합니다
+            let cited: Vec<&PaperResult> = claim
+                .papers
+                .iter()
+                .filter_map(|n| n.checked_sub(1).and_then(|i| papers.get(i)))
+                .collect();
+            let grades: Vec<String> = cited.iter().filter_map(|p| grade_of(p)).collect();
+            let grade = evidence::best_grade(grades.iter().map(String::as_str)).map(str::to_string);
+
+            BenefitEvidence {
+                benefit: claim.benefit.clone(),
+                paper_ids: cited.iter().map(|p| p.id.clone()).collect(),
+                weak_evidence: grade.as_deref().is_none_or(evidence::is_weak_grade),
+                grade,
+            }
         })
         .collect();
 
-    Ok(IngredientAnalysis {
-        ingredient_name: ingredient_name.to_string(),
-        korean_name: ingredient_name.to_string(),
+    // Papers cited for a benefit, then the strongest of the rest, at least three in total
+    let cited_ids: Vec<&String> = benefit_evidence.iter().flat_map(|b| &b.paper_ids).collect();
+    let (mut related_papers, mut rest): (Vec<PaperResult>, Vec<PaperResult>) =
+        papers.into_iter().partition(|p| cited_ids.contains(&&p.id));
+    rest.sort_by_key(|p| grade_of(p).unwrap_or_else(|| "D".to_string()));
+    let missing = 3usize.saturating_sub(related_papers.len());
+    related_papers.extend(rest.into_iter().take(missing));
+
+    IngredientAnalysis {
+        ingredient_name: ingredient
+            .as_ref()
+            .map(|i| i.inci_name.clone())
+            .unwrap_or_else(|| ingredient_name.to_string()),
+        korean_name: ingredient
+            .as_ref()
+            .map(|i| i.korean_name.clone())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| ingredient_name.to_string()),
         ewg_score: parsed.ewg_score,
-        benefits: parsed.benefits.unwrap_or_default(),
-        cautions: parsed.cautions.unwrap_or_default(),
+        benefits: parsed.benefits.into_iter().map(|b| b.benefit).collect(),
+        cautions: parsed.cautions,
         recommended_concentration: parsed.recommended_concentration,
         related_papers,
-    })
-}
-
-fn extract_json(text: &str) -> String {
-    // Find JSON in the response
-    if let Some(start) = text.find('{') {
-        if let Some(end) = text.rfind('}') {
-            return text[start..=end].to_string();
-        }
+        benefit_evidence,
+        ingredient,
+        regulatory_facts: vec![],
     }
-    text.to_string()
 }
 
 // ============================================
@@ -213,10 +323,19 @@ pub struct ConferenceSearchResult {
     pub source: String,
     pub doi: Option<String>,
     pub url: Option<String>,
+    #[serde(default)]
+    pub pmid: Option<String>,
+    #[serde(default)]
+    pub pmcid: Option<String>,
+    #[serde(default)]
+    pub citation_count: Option<u32>,
+    #[serde(default)]
+    pub open_access_url: Option<String>,
 }
 
 #[tauri::command]
 pub async fn search_conferences(
+    app: AppHandle,
     keyword: String,
     limit: Option<u32>,
 ) -> Result<Vec<ConferenceSearchResult>, String> {
@@ -224,13 +343,28 @@ pub async fn search_conferences(
         return Ok(vec![]);
     }
 
+    let settings = get_settings(app).await?;
     let limit = limit.unwrap_or(10) as usize;
-    let service = CrossRefService::new();
+    let service = match &settings.source_endpoints.crossref {
+        Some(url) => CrossRefService::with_base_url(url),
+        None => CrossRefService::new(),
+    };
     let results = service.search(&keyword, limit).await?;
 
+    // CrossRef 결과는 DOI만 있으므로 PMID/PMCID는 ID 변환기로 찾습니다
+    let ids: Vec<ArticleIds> = results
+        .iter()
+        .map(|r| ArticleIds {
+            doi: r.doi.clone(),
+            ..ArticleIds::default()
+        })
+        .collect();
+    let (enrichments, _) = CitationService::from_settings(&settings).enrich(ids).await;
+
     Ok(results
         .into_iter()
-        .map(|r| ConferenceSearchResult {
+        .zip(enrichments)
+        .map(|(r, found)| ConferenceSearchResult {
             id: r.id,
             title: r.title,
             authors: r.authors,
@@ -238,10 +372,46 @@ pub async fn search_conferences(
             source: r.source,
             doi: r.doi,
             url: r.url,
+            pmid: found.ids.pmid,
+            pmcid: found.ids.pmcid,
+            citation_count: found.citation_count,
+            open_access_url: found.open_access_url,
         })
         .collect())
 }
 
+/// One list from `search_papers` and `search_conferences` results, with records that share a
+/// DOI, PMID or PMCID merged (PubMed metadata wins, CrossRef fills the gaps)
+#[tauri::command]
+pub async fn merge_paper_results(
+    papers: Vec<PaperResult>,
+    conferences: Vec<ConferenceSearchResult>,
+) -> Result<Vec<PaperResult>, String> {
+    let conferences = conferences.into_iter().map(|c| PaperResult {
+        id: c.id,
+        title: c.title,
+        authors: c.authors,
+        abstract_text: String::new(),
+        publication_date: c.published_date,
+        source: "CrossRef".to_string(),
+        citation_count: c.citation_count,
+        doi: c.doi,
+        pmid: c.pmid,
+        pmcid: c.pmcid,
+        url: c.url,
+        open_access_url: c.open_access_url,
+        journal: Some(c.source).filter(|s| s != "Unknown"),
+        abstract_sections: vec![],
+        mesh_terms: vec![],
+        keywords: vec![],
+        publication_types: vec![],
+        country: None,
+        evidence: None,
+    });
+
+    Ok(citation::merge_duplicates(papers.into_iter().chain(conferences).collect()))
+}
+
 // ============================================
 // News Search (RSS Feeds)
 // ============================================
@@ -252,17 +422,41 @@ pub struct NewsSearchResult {
     pub description: String,
     pub link: String,
     pub pub_date: String,
+    pub published_at: Option<DateTime<FixedOffset>>,
     pub source: String,
+    pub category: String,
+    pub relevance: f64,
+    pub other_sources: Vec<String>,
 }
 
+/// Search the enabled feeds of the registry, optionally only those of the given categories
+/// and within a date window
 #[tauri::command]
-pub async fn search_news(keyword: String) -> Result<Vec<NewsSearchResult>, String> {
+pub async fn search_news(
+    app: AppHandle,
+    keyword: String,
+    categories: Option<Vec<String>>,
+    filters: Option<NewsSearchFilters>,
+) -> Result<Vec<NewsSearchResult>, String> {
     if keyword.trim().is_empty() {
         return Ok(vec![]);
     }
 
-    let service = NewsService::new();
-    let results = service.search_all(&keyword).await?;
+    // 사전에 있는 성분이면 표준 성분명으로 찾아 INCI명과 별칭도 함께 매칭되게 합니다
+    let query = resolve_ingredient_name(&app, &keyword)
+        .map(|i| if i.korean_name.is_empty() { i.inci_name } else { i.korean_name })
+        .unwrap_or_else(|| keyword.clone());
+
+    let settings = get_settings(app.clone()).await?;
+    let service = NewsService::from_settings(&settings);
+    let results = service
+        .search(&query, &categories.unwrap_or_default(), &filters.unwrap_or_default())
+        .await?;
+
+    // 검색할 때마다 키워드 트렌드 기록에도 반영합니다
+    if let Err(e) = record_news_results(&app, &keyword, &results).await {
+        eprintln!("뉴스 트렌드 기록 실패: {}", e);
+    }
 
     Ok(results
         .into_iter()
@@ -271,7 +465,11 @@ pub async fn search_news(keyword: String) -> Result<Vec<NewsSearchResult>, Strin
             description: r.description,
             link: r.link,
             pub_date: r.pub_date,
+            published_at: r.published_at,
             source: r.source,
+            category: r.category,
+            relevance: r.relevance,
+            other_sources: r.other_sources,
         })
         .collect())
 }
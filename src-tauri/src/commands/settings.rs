@@ -1,10 +1,19 @@
-use crate::models::{ApiKeys, AppSettings, ImagePrompt, LayoutSettings};
+use crate::models::{
+    ApiKeys, AppSettings, ImagePrompt, LayoutSettings, LayoutTemplate, ModelInfo, ModelSettings, NcbiSettings,
+    NewsFeed, NewsFeedTest, TextLayoutPreview, TextLayoutRequest,
+};
+use crate::render::{self, fonts};
+use crate::render::text::{self, FontStack, TextAlign, TextStyle, VerticalAlign};
 use crate::services::anthropic::AnthropicService;
 use crate::services::google::GoogleService;
 use crate::services::openai::OpenAIService;
+use crate::services::llm::LlmRegistry;
+use crate::services::news::{FeedFormat, NewsService};
+use crate::services::scheduler;
 use base64::{engine::general_purpose::STANDARD, Engine};
 use std::collections::HashSet;
 use std::fs;
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
 use tauri::{AppHandle, Manager};
 
@@ -195,8 +204,11 @@ pub async fn generate_preview_image(
     // Use provided aspect ratio or default to 3:4 (Instagram style)
     let img_aspect_ratio = aspect_ratio.unwrap_or_else(|| "3:4".to_string());
 
-    // Get model or use default (Imagen 4 Standard)
-    let google_model = model.unwrap_or_else(|| "imagen-4.0-generate-001".to_string());
+    // Get model or use the configured one (Imagen 4 Standard by default)
+    let models = get_settings(app.clone()).await?.model_settings;
+    let google_model = model
+        .or_else(|| models.google.image.clone())
+        .unwrap_or_else(|| "imagen-4.0-generate-001".to_string());
 
     // OpenAI size string based on aspect ratio
     let openai_size = match img_aspect_ratio.as_str() {
@@ -209,7 +221,7 @@ pub async fn generate_preview_image(
     // Generate image based on provider
     let image_data = match provider.as_str() {
         "openai" => {
-            let service = OpenAIService::new(&api_key);
+            let service = OpenAIService::with_models(&api_key, models.openai);
             service.generate_image(&prompt, openai_size).await?
         }
         _ => {
@@ -243,7 +255,7 @@ pub async fn generate_preview_image(
             .map_err(|e| format!("이미지 저장 실패: {}", e))?;
     } else {
         // URL - download the image
-        let client = reqwest::Client::new();
+        let client = scheduler::http_client();
         let response = client
             .get(&image_data)
             .send()
@@ -296,27 +308,234 @@ pub async fn delete_image_prompt(app: AppHandle, prompt_id: String) -> Result<()
 
 #[tauri::command]
 pub async fn save_layout_settings(app: AppHandle, layout: LayoutSettings) -> Result<(), String> {
+    render::validate_layout(&layout)?;
+
     let mut settings = get_settings(app.clone()).await?;
     settings.layout_settings = layout;
     save_settings(app, settings).await
 }
 
 #[tauri::command]
-pub async fn generate_prompt_from_image(
-    image_path: String,
-    api_key: String,
-    provider: String,
-) -> Result<String, String> {
-    if image_path.trim().is_empty() {
-        return Err("이미지 경로가 비어있습니다.".to_string());
+pub async fn list_layout_templates(app: AppHandle) -> Result<Vec<LayoutTemplate>, String> {
+    let settings = get_settings(app).await?;
+    Ok(settings.layout_templates)
+}
+
+#[tauri::command]
+pub async fn save_layout_template(app: AppHandle, template: LayoutTemplate) -> Result<(), String> {
+    if template.name.trim().is_empty() {
+        return Err("템플릿 이름을 입력해주세요.".to_string());
     }
+    render::validate_layout(&template.layout)?;
 
-    if api_key.trim().is_empty() {
-        return Err("API 키가 설정되지 않았습니다.".to_string());
+    let mut settings = get_settings(app.clone()).await?;
+
+    // Find and update existing template or add new one
+    if let Some(existing) = settings.layout_templates.iter_mut().find(|t| t.id == template.id) {
+        existing.name = template.name;
+        existing.layout = template.layout;
+    } else {
+        settings.layout_templates.push(template);
+    }
+
+    save_settings(app, settings).await
+}
+
+#[tauri::command]
+pub async fn delete_layout_template(app: AppHandle, template_id: String) -> Result<(), String> {
+    let mut settings = get_settings(app.clone()).await?;
+    settings.layout_templates.retain(|t| t.id != template_id);
+
+    // Roles pointing at the deleted template fall back to the default layout
+    let roles = &mut settings.layout_roles;
+    for slot in [&mut roles.cover, &mut roles.body, &mut roles.outro] {
+        if slot.as_deref() == Some(template_id.as_str()) {
+            *slot = None;
+        }
     }
 
+    save_settings(app, settings).await
+}
+
+#[tauri::command]
+pub async fn set_layout_template_role(
+    app: AppHandle,
+    role: String,
+    template_id: Option<String>,
+) -> Result<(), String> {
+    let mut settings = get_settings(app.clone()).await?;
+
+    if let Some(id) = &template_id {
+        if !settings.layout_templates.iter().any(|t| &t.id == id) {
+            return Err(format!("레이아웃 템플릿을 찾을 수 없습니다: {}", id));
+        }
+    }
+
+    match role.as_str() {
+        "cover" => settings.layout_roles.cover = template_id,
+        "body" => settings.layout_roles.body = template_id,
+        "outro" => settings.layout_roles.outro = template_id,
+        _ => return Err(format!("알 수 없는 슬라이드 역할입니다: {}", role)),
+    }
+
+    save_settings(app, settings).await
+}
+
+#[tauri::command]
+pub async fn save_model_settings(app: AppHandle, model_settings: ModelSettings) -> Result<(), String> {
+    validate_model_settings(&model_settings)?;
+
+    let mut settings = get_settings(app.clone()).await?;
+    settings.model_settings = model_settings;
+    save_settings(app, settings).await
+}
+
+#[tauri::command]
+pub async fn save_ncbi_settings(app: AppHandle, ncbi: NcbiSettings) -> Result<(), String> {
+    // 빈 문자열은 설정하지 않은 것으로 저장합니다
+    let clean = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+    let ncbi = NcbiSettings {
+        api_key: clean(ncbi.api_key),
+        tool: clean(ncbi.tool),
+        email: clean(ncbi.email),
+    };
+
+    if ncbi.tool.as_deref().is_some_and(|t| t.contains(char::is_whitespace)) {
+        return Err("NCBI tool 이름에는 공백을 쓸 수 없습니다.".to_string());
+    }
+    if ncbi.email.as_deref().is_some_and(|e| !e.contains('@')) {
+        return Err("NCBI 연락처 이메일 형식이 올바르지 않습니다.".to_string());
+    }
+
+    let mut settings = get_settings(app.clone()).await?;
+    settings.ncbi = ncbi;
+    save_settings(app, settings).await
+}
+
+#[tauri::command]
+pub async fn list_news_feeds(app: AppHandle) -> Result<Vec<NewsFeed>, String> {
+    let settings = get_settings(app).await?;
+    Ok(settings.news_feeds.feeds)
+}
+
+#[tauri::command]
+pub async fn add_news_feed(app: AppHandle, name: String, url: String, category: String) -> Result<NewsFeed, String> {
+    let name = name.trim();
+    let url = url.trim();
+    if name.is_empty() {
+        return Err("피드 이름을 입력해주세요.".to_string());
+    }
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err("피드 주소는 http:// 또는 https://로 시작해야 합니다.".to_string());
+    }
+
+    let mut settings = get_settings(app.clone()).await?;
+    if settings.news_feeds.feeds.iter().any(|f| f.url == url) {
+        return Err(format!("이미 등록된 피드입니다: {}", url));
+    }
+
+    let feed = NewsFeed {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        url: url.to_string(),
+        category: match category.trim() {
+            "" => "general".to_string(),
+            c => c.to_lowercase(),
+        },
+        enabled: true,
+    };
+    settings.news_feeds.feeds.push(feed.clone());
+    save_settings(app, settings).await?;
+
+    Ok(feed)
+}
+
+#[tauri::command]
+pub async fn remove_news_feed(app: AppHandle, feed_id: String) -> Result<(), String> {
+    let mut settings = get_settings(app.clone()).await?;
+    settings.news_feeds.feeds.retain(|f| f.id != feed_id);
+    save_settings(app, settings).await
+}
+
+#[tauri::command]
+pub async fn set_news_feed_enabled(app: AppHandle, feed_id: String, enabled: bool) -> Result<(), String> {
+    let mut settings = get_settings(app.clone()).await?;
+    let feed = settings
+        .news_feeds
+        .feeds
+        .iter_mut()
+        .find(|f| f.id == feed_id)
+        .ok_or_else(|| format!("뉴스 피드를 찾을 수 없습니다: {}", feed_id))?;
+    feed.enabled = enabled;
+    save_settings(app, settings).await
+}
+
+/// Download a feed before adding it, to check that it is RSS or Atom and has articles
+#[tauri::command]
+pub async fn test_news_feed(url: String) -> Result<NewsFeedTest, String> {
+    let feed = NewsService::new().fetch_feed(url.trim()).await?;
+
+    Ok(NewsFeedTest {
+        format: match feed.format {
+            FeedFormat::Rss => "rss",
+            FeedFormat::Atom => "atom",
+        }
+        .to_string(),
+        title: feed.title,
+        item_count: feed.entries.len(),
+        sample_titles: feed.entries.into_iter().take(5).map(|e| e.title).collect(),
+    })
+}
+
+/// Temperature range each provider's API accepts
+fn temperature_range(provider: &str) -> RangeInclusive<f32> {
+    match provider {
+        "anthropic" => 0.0..=1.0,
+        _ => 0.0..=2.0,
+    }
+}
+
+fn validate_model_settings(model_settings: &ModelSettings) -> Result<(), String> {
+    for (provider, models) in [
+        ("openai", &model_settings.openai),
+        ("anthropic", &model_settings.anthropic),
+        ("google", &model_settings.google),
+    ] {
+        if models.text.trim().is_empty() || models.vision.trim().is_empty() {
+            return Err(format!("{}: 모델 ID를 입력해주세요.", provider));
+        }
+        let range = temperature_range(provider);
+        if !range.contains(&models.temperature) {
+            return Err(format!(
+                "{}: temperature는 {}-{} 범위여야 합니다.",
+                provider,
+                range.start(),
+                range.end()
+            ));
+        }
+        if !(1..=200_000).contains(&models.max_tokens) {
+            return Err(format!("{}: 최대 토큰 수가 올바르지 않습니다.", provider));
+        }
+    }
+    Ok(())
+}
+
+/// Models the provider actually offers for the configured (or given) API key
+#[tauri::command]
+pub async fn list_provider_models(
+    app: AppHandle,
+    provider: String,
+    api_key: Option<String>,
+) -> Result<Vec<ModelInfo>, String> {
+    let settings = get_settings(app).await?;
+    let llm = LlmRegistry::from_settings(&settings).resolve(Some(&provider), api_key.as_deref())?;
+    llm.list_models().await
+}
+
+/// Base64 contents of an image file and its mime type, for vision requests
+pub(crate) fn read_image_base64(image_path: &str) -> Result<(String, &'static str), String> {
     // Read image file and convert to base64
-    let image_data = fs::read(&image_path)
+    let image_data = fs::read(image_path)
         .map_err(|e| format!("이미지 파일을 읽을 수 없습니다: {}", e))?;
 
     let base64_image = STANDARD.encode(&image_data);
@@ -332,6 +551,25 @@ pub async fn generate_prompt_from_image(
         "image/png" // default
     };
 
+    Ok((base64_image, mime_type))
+}
+
+#[tauri::command]
+pub async fn generate_prompt_from_image(
+    app: AppHandle,
+    image_path: String,
+    api_key: Option<String>,
+    provider: Option<String>,
+) -> Result<String, String> {
+    if image_path.trim().is_empty() {
+        return Err("이미지 경로가 비어있습니다.".to_string());
+    }
+
+    let settings = get_settings(app).await?;
+    let llm = LlmRegistry::from_settings(&settings).resolve(provider.as_deref(), api_key.as_deref())?;
+
+    let (base64_image, mime_type) = read_image_base64(&image_path)?;
+
     let system_prompt = r#"당신은 이미지 스타일 분석 전문가입니다. 주어진 이미지를 분석하여 이 이미지를 AI 이미지 생성 모델로 재현하기 위한 상세한 프롬프트를 작성해주세요.
 
 다음 요소들을 포함하여 분석해주세요:
@@ -348,82 +586,15 @@ pub async fn generate_prompt_from_image(
     let user_prompt = "이 이미지의 스타일을 분석하여 AI 이미지 생성을 위한 상세한 프롬프트를 작성해주세요.";
 
     // Call LLM with vision capability
-    match provider.as_str() {
-        "openai" => {
-            let service = OpenAIService::new(&api_key);
-            service.analyze_image_for_prompt(&base64_image, mime_type, system_prompt, user_prompt).await
-        }
-        "anthropic" => {
-            let service = AnthropicService::new(&api_key);
-            service.analyze_image_for_prompt(&base64_image, mime_type, system_prompt, user_prompt).await
-        }
-        _ => {
-            // Default to Google
-            let service = GoogleService::new(&api_key);
-            service.analyze_image_for_prompt(&base64_image, mime_type, system_prompt, user_prompt).await
-        }
-    }
+    llm.analyze_image(&base64_image, mime_type, system_prompt, user_prompt).await
 }
 
 #[tauri::command]
 pub async fn get_system_fonts() -> Result<Vec<String>, String> {
-    let mut fonts: HashSet<String> = HashSet::new();
-
-    // Windows fonts directories
-    #[cfg(target_os = "windows")]
-    {
-        // System fonts
-        if let Ok(windows_dir) = std::env::var("WINDIR") {
-            let system_fonts = PathBuf::from(&windows_dir).join("Fonts");
-            if system_fonts.exists() {
-                collect_fonts_from_dir(&system_fonts, &mut fonts);
-            }
-        }
-
-        // User fonts
-        if let Ok(local_app_data) = std::env::var("LOCALAPPDATA") {
-            let user_fonts = PathBuf::from(&local_app_data)
-                .join("Microsoft")
-                .join("Windows")
-                .join("Fonts");
-            if user_fonts.exists() {
-                collect_fonts_from_dir(&user_fonts, &mut fonts);
-            }
-        }
-    }
-
-    // macOS fonts directories
-    #[cfg(target_os = "macos")]
-    {
-        let font_dirs = vec![
-            PathBuf::from("/System/Library/Fonts"),
-            PathBuf::from("/Library/Fonts"),
-            dirs::home_dir().map(|h| h.join("Library/Fonts")).unwrap_or_default(),
-        ];
-
-        for dir in font_dirs {
-            if dir.exists() {
-                collect_fonts_from_dir(&dir, &mut fonts);
-            }
-        }
-    }
-
-    // Linux fonts directories
-    #[cfg(target_os = "linux")]
-    {
-        let font_dirs = vec![
-            PathBuf::from("/usr/share/fonts"),
-            PathBuf::from("/usr/local/share/fonts"),
-            dirs::home_dir().map(|h| h.join(".fonts")).unwrap_or_default(),
-            dirs::home_dir().map(|h| h.join(".local/share/fonts")).unwrap_or_default(),
-        ];
-
-        for dir in font_dirs {
-            if dir.exists() {
-                collect_fonts_from_dir(&dir, &mut fonts);
-            }
-        }
-    }
+    let fonts: HashSet<String> = fonts::scan_system_fonts()
+        .into_iter()
+        .map(|face| face.family)
+        .collect();
 
     let mut font_list: Vec<String> = fonts.into_iter().collect();
     font_list.sort_by(|a, b| a.to_lowercase().cmp(&b.to_lowercase()));
@@ -431,78 +602,45 @@ pub async fn get_system_fonts() -> Result<Vec<String>, String> {
     Ok(font_list)
 }
 
-fn collect_fonts_from_dir(dir: &PathBuf, fonts: &mut HashSet<String>) {
-    if let Ok(entries) = fs::read_dir(dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-
-            if path.is_dir() {
-                // Recursively search subdirectories
-                collect_fonts_from_dir(&path, fonts);
-            } else if let Some(ext) = path.extension() {
-                let ext_lower = ext.to_string_lossy().to_lowercase();
-                if ext_lower == "ttf" || ext_lower == "otf" {
-                    // Try to read actual font family name from font metadata
-                    if let Some(font_name) = get_font_family_name(&path) {
-                        if !font_name.is_empty() {
-                            fonts.insert(font_name);
-                        }
-                    }
-                } else if ext_lower == "ttc" {
-                    // TTC files can contain multiple fonts
-                    if let Ok(data) = fs::read(&path) {
-                        if let Some(count) = ttf_parser::fonts_in_collection(&data) {
-                            for index in 0..count {
-                                if let Ok(face) = ttf_parser::Face::parse(&data, index) {
-                                    if let Some(name) = extract_family_name(&face) {
-                                        fonts.insert(name);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
+/// Fit text into a box with the slide renderer's layout engine so the editor can preview wrapping
+#[tauri::command]
+pub async fn preview_text_layout(request: TextLayoutRequest) -> Result<TextLayoutPreview, String> {
+    if request.width <= 0.0 || request.height <= 0.0 {
+        return Err("텍스트 박스 크기가 올바르지 않습니다.".to_string());
     }
-}
-
-/// Read actual font family name from font file metadata
-fn get_font_family_name(path: &PathBuf) -> Option<String> {
-    let data = fs::read(path).ok()?;
-    let face = ttf_parser::Face::parse(&data, 0).ok()?;
-    extract_family_name(&face)
-}
-
-/// Extract font family name from parsed font face
-fn extract_family_name(face: &ttf_parser::Face) -> Option<String> {
-    // Try to find the font family name in the naming table
-    // Priority: Windows platform with Korean (1042), then English (1033), then any
-    let names = face.names();
-
-    // Name ID 1 = Font Family Name
-    // Name ID 16 = Typographic Family Name (preferred)
 
-    // First try typographic family name (ID 16)
-    let mut family_name: Option<String> = None;
-
-    // Try to get Korean name first (platform 3, encoding 1, language 1042)
-    for name in names.into_iter() {
-        if name.name_id == ttf_parser::name_id::TYPOGRAPHIC_FAMILY || name.name_id == ttf_parser::name_id::FAMILY {
-            if let Some(name_str) = name.to_string() {
-                // Prefer Korean name if available
-                if name.language_id == 1042 {
-                    return Some(name_str);
-                }
-                // Otherwise use English or first available
-                if family_name.is_none() || name.language_id == 1033 {
-                    family_name = Some(name_str);
-                }
-            }
-        }
-    }
+    let defaults = TextStyle::default();
+    let style = TextStyle {
+        font_family: request.font_family,
+        font_weight: request.font_weight.unwrap_or(defaults.font_weight),
+        min_font_size: request.min_font_size.unwrap_or(defaults.min_font_size),
+        max_font_size: request.max_font_size.unwrap_or(defaults.max_font_size),
+        line_height: request.line_height.unwrap_or(defaults.line_height),
+        align: request.align.as_deref().map(TextAlign::parse).unwrap_or(defaults.align),
+        vertical_align: request
+            .vertical_align
+            .as_deref()
+            .map(VerticalAlign::parse)
+            .unwrap_or(defaults.vertical_align),
+    };
 
-    family_name
+    tokio::task::spawn_blocking(move || {
+        let stack = FontStack::resolve(style.font_family.as_deref(), style.font_weight)?;
+        let layout = text::layout_text(&stack, &request.text, &style, request.width, request.height);
+        let (x, y, width, height) = layout.bounds;
+
+        Ok(TextLayoutPreview {
+            font_size: layout.font_size,
+            lines: layout.lines,
+            truncated: layout.truncated,
+            x,
+            y,
+            width,
+            height,
+        })
+    })
+    .await
+    .map_err(|e| format!("텍스트 배치 계산 실패: {}", e))?
 }
 
 #[tauri::command]
@@ -583,3 +721,26 @@ pub async fn open_external_url(url: String) -> Result<(), String> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_temperature_per_provider() {
+        let mut settings = ModelSettings::default();
+        assert!(validate_model_settings(&settings).is_ok());
+
+        settings.openai.temperature = 1.5;
+        settings.google.temperature = 2.0;
+        assert!(validate_model_settings(&settings).is_ok());
+
+        settings.anthropic.temperature = 1.5;
+        let error = validate_model_settings(&settings).unwrap_err();
+        assert!(error.starts_with("anthropic"), "{error}");
+
+        settings.anthropic.temperature = 1.0;
+        settings.openai.temperature = 2.5;
+        assert!(validate_model_settings(&settings).unwrap_err().starts_with("openai"));
+    }
+}
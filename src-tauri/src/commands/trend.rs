@@ -0,0 +1,75 @@
+use crate::commands::settings::get_settings;
+use crate::models::{KeywordTrend, TrendStore};
+use crate::services::news::NewsResult;
+use crate::services::trend::{self, TrendCollector};
+use chrono::Utc;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use tokio::sync::Mutex;
+
+// 읽고-수정하고-저장하는 사이에 다른 명령이 끼어들지 않도록 직렬화합니다
+static STORE_LOCK: Mutex<()> = Mutex::const_new(());
+
+fn get_trend_store_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("앱 데이터 디렉토리를 찾을 수 없습니다: {}", e))?;
+    fs::create_dir_all(&app_data_dir).map_err(|e| format!("앱 데이터 디렉토리 생성 실패: {}", e))?;
+    Ok(app_data_dir.join("keyword_trends.json"))
+}
+
+pub(crate) fn load_trend_store(app: &AppHandle) -> Result<TrendStore, String> {
+    let path = get_trend_store_path(app)?;
+    if !path.exists() {
+        return Ok(TrendStore::default());
+    }
+
+    let json = fs::read_to_string(&path).map_err(|e| format!("트렌드 기록 읽기 실패: {}", e))?;
+    serde_json::from_str(&json).map_err(|e| format!("트렌드 기록 파싱 실패: {}", e))
+}
+
+fn save_trend_store(app: &AppHandle, store: &TrendStore) -> Result<(), String> {
+    let path = get_trend_store_path(app)?;
+    let json = serde_json::to_string_pretty(store).map_err(|e| format!("트렌드 기록 직렬화 실패: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("트렌드 기록 저장 실패: {}", e))
+}
+
+/// Count news items a search already fetched into the keyword's history
+pub(crate) async fn record_news_results(app: &AppHandle, keyword: &str, items: &[NewsResult]) -> Result<(), String> {
+    let _guard = STORE_LOCK.lock().await;
+    let mut store = load_trend_store(app)?;
+    let history = trend::history_entry(&mut store, keyword);
+    trend::record_news(history, items);
+    history.updated_at = Utc::now().to_rfc3339();
+    save_trend_store(app, &store)
+}
+
+/// Stored series and classification for a keyword (no network access)
+#[tauri::command]
+pub async fn get_keyword_trend(app: AppHandle, keyword: String) -> Result<KeywordTrend, String> {
+    let store = load_trend_store(&app)?;
+    let history = store.keywords.get(&trend::trend_key(&keyword));
+    Ok(trend::keyword_trend(&keyword, history, Utc::now().date_naive()))
+}
+
+/// Fetch yearly PubMed/CrossRef counts and the current news items, record them and reclassify
+#[tauri::command]
+pub async fn refresh_keyword_trend(app: AppHandle, keyword: String) -> Result<KeywordTrend, String> {
+    if keyword.trim().is_empty() {
+        return Err("키워드를 입력해주세요.".to_string());
+    }
+
+    let settings = get_settings(app.clone()).await?;
+    let update = TrendCollector::new(&settings).collect(&keyword).await;
+
+    let _guard = STORE_LOCK.lock().await;
+    let mut store = load_trend_store(&app)?;
+    let history = trend::history_entry(&mut store, &keyword);
+    update.apply(history);
+    let result = trend::keyword_trend(&keyword, Some(history), Utc::now().date_naive());
+    save_trend_store(&app, &store)?;
+
+    Ok(result)
+}
@@ -12,6 +12,12 @@ pub enum AppError {
     #[error("잘못된 API 제공자: {0}")]
     InvalidProvider(String),
 
+    #[error("API 키가 설정되지 않았습니다: {0}")]
+    MissingApiKey(String),
+
+    #[error("AI 응답 형식이 올바르지 않습니다: {0}")]
+    InvalidLlmOutput(String),
+
     #[error("설정을 찾을 수 없습니다")]
     SettingsNotFound,
 
@@ -31,6 +37,13 @@ pub enum AppError {
     FileWriteError(String),
 }
 
+// Tauri commands report errors as plain strings
+impl From<AppError> for String {
+    fn from(err: AppError) -> Self {
+        err.to_string()
+    }
+}
+
 impl From<reqwest::Error> for AppError {
     fn from(err: reqwest::Error) -> Self {
         AppError::NetworkError(err.to_string())
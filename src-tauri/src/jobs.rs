@@ -0,0 +1,410 @@
+use crate::models::{GeneratedImage, ImageGenerationRequest};
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tauri::{Emitter, Window};
+use tokio::sync::watch;
+use uuid::Uuid;
+
+// 프론트엔드로 보내는 작업 이벤트 이름
+pub const EVENT_PROGRESS: &str = "job:progress";
+pub const EVENT_ITEM_COMPLETED: &str = "job:completed";
+pub const EVENT_ITEM_FAILED: &str = "job:failed";
+pub const EVENT_FINISHED: &str = "job:finished";
+
+// 재시도할 수 있도록 남겨 두는 종료된 작업 수
+const MAX_FINISHED_JOBS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Paused,
+    Cancelled,
+    Completed,
+    Failed,
+}
+
+impl JobStatus {
+    pub fn is_finished(self) -> bool {
+        matches!(self, JobStatus::Cancelled | JobStatus::Completed | JobStatus::Failed)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedJobItem {
+    pub content_id: String,
+    pub error: String,
+}
+
+/// Everything needed to (re)run an image batch. The API key is dropped once the job finishes; a retry
+/// brings its own.
+#[derive(Debug, Clone)]
+pub struct ImageJobInput {
+    pub requests: Vec<ImageGenerationRequest>,
+    pub api_key: Option<String>,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub aspect_ratio: Option<String>,
+    pub negative_prompt: Option<String>,
+}
+
+/// Public view of a job, returned by commands and sent with `job:finished`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobSnapshot {
+    pub id: String,
+    pub status: JobStatus,
+    pub total: usize,
+    pub processed: usize,
+    pub results: Vec<GeneratedImage>,
+    pub failed: Vec<FailedJobItem>,
+    pub created_at: String,
+    pub finished_at: Option<String>,
+    /// Set when this job retries items from another job
+    pub retry_of: Option<String>,
+}
+
+/// Per-item event payload for `job:progress`, `job:completed` and `job:failed`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobItemEvent {
+    pub job_id: String,
+    pub content_id: String,
+    pub index: usize,
+    pub total: usize,
+    pub processed: usize,
+    pub image: Option<GeneratedImage>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ControlState {
+    Running,
+    Paused,
+    Cancelled,
+}
+
+/// Pause/cancel switch shared between the commands and the running task
+#[derive(Debug)]
+pub struct JobControl {
+    state: watch::Sender<ControlState>,
+}
+
+impl JobControl {
+    fn new() -> Self {
+        Self {
+            state: watch::channel(ControlState::Running).0,
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.state.borrow() == ControlState::Cancelled
+    }
+
+    /// Wait while the job is paused; returns false once it has been cancelled
+    pub async fn proceed(&self) -> bool {
+        let mut rx = self.state.subscribe();
+        let running = match rx.wait_for(|s| *s != ControlState::Paused).await {
+            Ok(state) => *state == ControlState::Running,
+            Err(_) => false,
+        };
+        running
+    }
+
+    /// Resolves when the job is cancelled (used to abort an in-flight request)
+    pub async fn cancelled(&self) {
+        let mut rx = self.state.subscribe();
+        let _ = rx.wait_for(|s| *s == ControlState::Cancelled).await;
+    }
+
+    fn set(&self, next: ControlState) {
+        self.state.send_if_modified(|state| {
+            // A cancelled job stays cancelled
+            if *state == ControlState::Cancelled || *state == next {
+                return false;
+            }
+            *state = next;
+            true
+        });
+    }
+}
+
+struct JobEntry {
+    snapshot: JobSnapshot,
+    control: Arc<JobControl>,
+    input: ImageJobInput,
+}
+
+/// Background jobs held in Tauri managed state
+#[derive(Default)]
+pub struct JobManager {
+    jobs: Mutex<HashMap<String, JobEntry>>,
+    // Finished job ids, oldest first
+    finished: Mutex<VecDeque<String>>,
+}
+
+impl JobManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a new queued job and hand back its id and control handle
+    pub fn create(&self, input: ImageJobInput, retry_of: Option<String>) -> Result<(String, Arc<JobControl>), String> {
+        let id = Uuid::new_v4().to_string();
+        let control = Arc::new(JobControl::new());
+        let snapshot = JobSnapshot {
+            id: id.clone(),
+            status: JobStatus::Queued,
+            total: input.requests.len(),
+            processed: 0,
+            results: Vec::new(),
+            failed: Vec::new(),
+            created_at: Utc::now().to_rfc3339(),
+            finished_at: None,
+            retry_of,
+        };
+
+        self.lock()?.insert(
+            id.clone(),
+            JobEntry {
+                snapshot,
+                control: control.clone(),
+                input,
+            },
+        );
+        Ok((id, control))
+    }
+
+    pub fn get(&self, job_id: &str) -> Result<JobSnapshot, String> {
+        self.lock()?
+            .get(job_id)
+            .map(|entry| entry.snapshot.clone())
+            .ok_or_else(|| format!("작업을 찾을 수 없습니다: {}", job_id))
+    }
+
+    pub fn list(&self) -> Result<Vec<JobSnapshot>, String> {
+        let mut jobs: Vec<JobSnapshot> = self.lock()?.values().map(|e| e.snapshot.clone()).collect();
+        jobs.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(jobs)
+    }
+
+    /// Apply `f` to a job's snapshot and return the updated copy
+    pub fn update<F>(&self, job_id: &str, f: F) -> Result<JobSnapshot, String>
+    where
+        F: FnOnce(&mut JobSnapshot),
+    {
+        let mut jobs = self.lock()?;
+        let entry = jobs
+            .get_mut(job_id)
+            .ok_or_else(|| format!("작업을 찾을 수 없습니다: {}", job_id))?;
+        f(&mut entry.snapshot);
+        Ok(entry.snapshot.clone())
+    }
+
+    pub fn pause(&self, job_id: &str) -> Result<JobSnapshot, String> {
+        self.transition(job_id, ControlState::Paused, JobStatus::Paused)
+    }
+
+    pub fn resume(&self, job_id: &str) -> Result<JobSnapshot, String> {
+        self.transition(job_id, ControlState::Running, JobStatus::Running)
+    }
+
+    pub fn cancel(&self, job_id: &str) -> Result<JobSnapshot, String> {
+        self.transition(job_id, ControlState::Cancelled, JobStatus::Cancelled)
+    }
+
+    /// Apply the final update `f` (which sets the finished status), forget the job's API key and evict the
+    /// oldest finished jobs beyond `MAX_FINISHED_JOBS`
+    pub fn finish<F>(&self, job_id: &str, f: F) -> Result<JobSnapshot, String>
+    where
+        F: FnOnce(&mut JobSnapshot),
+    {
+        let mut jobs = self.lock()?;
+        let entry = jobs
+            .get_mut(job_id)
+            .ok_or_else(|| format!("작업을 찾을 수 없습니다: {}", job_id))?;
+        f(&mut entry.snapshot);
+        entry.input.api_key = None;
+        let snapshot = entry.snapshot.clone();
+
+        let mut finished = self.finished.lock().map_err(|e| format!("작업 목록 잠금 실패: {}", e))?;
+        finished.push_back(job_id.to_string());
+        while finished.len() > MAX_FINISHED_JOBS {
+            if let Some(oldest) = finished.pop_front() {
+                jobs.remove(&oldest);
+            }
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Input for a follow-up job that reruns failed items (all of them, or only `content_ids`) with `api_key`
+    pub fn retry_input(
+        &self,
+        job_id: &str,
+        content_ids: Option<&[String]>,
+        api_key: Option<String>,
+    ) -> Result<ImageJobInput, String> {
+        let jobs = self.lock()?;
+        let entry = jobs
+            .get(job_id)
+            .ok_or_else(|| format!("작업을 찾을 수 없습니다: {}", job_id))?;
+
+        if !entry.snapshot.status.is_finished() {
+            return Err("진행 중인 작업은 재시도할 수 없습니다.".to_string());
+        }
+
+        let failed: Vec<&str> = entry
+            .snapshot
+            .failed
+            .iter()
+            .map(|f| f.content_id.as_str())
+            .filter(|id| content_ids.is_none_or(|ids| ids.iter().any(|c| c == id)))
+            .collect();
+
+        let requests: Vec<ImageGenerationRequest> = entry
+            .input
+            .requests
+            .iter()
+            .filter(|r| failed.contains(&r.content_id.as_str()))
+            .cloned()
+            .collect();
+
+        if requests.is_empty() {
+            return Err("재시도할 실패 항목이 없습니다.".to_string());
+        }
+
+        Ok(ImageJobInput {
+            requests,
+            api_key,
+            ..entry.input.clone()
+        })
+    }
+
+    fn transition(&self, job_id: &str, control: ControlState, status: JobStatus) -> Result<JobSnapshot, String> {
+        let mut jobs = self.lock()?;
+        let entry = jobs
+            .get_mut(job_id)
+            .ok_or_else(|| format!("작업을 찾을 수 없습니다: {}", job_id))?;
+
+        if entry.snapshot.status.is_finished() {
+            return Err("이미 종료된 작업입니다.".to_string());
+        }
+        // A cancel request only takes effect once the current item stops; until then the job must not look
+        // paused or running again
+        if entry.control.is_cancelled() {
+            return match status {
+                JobStatus::Cancelled => Ok(entry.snapshot.clone()),
+                _ => Err("취소 중인 작업입니다.".to_string()),
+            };
+        }
+
+        entry.control.set(control);
+        // The runner marks a cancelled job finished once the current item stops
+        if status != JobStatus::Cancelled {
+            entry.snapshot.status = status;
+        }
+        Ok(entry.snapshot.clone())
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, HashMap<String, JobEntry>>, String> {
+        self.jobs.lock().map_err(|e| format!("작업 목록 잠금 실패: {}", e))
+    }
+}
+
+/// Send a job event to the window that started the job; a closed window must not stop the job
+pub fn emit_event<S: Serialize + Clone>(window: &Window, event: &str, payload: S) {
+    if let Err(e) = window.emit_to(window.label(), event, payload) {
+        eprintln!("작업 이벤트 전송 실패 ({}): {}", event, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(api_key: &str, content_ids: &[&str]) -> ImageJobInput {
+        ImageJobInput {
+            requests: content_ids
+                .iter()
+                .map(|id| ImageGenerationRequest {
+                    content_id: id.to_string(),
+                    image_concept: String::new(),
+                    style_prompt: String::new(),
+                    style_image_path: None,
+                })
+                .collect(),
+            api_key: Some(api_key.to_string()),
+            provider: None,
+            model: None,
+            aspect_ratio: None,
+            negative_prompt: None,
+        }
+    }
+
+    fn finish_failed(jobs: &JobManager, job_id: &str, content_id: &str) -> JobSnapshot {
+        jobs.finish(job_id, |job| {
+            job.failed.push(FailedJobItem { content_id: content_id.to_string(), error: "실패".to_string() });
+            job.status = JobStatus::Failed;
+            job.finished_at = Some(Utc::now().to_rfc3339());
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn pause_and_resume_are_refused_once_cancel_is_requested() {
+        let jobs = JobManager::new();
+        let (id, control) = jobs.create(input("key", &["a"]), None).unwrap();
+
+        assert_eq!(jobs.pause(&id).unwrap().status, JobStatus::Paused);
+        assert_eq!(jobs.resume(&id).unwrap().status, JobStatus::Running);
+
+        jobs.cancel(&id).unwrap();
+        assert!(control.is_cancelled());
+        assert!(jobs.pause(&id).is_err());
+        assert!(jobs.resume(&id).is_err());
+        assert!(jobs.cancel(&id).is_ok());
+        assert_eq!(jobs.get(&id).unwrap().status, JobStatus::Running);
+        assert!(control.is_cancelled());
+
+        finish_failed(&jobs, &id, "a");
+        assert!(jobs.cancel(&id).is_err());
+    }
+
+    #[test]
+    fn finished_jobs_forget_the_key_and_retries_bring_their_own() {
+        let jobs = JobManager::new();
+        let (id, _) = jobs.create(input("secret", &["a", "b"]), None).unwrap();
+        assert!(jobs.retry_input(&id, None, None).is_err());
+
+        finish_failed(&jobs, &id, "b");
+        assert!(jobs.lock().unwrap()[&id].input.api_key.is_none());
+
+        let retry = jobs.retry_input(&id, None, Some("new".to_string())).unwrap();
+        assert_eq!(retry.api_key.as_deref(), Some("new"));
+        let retried: Vec<&str> = retry.requests.iter().map(|r| r.content_id.as_str()).collect();
+        assert_eq!(retried, ["b"]);
+    }
+
+    #[test]
+    fn evicts_the_oldest_finished_jobs() {
+        let jobs = JobManager::new();
+        let (running, _) = jobs.create(input("key", &["a"]), None).unwrap();
+        let ids: Vec<String> = (0..MAX_FINISHED_JOBS + 2)
+            .map(|_| {
+                let (id, _) = jobs.create(input("key", &["a"]), None).unwrap();
+                finish_failed(&jobs, &id, "a");
+                id
+            })
+            .collect();
+
+        assert!(jobs.get(&running).is_ok());
+        assert!(jobs.get(&ids[0]).is_err());
+        assert!(jobs.get(&ids[1]).is_err());
+        assert!(ids[2..].iter().all(|id| jobs.get(id).is_ok()));
+    }
+}
@@ -1,24 +1,47 @@
 mod commands;
 mod error;
+mod jobs;
 mod models;
+mod render;
 mod services;
 
-use commands::{content, image, keyword, project, research, settings};
+use commands::{compatibility, content, image, ingredient, job, keyword, label, project, regulation, research, settings, trend};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .manage(jobs::JobManager::new())
         .invoke_handler(tauri::generate_handler![
             // Keyword commands
             keyword::suggest_keywords,
+            // Trend commands
+            trend::get_keyword_trend,
+            trend::refresh_keyword_trend,
             // Research commands
             research::search_papers,
             research::analyze_ingredient,
             research::search_web,
             research::search_conferences,
+            research::merge_paper_results,
+            research::search_literature,
             research::search_news,
+            // Ingredient dictionary commands
+            ingredient::lookup_ingredient,
+            ingredient::autocomplete_ingredient,
+            ingredient::resolve_ingredient,
+            ingredient::import_ingredient_dataset,
+            // Regulatory data commands
+            regulation::get_regulatory_status,
+            regulation::import_regulatory_dataset,
+            // Ingredient label commands
+            label::analyze_ingredient_label,
+            label::extract_ingredient_label,
+            label::save_label_research,
+            // Ingredient compatibility commands
+            compatibility::check_ingredient_compatibility,
+            compatibility::get_ingredient_pairings,
             // Content commands
             content::generate_content_plan,
             content::create_persona,
@@ -28,6 +51,13 @@ pub fn run() {
             image::generate_batch_images,
             image::download_image,
             image::download_all_images,
+            image::retry_failed_images,
+            // Job commands
+            job::get_job,
+            job::list_jobs,
+            job::pause_job,
+            job::resume_job,
+            job::cancel_job,
             // Settings commands
             settings::get_settings,
             settings::save_settings,
@@ -41,8 +71,21 @@ pub fn run() {
             settings::save_image_prompt,
             settings::delete_image_prompt,
             settings::save_layout_settings,
+            settings::list_layout_templates,
+            settings::save_layout_template,
+            settings::delete_layout_template,
+            settings::set_layout_template_role,
+            settings::save_model_settings,
+            settings::save_ncbi_settings,
+            settings::list_news_feeds,
+            settings::add_news_feed,
+            settings::remove_news_feed,
+            settings::set_news_feed_enabled,
+            settings::test_news_feed,
+            settings::list_provider_models,
             settings::generate_prompt_from_image,
             settings::get_system_fonts,
+            settings::preview_text_layout,
             settings::delete_image_file,
             settings::open_folder_in_explorer,
             settings::open_external_url,
@@ -54,6 +97,7 @@ pub fn run() {
             project::list_projects,
             project::save_research_item,
             project::save_content_group,
+            project::assign_content_layout,
             project::get_project_images_dir,
         ])
         .run(tauri::generate_context!())
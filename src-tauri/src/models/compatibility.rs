@@ -0,0 +1,79 @@
+use super::ingredient::Ingredient;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Paper, review or regulation a pairing rule is based on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InteractionSource {
+    pub id: String,
+    /// Citation, e.g. "Lin JY, ... J Am Acad Dermatol. 2003;48(6):866-874"
+    pub title: String,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Compatibility {
+    /// 함께 쓰면 좋은 조합
+    Synergistic,
+    /// 함께 써도 문제없는 조합 (잘못 알려진 경우 포함)
+    Neutral,
+    /// 겹쳐 쓰면 자극, 분해 또는 규제상 주의가 필요한 조합
+    Cautionary,
+}
+
+/// How ingredients on one side combine with those on the other, as stored in the data file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InteractionRule {
+    /// Dictionary ids or group names
+    pub first: Vec<String>,
+    pub second: Vec<String>,
+    pub compatibility: Compatibility,
+    pub rationale: String,
+    /// How to use the two together, e.g. "아침과 저녁으로 나눠 쓰세요"
+    #[serde(default)]
+    pub advice: Option<String>,
+    /// Part of the source, e.g. "Annex V"
+    #[serde(default)]
+    pub reference: Option<String>,
+    pub source_id: String,
+}
+
+/// File format of the bundled interaction data
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InteractionDataset {
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub sources: Vec<InteractionSource>,
+    /// Group name → dictionary ids, e.g. "retinoids" → ["retinol", "retinal", ...]
+    #[serde(default)]
+    pub groups: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub rules: Vec<InteractionRule>,
+}
+
+/// A rule that applies to two ingredients, with the source it is cited from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngredientPairing {
+    pub first: Ingredient,
+    pub second: Ingredient,
+    pub compatibility: Compatibility,
+    pub rationale: String,
+    pub advice: Option<String>,
+    pub reference: Option<String>,
+    pub source: InteractionSource,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompatibilityReport {
+    /// Dictionary entries the given names resolved to, in input order
+    pub ingredients: Vec<Ingredient>,
+    /// Names the dictionary does not know; they could not be checked
+    pub unresolved: Vec<String>,
+    /// Cautionary pairs first, then synergistic, then neutral
+    pub pairings: Vec<IngredientPairing>,
+}
@@ -1,3 +1,6 @@
+use super::ingredient::Ingredient;
+use super::regulation::RegulatoryFact;
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,6 +21,139 @@ pub struct PaperResult {
     pub source: String,
     pub citation_count: Option<u32>,
     pub doi: Option<String>,
+    #[serde(default)]
+    pub pmid: Option<String>,
+    #[serde(default)]
+    pub pmcid: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Free full-text PDF (Unpaywall or PMC)
+    #[serde(default)]
+    pub open_access_url: Option<String>,
+    #[serde(default)]
+    pub journal: Option<String>,
+    #[serde(default)]
+    pub abstract_sections: Vec<AbstractSection>,
+    #[serde(default)]
+    pub mesh_terms: Vec<String>,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    #[serde(default)]
+    pub publication_types: Vec<String>,
+    #[serde(default)]
+    pub country: Option<String>,
+    #[serde(default)]
+    pub evidence: Option<EvidenceAssessment>,
+}
+
+/// How much weight a paper can carry: study design, who was studied and how many
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvidenceAssessment {
+    pub grade: String,        // "A" (strongest) .. "D"
+    pub study_design: String, // "meta_analysis", "randomized_controlled_trial", "cohort", ...
+    pub subject: String,      // "human", "animal", "in_vitro", "unknown"
+    pub sample_size: Option<u32>,
+    pub year: Option<i32>,
+    /// Grade C or D: not enough to support a claim on its own
+    pub weak: bool,
+}
+
+/// One labeled part of a structured abstract (BACKGROUND, METHODS, RESULTS, ...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbstractSection {
+    pub label: Option<String>,
+    pub text: String,
+}
+
+/// One de-duplicated result of a federated literature search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScholarlyRecord {
+    #[serde(flatten)]
+    pub paper: PaperResult,
+    /// Every source that returned this record ("pubmed", "crossref", "web")
+    pub found_in: Vec<String>,
+    /// Ranking score from relevance, evidence strength and recency (0-1, higher first)
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceFailure {
+    pub source: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiteratureSearchResult {
+    pub records: Vec<ScholarlyRecord>,
+    /// Sources that were queried, including the failed ones
+    pub searched: Vec<String>,
+    pub failures: Vec<SourceFailure>,
+}
+
+/// Filters for `search_papers` and `search_literature`; every field is optional
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PaperSearchFilters {
+    pub year_from: Option<u16>,
+    pub year_to: Option<u16>,
+    /// Any of these publication types (empty = all)
+    pub article_types: Vec<ArticleType>,
+    pub humans_only: bool,
+    /// PubMed language names, e.g. "english", "korean" (empty = all)
+    pub languages: Vec<String>,
+    /// Restrict to studies about any of these groups (empty = everyone)
+    pub populations: Vec<Population>,
+    pub sort: PaperSort,
+    /// Index of the first result, for paging
+    pub offset: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArticleType {
+    MetaAnalysis,
+    SystematicReview,
+    RandomizedControlledTrial,
+    ClinicalTrial,
+    Review,
+    CaseReport,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Population {
+    Pregnancy,
+    Infant,
+    Pediatric,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaperSort {
+    #[default]
+    Relevance,
+    Newest,
+}
+
+/// Filters for `search_news`; every field is optional
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NewsSearchFilters {
+    /// Published on or after this day ("YYYY-MM-DD")
+    pub date_from: Option<NaiveDate>,
+    /// Published on or before this day
+    pub date_to: Option<NaiveDate>,
+    /// Published within the last N days
+    pub days: Option<u32>,
+    pub sort: NewsSort,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NewsSort {
+    #[default]
+    Relevance,
+    Newest,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +165,25 @@ pub struct IngredientAnalysis {
     pub cautions: Vec<String>,
     pub recommended_concentration: Option<String>,
     pub related_papers: Vec<PaperResult>,
+    #[serde(default)]
+    pub benefit_evidence: Vec<BenefitEvidence>,
+    /// Dictionary entry the input resolved to
+    #[serde(default)]
+    pub ingredient: Option<Ingredient>,
+    /// MFDS/EU/pregnancy list entries from the local regulatory data, each with its source.
+    /// Unlike `cautions` and `recommended_concentration`, these are not written by the LLM.
+    #[serde(default)]
+    pub regulatory_facts: Vec<RegulatoryFact>,
+}
+
+/// Papers behind one claimed benefit and the best grade among them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenefitEvidence {
+    pub benefit: String,
+    pub paper_ids: Vec<String>,
+    pub grade: Option<String>,
+    /// Backed only by weak (C/D) papers, or by none at all
+    pub weak_evidence: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
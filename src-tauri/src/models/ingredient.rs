@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+/// One cosmetic ingredient and every name it goes by
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ingredient {
+    /// Stable slug, e.g. "niacinamide"; derived from the INCI name when an import leaves it out
+    #[serde(default)]
+    pub id: String,
+    pub inci_name: String,
+    /// 화장품 표준 성분명
+    #[serde(default)]
+    pub korean_name: String,
+    /// Common, trade and older names, in any language
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub cas_numbers: Vec<String>,
+    #[serde(default)]
+    pub ec_numbers: Vec<String>,
+    /// 배합 목적, e.g. "미백", "방부제", "향료"
+    #[serde(default)]
+    pub functions: Vec<String>,
+}
+
+/// File format of the bundled seed data and of imports
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IngredientDataset {
+    #[serde(default)]
+    pub version: Option<String>,
+    pub ingredients: Vec<Ingredient>,
+}
+
+/// A dictionary entry found for a query, with the name that matched
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngredientMatch {
+    pub ingredient: Ingredient,
+    pub matched_name: String,
+    /// 1.0 for an exact name, CAS or EC number match
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngredientImportSummary {
+    pub added: usize,
+    pub updated: usize,
+    /// Entries in the dictionary after the import, seed data included
+    pub total: usize,
+}
@@ -0,0 +1,89 @@
+use super::compatibility::InteractionSource;
+use super::ingredient::Ingredient;
+use super::regulation::RegulatoryFact;
+use serde::{Deserialize, Serialize};
+
+/// Why a label entry deserves attention
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelFlag {
+    /// 향료
+    Fragrance,
+    /// 착향제 중 알레르기 유발 성분
+    Allergen,
+    Prohibited,
+    Restricted,
+    PregnancyCaution,
+    InfantCaution,
+}
+
+/// One entry of a product's ingredient list (전성분), in label order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelEntry {
+    /// 1-based; labels list ingredients by decreasing content
+    pub position: usize,
+    /// As printed, without percentages and footnote marks
+    pub name: String,
+    /// None when the dictionary does not know the name
+    pub ingredient: Option<Ingredient>,
+    /// For unknown names, the INCI name of a similar dictionary entry ("did you mean"); never checked
+    #[serde(default)]
+    pub suggestion: Option<String>,
+    pub flags: Vec<LabelFlag>,
+    pub regulatory_facts: Vec<RegulatoryFact>,
+}
+
+/// Ingredients on the same label that are a concern together
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelCombination {
+    /// Label names of the ingredients involved
+    pub ingredients: Vec<String>,
+    pub reason: String,
+    pub advice: Option<String>,
+    pub source: InteractionSource,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelReport {
+    #[serde(default)]
+    pub product_name: Option<String>,
+    pub entries: Vec<LabelEntry>,
+    /// Names the dictionary does not know; nothing could be checked for them
+    pub unresolved: Vec<String>,
+    pub combinations: Vec<LabelCombination>,
+    /// Short overview in Korean, used as the research item summary
+    pub summary: String,
+}
+
+/// Where something is in a photo, as fractions (0-1) of the image width and height from the top left
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct BoundingBox {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// A label entry read from a product photo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelOcrEntry {
+    /// 1-based, in label order
+    pub position: usize,
+    /// What the model read, before cleaning
+    pub text: String,
+    pub name: String,
+    /// None when the dictionary does not know the name
+    pub ingredient: Option<Ingredient>,
+    /// How legible the model found the entry, 0-1
+    pub confidence: f64,
+    pub bounding_box: Option<BoundingBox>,
+    /// Low confidence or unknown to the dictionary: worth checking against the photo
+    pub needs_review: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelOcrResult {
+    pub entries: Vec<LabelOcrEntry>,
+    /// The entries as one comma-separated list, ready for `analyze_ingredient_label`
+    pub label_text: String,
+}
@@ -1,7 +1,17 @@
+pub mod compatibility;
 pub mod content;
+pub mod ingredient;
+pub mod label;
 pub mod project;
+pub mod regulation;
 pub mod settings;
+pub mod trend;
 
+pub use compatibility::*;
 pub use content::*;
+pub use ingredient::*;
+pub use label::*;
+pub use regulation::*;
 pub use settings::*;
+pub use trend::*;
 // Note: project types are accessed via crate::models::project::{...} to avoid name conflicts
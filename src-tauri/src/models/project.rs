@@ -1,4 +1,6 @@
+use super::label::LabelFlag;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -42,6 +44,9 @@ pub struct ProjectResearchReport {
     pub ingredient_analysis: Option<ProjectIngredientAnalysis>,
     pub papers: Vec<ProjectPaperResult>,
     pub sources: Vec<ProjectSourceReference>,
+    /// Whole-product ingredient list check, for research saved from a label
+    #[serde(default)]
+    pub label_analysis: Option<ProjectLabelAnalysis>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -53,6 +58,53 @@ pub struct ProjectIngredientAnalysis {
     pub benefits: Vec<String>,
     pub cautions: Vec<String>,
     pub recommended_concentration: Option<String>,
+    #[serde(default)]
+    pub benefit_evidence: Vec<ProjectBenefitEvidence>,
+    /// One line per sourced regulatory fact, e.g. "식품의약품안전처 별표 2: 최대 1% (보존제로서)"
+    #[serde(default)]
+    pub regulatory_notes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectLabelAnalysis {
+    pub product_name: Option<String>,
+    pub entries: Vec<ProjectLabelEntry>,
+    pub combinations: Vec<ProjectLabelCombination>,
+    pub unresolved: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectLabelEntry {
+    pub position: usize,
+    pub name: String,
+    pub inci_name: Option<String>,
+    pub korean_name: Option<String>,
+    pub flags: Vec<LabelFlag>,
+    /// One line per regulatory fact, e.g. "mfds_restricted: 1% (보존제로서)"
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectLabelCombination {
+    pub ingredients: Vec<String>,
+    pub reason: String,
+    #[serde(default)]
+    pub advice: Option<String>,
+    /// Title of the source the reason is based on
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectBenefitEvidence {
+    pub benefit: String,
+    pub paper_ids: Vec<String>,
+    pub grade: Option<String>,
+    pub weak_evidence: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,6 +120,21 @@ pub struct ProjectPaperResult {
     pub citation_count: Option<i32>,
     pub doi: Option<String>,
     pub url: Option<String>,
+    #[serde(default)]
+    pub open_access_url: Option<String>,
+    #[serde(default)]
+    pub evidence: Option<ProjectEvidenceAssessment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectEvidenceAssessment {
+    pub grade: String,
+    pub study_design: String,
+    pub subject: String,
+    pub sample_size: Option<u32>,
+    pub year: Option<i32>,
+    pub weak: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -89,6 +156,18 @@ pub struct ProjectContentGroup {
     pub research_item_ids: Vec<String>,
     pub contents: Vec<ProjectContentItem>,
     pub created_at: String,
+    /// Content item id -> layout template id; unassigned items use the slide-role template
+    #[serde(default)]
+    pub item_layout_templates: HashMap<String, String>,
+}
+
+impl ProjectContentGroup {
+    /// Template explicitly chosen for an item and the item's slide role within the group
+    pub fn layout_for_item(&self, content_id: &str) -> (Option<&str>, &'static str) {
+        let template_id = self.item_layout_templates.get(content_id).map(|s| s.as_str());
+        let index = self.contents.iter().position(|c| c.id == content_id).unwrap_or(0);
+        (template_id, super::settings::slide_role(index, self.contents.len()))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,98 @@
+use super::ingredient::Ingredient;
+use serde::{Deserialize, Serialize};
+
+/// Regulation or reference a rule comes from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegulatorySource {
+    pub id: String,
+    pub title: String,
+    pub issuer: String,
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Which part of the source, version or amendment, e.g. "[별표 2] 사용상의 제한이 필요한 원료"
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegulatoryList {
+    /// 화장품에 사용할 수 없는 원료
+    MfdsProhibited,
+    /// 사용상의 제한이 필요한 원료
+    MfdsRestricted,
+    /// 착향제 구성 성분 중 알레르기 유발 성분 (일정 함량을 넘으면 전성분에 표시)
+    MfdsAllergen,
+    /// EU prohibited substances
+    EuAnnexIi,
+    /// EU substances subject to restrictions
+    EuAnnexIii,
+    /// EU allowed preservatives
+    EuAnnexV,
+    /// EU allowed UV filters
+    EuAnnexVi,
+    PregnancyCaution,
+    InfantCaution,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegulatoryStatus {
+    Prohibited,
+    Restricted,
+    Caution,
+}
+
+/// One entry of a regulatory list, as stored in a dataset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegulatoryRule {
+    /// Ingredient dictionary id; imports may give any name the dictionary can resolve
+    pub ingredient_id: String,
+    pub list: RegulatoryList,
+    pub status: RegulatoryStatus,
+    #[serde(default)]
+    pub max_concentration: Option<String>,
+    #[serde(default)]
+    pub conditions: Option<String>,
+    /// Entry in the source, e.g. "Annex V/29", "별표 2"
+    #[serde(default)]
+    pub reference: Option<String>,
+    pub source_id: String,
+}
+
+/// File format of the bundled regulatory data and of imports
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RegulatoryDataset {
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub sources: Vec<RegulatorySource>,
+    #[serde(default)]
+    pub rules: Vec<RegulatoryRule>,
+}
+
+/// A rule that applies to an ingredient, with the source it is cited from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegulatoryFact {
+    pub list: RegulatoryList,
+    pub status: RegulatoryStatus,
+    pub max_concentration: Option<String>,
+    pub conditions: Option<String>,
+    pub reference: Option<String>,
+    pub source: RegulatorySource,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegulatoryReport {
+    /// None when the name is not in the ingredient dictionary (then there are no facts)
+    pub ingredient: Option<Ingredient>,
+    pub facts: Vec<RegulatoryFact>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegulatoryImportSummary {
+    pub sources: usize,
+    pub rules: usize,
+    /// Ingredient names in the file the dictionary does not know; their rules were skipped
+    pub unmatched_ingredients: Vec<String>,
+}
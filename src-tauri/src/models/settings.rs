@@ -11,6 +11,56 @@ pub struct AppSettings {
     pub layout_settings: LayoutSettings,
     #[serde(default)]
     pub image_size_presets: Vec<ImageSizePreset>,
+    #[serde(default)]
+    pub layout_templates: Vec<LayoutTemplate>,
+    #[serde(default)]
+    pub layout_roles: LayoutRoleAssignment,
+    #[serde(default)]
+    pub model_settings: ModelSettings,
+    #[serde(default)]
+    pub source_endpoints: SourceEndpoints,
+    #[serde(default)]
+    pub ncbi: NcbiSettings,
+    #[serde(default)]
+    pub news_feeds: NewsFeedRegistry,
+}
+
+impl AppSettings {
+    /// Layout for a slide: the explicitly chosen template, else the template assigned to the
+    /// slide's role, else the default `layout_settings`
+    pub fn resolve_layout(&self, template_id: Option<&str>, role: &str) -> &LayoutSettings {
+        let role_template = match role {
+            "cover" => self.layout_roles.cover.as_deref(),
+            "outro" => self.layout_roles.outro.as_deref(),
+            _ => self.layout_roles.body.as_deref(),
+        };
+
+        template_id
+            .and_then(|id| self.layout_templates.iter().find(|t| t.id == id))
+            .or_else(|| role_template.and_then(|id| self.layout_templates.iter().find(|t| t.id == id)))
+            .map(|t| &t.layout)
+            .unwrap_or(&self.layout_settings)
+    }
+
+    /// Image size preset selected by a layout (Instagram portrait when missing)
+    pub fn size_preset_for(&self, layout: &LayoutSettings) -> ImageSizePreset {
+        self.image_size_presets
+            .iter()
+            .find(|p| p.id == layout.selected_preset_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Slide role from its position in a carousel: first = "cover", last = "outro", others = "body"
+pub fn slide_role(index: usize, total: usize) -> &'static str {
+    if index == 0 && total > 1 {
+        "cover"
+    } else if total > 2 && index == total - 1 {
+        "outro"
+    } else {
+        "body"
+    }
 }
 
 // 이미지 크기 프리셋
@@ -46,6 +96,131 @@ pub struct LayoutElement {
     pub y: f32,
     pub width: f32,
     pub height: f32,
+    #[serde(default)]
+    pub style: ElementStyle,
+}
+
+// 레이아웃 요소 스타일 (None이면 요소 역할별 기본값 사용)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElementStyle {
+    #[serde(default)]
+    pub font_family: Option<String>, // get_system_fonts 목록의 글꼴 이름
+    #[serde(default)]
+    pub font_weight: Option<u16>, // 100-900
+    #[serde(default)]
+    pub font_size: Option<f32>, // 고정 크기 (px), 지정 시 자동 맞춤 범위 무시
+    #[serde(default)]
+    pub min_font_size: Option<f32>, // 자동 맞춤 최소 크기 (px)
+    #[serde(default)]
+    pub max_font_size: Option<f32>, // 자동 맞춤 최대 크기 (px)
+    #[serde(default)]
+    pub line_height: Option<f32>, // 글자 크기 배수
+    #[serde(default = "default_text_color")]
+    pub text_color: String,
+    #[serde(default = "default_align")]
+    pub align: String, // "left", "center", "right"
+    #[serde(default = "default_vertical_align")]
+    pub vertical_align: String, // "top", "middle", "bottom"
+    #[serde(default)]
+    pub padding: f32, // px
+    #[serde(default)]
+    pub stroke: Option<StrokeStyle>,
+    #[serde(default)]
+    pub shadow: Option<ShadowStyle>,
+    #[serde(default)]
+    pub fill: Option<FillStyle>,
+}
+
+fn default_text_color() -> String {
+    "#333333".to_string()
+}
+
+fn default_align() -> String {
+    "left".to_string()
+}
+
+fn default_vertical_align() -> String {
+    "top".to_string()
+}
+
+fn default_opacity() -> f32 {
+    1.0
+}
+
+impl Default for ElementStyle {
+    fn default() -> Self {
+        Self {
+            font_family: None,
+            font_weight: None,
+            font_size: None,
+            min_font_size: None,
+            max_font_size: None,
+            line_height: None,
+            text_color: default_text_color(),
+            align: default_align(),
+            vertical_align: default_vertical_align(),
+            padding: 0.0,
+            stroke: None,
+            shadow: None,
+            fill: None,
+        }
+    }
+}
+
+// 글자 외곽선
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrokeStyle {
+    pub color: String,
+    pub width: f32, // px
+}
+
+// 글자 그림자
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShadowStyle {
+    pub color: String,
+    #[serde(default = "default_opacity")]
+    pub opacity: f32, // 0-1
+    #[serde(default)]
+    pub offset_x: f32, // px
+    #[serde(default)]
+    pub offset_y: f32, // px
+    #[serde(default)]
+    pub blur: f32, // px
+}
+
+// 요소 배경 채우기
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FillStyle {
+    pub color: String,
+    #[serde(default = "default_opacity")]
+    pub opacity: f32, // 0-1
+    #[serde(default = "default_fill_shape")]
+    pub shape: String, // "rect": 요소 박스 전체, "pill": 글자 영역에 맞춘 둥근 배경
+    #[serde(default)]
+    pub corner_radius: f32, // px ("rect"에만 적용)
+}
+
+fn default_fill_shape() -> String {
+    "rect".to_string()
+}
+
+// 이름을 붙여 저장한 레이아웃 템플릿
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutTemplate {
+    pub id: String,
+    pub name: String,
+    pub layout: LayoutSettings,
+}
+
+// 슬라이드 역할별 템플릿 지정 (None이면 기본 레이아웃 사용)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LayoutRoleAssignment {
+    #[serde(default)]
+    pub cover: Option<String>, // 첫 장 (표지)
+    #[serde(default)]
+    pub body: Option<String>, // 본문
+    #[serde(default)]
+    pub outro: Option<String>, // 마지막 장 (CTA)
 }
 
 // 레이아웃 설정
@@ -71,6 +246,10 @@ impl Default for LayoutSettings {
                     y: 5.0,
                     width: 50.0,
                     height: 10.0,
+                    style: ElementStyle {
+                        font_weight: Some(700),
+                        ..ElementStyle::default()
+                    },
                 },
                 LayoutElement {
                     id: "subtitle".to_string(),
@@ -83,6 +262,10 @@ impl Default for LayoutSettings {
                     y: 17.0,
                     width: 50.0,
                     height: 8.0,
+                    style: ElementStyle {
+                        font_weight: Some(600),
+                        ..ElementStyle::default()
+                    },
                 },
                 LayoutElement {
                     id: "short_knowledge".to_string(),
@@ -95,6 +278,10 @@ impl Default for LayoutSettings {
                     y: 75.0,
                     width: 45.0,
                     height: 20.0,
+                    style: ElementStyle {
+                        line_height: Some(1.4),
+                        ..ElementStyle::default()
+                    },
                 },
                 LayoutElement {
                     id: "hero_image".to_string(),
@@ -107,6 +294,7 @@ impl Default for LayoutSettings {
                     y: 25.0,
                     width: 45.0,
                     height: 50.0,
+                    style: ElementStyle::default(),
                 },
                 LayoutElement {
                     id: "background".to_string(),
@@ -119,12 +307,40 @@ impl Default for LayoutSettings {
                     y: 0.0,
                     width: 100.0,
                     height: 100.0,
+                    style: ElementStyle::default(),
                 },
             ],
         }
     }
 }
 
+// 텍스트 박스 배치 미리보기 요청 (px 단위)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextLayoutRequest {
+    pub text: String,
+    pub width: f32,
+    pub height: f32,
+    pub font_family: Option<String>,
+    pub font_weight: Option<u16>,
+    pub min_font_size: Option<f32>,
+    pub max_font_size: Option<f32>,
+    pub line_height: Option<f32>,
+    pub align: Option<String>,          // "left", "center", "right"
+    pub vertical_align: Option<String>, // "top", "middle", "bottom"
+}
+
+// 텍스트 박스 배치 결과
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextLayoutPreview {
+    pub font_size: f32,
+    pub lines: Vec<String>,
+    pub truncated: bool,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ApiKeys {
     pub google: Option<String>,
@@ -147,6 +363,151 @@ impl Default for ApiSelection {
     }
 }
 
+/// Model per task plus generation parameters for one provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderModels {
+    pub text: String,
+    pub vision: String,
+    #[serde(default)]
+    pub image: Option<String>, // None = provider has no image generation
+    #[serde(default = "default_temperature")]
+    pub temperature: f32,
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: u32,
+}
+
+fn default_temperature() -> f32 {
+    0.7
+}
+
+fn default_max_tokens() -> u32 {
+    4096
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelSettings {
+    pub openai: ProviderModels,
+    pub anthropic: ProviderModels,
+    pub google: ProviderModels,
+}
+
+impl Default for ModelSettings {
+    fn default() -> Self {
+        Self {
+            openai: ProviderModels {
+                text: "gpt-4o-mini".to_string(),
+                vision: "gpt-4o".to_string(),
+                image: Some("dall-e-3".to_string()),
+                temperature: default_temperature(),
+                max_tokens: default_max_tokens(),
+            },
+            anthropic: ProviderModels {
+                text: "claude-3-5-sonnet-20241022".to_string(),
+                vision: "claude-3-5-sonnet-20241022".to_string(),
+                image: None,
+                temperature: default_temperature(),
+                max_tokens: default_max_tokens(),
+            },
+            google: ProviderModels {
+                text: "gemini-2.0-flash".to_string(),
+                vision: "gemini-2.0-flash".to_string(),
+                image: Some("imagen-4.0-generate-001".to_string()),
+                temperature: default_temperature(),
+                max_tokens: default_max_tokens(),
+            },
+        }
+    }
+}
+
+/// Base URL overrides for research sources, e.g. to point them at a local fixture server.
+/// `None` uses the public endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct SourceEndpoints {
+    pub autocomplete: Option<String>,
+    pub pubmed: Option<String>,
+    pub crossref: Option<String>,
+    /// OpenCitations index API (citation counts)
+    pub opencitations: Option<String>,
+    /// Unpaywall API (open-access locations)
+    pub unpaywall: Option<String>,
+    /// PMC ID converter (PMID/PMCID/DOI cross-links)
+    pub idconv: Option<String>,
+}
+
+/// E-utilities identification. With an API key NCBI allows 10 requests/s instead of 3;
+/// `tool` and `email` let NCBI contact the developer instead of blocking the client.
+/// The email is also the contact Unpaywall requires for open-access lookups.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct NcbiSettings {
+    pub api_key: Option<String>,
+    pub tool: Option<String>,
+    pub email: Option<String>,
+}
+
+/// A news feed (RSS 2.0 or Atom) searched by `search_news`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewsFeed {
+    pub id: String,
+    /// Shown as the source of its articles, e.g. "연합뉴스"
+    pub name: String,
+    pub url: String,
+    /// Free-form group used to narrow searches, e.g. "economy", "beauty", "regulation"
+    pub category: String,
+    pub enabled: bool,
+}
+
+/// The user's feed list; starts with the Yonhap and CNN feeds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NewsFeedRegistry {
+    pub feeds: Vec<NewsFeed>,
+}
+
+impl Default for NewsFeedRegistry {
+    fn default() -> Self {
+        let feed = |id: &str, name: &str, url: &str, category: &str| NewsFeed {
+            id: id.to_string(),
+            name: name.to_string(),
+            url: url.to_string(),
+            category: category.to_string(),
+            enabled: true,
+        };
+        Self {
+            feeds: vec![
+                feed("yonhap-all", "연합뉴스", "https://www.yna.co.kr/rss/news.xml", "general"),
+                feed("yonhap-economy", "연합뉴스", "https://www.yna.co.kr/rss/economy.xml", "economy"),
+                feed("yonhap-science", "연합뉴스", "https://www.yna.co.kr/rss/science.xml", "science"),
+                feed("cnn-top", "CNN", "http://rss.cnn.com/rss/edition.rss", "general"),
+                feed("cnn-world", "CNN", "http://rss.cnn.com/rss/edition_world.rss", "world"),
+                feed("cnn-technology", "CNN", "http://rss.cnn.com/rss/edition_technology.rss", "technology"),
+            ],
+        }
+    }
+}
+
+/// What `test_news_feed` found at a URL
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewsFeedTest {
+    pub format: String, // "rss", "atom"
+    pub title: Option<String>,
+    pub item_count: usize,
+    pub sample_titles: Vec<String>,
+}
+
+/// A model offered by a provider's model-listing endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelInfo {
+    pub id: String,
+    pub display_name: Option<String>,
+    pub tasks: Vec<String>, // "text", "vision", "image"
+    /// Reasoning model: takes no temperature and limits output with `max_completion_tokens`
+    #[serde(default)]
+    pub reasoning: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImagePrompt {
     pub id: String,
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Period size of a trend series: news is counted per day, literature per publication year
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TrendGranularity {
+    Day,
+    Year,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendPoint {
+    pub period: String, // "2024-05-13" (day) or "2024" (year)
+    pub count: u64,
+}
+
+/// How often a keyword appeared in one source, oldest period first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendSeries {
+    pub source: String, // "news", "pubmed", "crossref"
+    pub granularity: TrendGranularity,
+    pub points: Vec<TrendPoint>,
+}
+
+/// Everything recorded for one keyword
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeywordHistory {
+    pub keyword: String,
+    pub updated_at: String,
+    #[serde(default)]
+    pub series: Vec<TrendSeries>,
+    /// Links of news items already counted, so re-fetching a feed does not count them twice
+    #[serde(default)]
+    pub seen_news: Vec<String>,
+}
+
+/// Local time-series store, persisted as keyword_trends.json in the app data directory
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendStore {
+    /// Keyed by the normalized (lower-case, single-spaced) keyword
+    #[serde(default)]
+    pub keywords: BTreeMap<String, KeywordHistory>,
+}
+
+/// Series for charting plus the label derived from them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeywordTrend {
+    pub keyword: String,
+    pub trend: String, // "rising", "stable", "hot"
+    /// Recent activity relative to the baseline (1.0 = unchanged); None without enough history
+    pub growth: Option<f64>,
+    pub updated_at: Option<String>,
+    pub series: Vec<TrendSeries>,
+}
@@ -0,0 +1,133 @@
+use image::{imageops, GrayImage, Luma, Rgba, RgbaImage};
+
+/// Parse "#RGB", "#RRGGBB" or "#RRGGBBAA" and apply an extra 0-1 opacity
+pub fn parse_color(hex: &str, opacity: f32) -> Result<Rgba<u8>, String> {
+    let digits = hex.trim().trim_start_matches('#');
+    let invalid = || format!("잘못된 색상 값입니다: {}", hex);
+    // 바이트 단위로 자르기 전에 16진수 ASCII만 있는지 확인합니다
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+    let channel = |s: &str| u8::from_str_radix(s, 16).map_err(|_| invalid());
+
+    let (r, g, b, a) = match digits.len() {
+        3 => {
+            let expand = |i: usize| channel(&digits[i..i + 1].repeat(2));
+            (expand(0)?, expand(1)?, expand(2)?, 255)
+        }
+        6 => (channel(&digits[0..2])?, channel(&digits[2..4])?, channel(&digits[4..6])?, 255),
+        8 => (
+            channel(&digits[0..2])?,
+            channel(&digits[2..4])?,
+            channel(&digits[4..6])?,
+            channel(&digits[6..8])?,
+        ),
+        _ => return Err(invalid()),
+    };
+
+    let alpha = (a as f32 * opacity.clamp(0.0, 1.0)).round() as u8;
+    Ok(Rgba([r, g, b, alpha]))
+}
+
+/// Paint `color` through a coverage mask whose top-left corner sits at (x, y)
+pub fn paint_mask(canvas: &mut RgbaImage, mask: &GrayImage, x: i32, y: i32, color: Rgba<u8>) {
+    for (mx, my, coverage) in mask.enumerate_pixels() {
+        if coverage[0] == 0 {
+            continue;
+        }
+        super::blend_pixel(canvas, x + mx as i32, y + my as i32, color, coverage[0] as f32 / 255.0);
+    }
+}
+
+/// Grow a mask by `radius` px (used for text outlines)
+pub fn dilate(mask: &GrayImage, radius: f32) -> GrayImage {
+    let r = radius.ceil() as i32;
+    if r <= 0 {
+        return mask.clone();
+    }
+
+    let offsets: Vec<(i32, i32)> = (-r..=r)
+        .flat_map(|dy| (-r..=r).map(move |dx| (dx, dy)))
+        .filter(|(dx, dy)| ((dx * dx + dy * dy) as f32).sqrt() <= radius + 0.5)
+        .collect();
+
+    let (w, h) = mask.dimensions();
+    GrayImage::from_fn(w, h, |x, y| {
+        let mut max = 0u8;
+        for (dx, dy) in &offsets {
+            let (sx, sy) = (x as i32 + dx, y as i32 + dy);
+            if sx >= 0 && sy >= 0 && (sx as u32) < w && (sy as u32) < h {
+                max = max.max(mask.get_pixel(sx as u32, sy as u32)[0]);
+                if max == 255 {
+                    break;
+                }
+            }
+        }
+        Luma([max])
+    })
+}
+
+/// Gaussian-blur a mask (used for soft drop shadows)
+pub fn blur(mask: &GrayImage, radius: f32) -> GrayImage {
+    if radius <= 0.0 {
+        return mask.clone();
+    }
+    // CSS-style blur radius is roughly two standard deviations
+    imageops::blur(mask, radius / 2.0)
+}
+
+/// Fill an anti-aliased rounded rectangle
+pub fn fill_rounded_rect(canvas: &mut RgbaImage, x: f32, y: f32, width: f32, height: f32, radius: f32, color: Rgba<u8>) {
+    if width <= 0.0 || height <= 0.0 {
+        return;
+    }
+
+    let radius = radius.clamp(0.0, width.min(height) / 2.0);
+    let x0 = x.floor().max(0.0) as u32;
+    let y0 = y.floor().max(0.0) as u32;
+    let x1 = ((x + width).ceil() as u32).min(canvas.width());
+    let y1 = ((y + height).ceil() as u32).min(canvas.height());
+
+    for py in y0..y1 {
+        for px in x0..x1 {
+            // Sample at the pixel center
+            let cx = px as f32 + 0.5;
+            let cy = py as f32 + 0.5;
+
+            // Distance outside the rounded rect (negative inside)
+            let qx = (cx - (x + width / 2.0)).abs() - (width / 2.0 - radius);
+            let qy = (cy - (y + height / 2.0)).abs() - (height / 2.0 - radius);
+            let outside = (qx.max(0.0).powi(2) + qy.max(0.0).powi(2)).sqrt() + qx.max(qy).min(0.0) - radius;
+
+            let coverage = (0.5 - outside).clamp(0.0, 1.0);
+            if coverage > 0.0 {
+                super::blend_pixel(canvas, px as i32, py as i32, color, coverage);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_short_long_and_alpha_forms() {
+        assert_eq!(parse_color("#f0a", 1.0), Ok(Rgba([0xff, 0x00, 0xaa, 255])));
+        assert_eq!(parse_color(" #1A2b3C ", 1.0), Ok(Rgba([0x1a, 0x2b, 0x3c, 255])));
+        assert_eq!(parse_color("11223380", 1.0), Ok(Rgba([0x11, 0x22, 0x33, 0x80])));
+    }
+
+    #[test]
+    fn applies_opacity_to_alpha() {
+        assert_eq!(parse_color("#000000", 0.5), Ok(Rgba([0, 0, 0, 128])));
+        assert_eq!(parse_color("#000000", 2.0), Ok(Rgba([0, 0, 0, 255])));
+    }
+
+    #[test]
+    fn rejects_invalid_input_without_panicking() {
+        for hex in ["", "#", "#12", "#12345", "#ggg", "#é12", "#1é2", "#가나다", "#12345é", "#+1+2+3"] {
+            assert!(parse_color(hex, 1.0).is_err(), "{hex:?} should be rejected");
+        }
+    }
+}
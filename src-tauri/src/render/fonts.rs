@@ -0,0 +1,194 @@
+use ab_glyph::FontArc;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// A single face found in a system font file (TTC files yield several)
+#[derive(Debug, Clone)]
+pub struct FontFace {
+    pub family: String,
+    pub path: PathBuf,
+    pub index: u32,
+    pub weight: u16,
+    pub italic: bool,
+}
+
+// 슬라이드 렌더링 시 글꼴이 지정되지 않았을 때 우선 사용할 한글 글꼴
+const DEFAULT_KOREAN_FAMILIES: &[&str] = &[
+    "맑은 고딕",
+    "Malgun Gothic",
+    "Apple SD Gothic Neo",
+    "나눔고딕",
+    "NanumGothic",
+    "Noto Sans KR",
+    "Noto Sans CJK KR",
+];
+
+/// Directories that hold installed fonts on the current platform
+pub fn system_font_dirs() -> Vec<PathBuf> {
+    #[allow(unused_mut)]
+    let mut dirs_list: Vec<PathBuf> = Vec::new();
+
+    // Windows fonts directories
+    #[cfg(target_os = "windows")]
+    {
+        // System fonts
+        if let Ok(windows_dir) = std::env::var("WINDIR") {
+            dirs_list.push(PathBuf::from(&windows_dir).join("Fonts"));
+        }
+
+        // User fonts
+        if let Ok(local_app_data) = std::env::var("LOCALAPPDATA") {
+            dirs_list.push(
+                PathBuf::from(&local_app_data)
+                    .join("Microsoft")
+                    .join("Windows")
+                    .join("Fonts"),
+            );
+        }
+    }
+
+    // macOS fonts directories
+    #[cfg(target_os = "macos")]
+    {
+        dirs_list.push(PathBuf::from("/System/Library/Fonts"));
+        dirs_list.push(PathBuf::from("/Library/Fonts"));
+        dirs_list.push(dirs::home_dir().map(|h| h.join("Library/Fonts")).unwrap_or_default());
+    }
+
+    // Linux fonts directories
+    #[cfg(target_os = "linux")]
+    {
+        dirs_list.push(PathBuf::from("/usr/share/fonts"));
+        dirs_list.push(PathBuf::from("/usr/local/share/fonts"));
+        dirs_list.push(dirs::home_dir().map(|h| h.join(".fonts")).unwrap_or_default());
+        dirs_list.push(dirs::home_dir().map(|h| h.join(".local/share/fonts")).unwrap_or_default());
+    }
+
+    dirs_list.into_iter().filter(|d| d.exists()).collect()
+}
+
+/// Scan every system font directory and return all faces found
+pub fn scan_system_fonts() -> Vec<FontFace> {
+    let mut faces = Vec::new();
+    for dir in system_font_dirs() {
+        collect_faces_from_dir(&dir, &mut faces);
+    }
+    faces
+}
+
+/// Cached result of `scan_system_fonts` for the renderer
+pub fn system_fonts() -> &'static [FontFace] {
+    static FACES: OnceLock<Vec<FontFace>> = OnceLock::new();
+    FACES.get_or_init(scan_system_fonts)
+}
+
+fn collect_faces_from_dir(dir: &Path, faces: &mut Vec<FontFace>) {
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+
+            if path.is_dir() {
+                // Recursively search subdirectories
+                collect_faces_from_dir(&path, faces);
+            } else if let Some(ext) = path.extension() {
+                let ext_lower = ext.to_string_lossy().to_lowercase();
+                if ext_lower != "ttf" && ext_lower != "otf" && ext_lower != "ttc" {
+                    continue;
+                }
+
+                let Ok(data) = fs::read(&path) else { continue };
+
+                // TTC files can contain multiple fonts
+                let count = ttf_parser::fonts_in_collection(&data).unwrap_or(1);
+                for index in 0..count {
+                    if let Ok(face) = ttf_parser::Face::parse(&data, index) {
+                        if let Some(family) = extract_family_name(&face) {
+                            if family.is_empty() {
+                                continue;
+                            }
+                            faces.push(FontFace {
+                                family,
+                                path: path.clone(),
+                                index,
+                                weight: face.weight().to_number(),
+                                italic: face.is_italic(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Extract font family name from parsed font face
+pub fn extract_family_name(face: &ttf_parser::Face) -> Option<String> {
+    // Try to find the font family name in the naming table
+    // Priority: Windows platform with Korean (1042), then English (1033), then any
+    let names = face.names();
+
+    // Name ID 1 = Font Family Name
+    // Name ID 16 = Typographic Family Name (preferred)
+
+    // First try typographic family name (ID 16)
+    let mut family_name: Option<String> = None;
+
+    // Try to get Korean name first (platform 3, encoding 1, language 1042)
+    for name in names.into_iter() {
+        if name.name_id == ttf_parser::name_id::TYPOGRAPHIC_FAMILY || name.name_id == ttf_parser::name_id::FAMILY {
+            if let Some(name_str) = name.to_string() {
+                // Prefer Korean name if available
+                if name.language_id == 1042 {
+                    return Some(name_str);
+                }
+                // Otherwise use English or first available
+                if family_name.is_none() || name.language_id == 1033 {
+                    family_name = Some(name_str);
+                }
+            }
+        }
+    }
+
+    family_name
+}
+
+/// Find the upright face of `family` whose weight is closest to `weight`
+pub fn find_face(family: &str, weight: u16) -> Option<&'static FontFace> {
+    let family_lower = family.to_lowercase();
+    system_fonts()
+        .iter()
+        .filter(|f| f.family.to_lowercase() == family_lower)
+        .min_by_key(|f| (f.italic, (f.weight as i32 - weight as i32).abs()))
+}
+
+/// Find a face for the first installed family among the default Korean fonts
+pub fn default_face(weight: u16) -> Option<&'static FontFace> {
+    DEFAULT_KOREAN_FAMILIES
+        .iter()
+        .find_map(|family| find_face(family, weight))
+}
+
+/// Load a face into memory, reusing fonts that were already loaded
+pub fn load_font(face: &FontFace) -> Result<FontArc, String> {
+    static LOADED: OnceLock<Mutex<HashMap<(PathBuf, u32), FontArc>>> = OnceLock::new();
+    let cache = LOADED.get_or_init(|| Mutex::new(HashMap::new()));
+    let key = (face.path.clone(), face.index);
+
+    if let Some(font) = cache.lock().map_err(|e| e.to_string())?.get(&key) {
+        return Ok(font.clone());
+    }
+
+    let data = fs::read(&face.path)
+        .map_err(|e| format!("글꼴 파일을 읽을 수 없습니다 ({}): {}", face.path.display(), e))?;
+    let font = ab_glyph::FontVec::try_from_vec_and_index(data, face.index)
+        .map(FontArc::new)
+        .map_err(|e| format!("글꼴을 불러올 수 없습니다 ({}): {}", face.family, e))?;
+
+    cache
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(key, font.clone());
+    Ok(font)
+}
@@ -0,0 +1,569 @@
+// Server-side carousel slide rendering
+
+pub mod effects;
+pub mod fonts;
+pub mod text;
+
+use crate::models::project::ProjectContentItem;
+use crate::models::{ElementStyle, ImageSizePreset, LayoutElement, LayoutSettings};
+use image::{imageops, Rgba, RgbaImage};
+use std::path::Path;
+use text::{FontStack, TextAlign, TextStyle, VerticalAlign};
+
+const CANVAS_COLOR: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+/// Text drawn into the text-type boxes of a slide
+#[derive(Debug, Clone)]
+pub struct SlideText {
+    pub title: String,
+    pub subtitle: String,
+    pub short_knowledge: String,
+}
+
+impl SlideText {
+    /// Same mapping as the layout editor preview: journal header, content title, body
+    pub fn from_content(item: &ProjectContentItem) -> Self {
+        Self {
+            title: format!("{}의 연구일지 #{}", item.character_name, item.journal_number),
+            subtitle: item.title.clone(),
+            short_knowledge: item.content.clone(),
+        }
+    }
+
+    fn for_element<'a>(&'a self, element: &'a LayoutElement) -> Option<&'a str> {
+        let text = match element.id.as_str() {
+            "title" => self.title.as_str(),
+            "subtitle" => self.subtitle.as_str(),
+            "short_knowledge" => self.short_knowledge.as_str(),
+            // Custom text boxes carry their fixed text in the prompt field
+            _ => element.prompt.as_str(),
+        };
+        if text.trim().is_empty() {
+            None
+        } else {
+            Some(text)
+        }
+    }
+}
+
+/// Pixel rectangle of a layout element on the canvas
+#[derive(Debug, Clone, Copy)]
+pub struct BoxRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl BoxRect {
+    /// Convert the element's 0-100 (%) box to pixels, clamped to the canvas
+    pub fn from_element(element: &LayoutElement, canvas_width: u32, canvas_height: u32) -> Option<Self> {
+        let to_px = |percent: f32, total: u32| ((percent.clamp(0.0, 100.0) / 100.0) * total as f32).round() as u32;
+
+        let x = to_px(element.x, canvas_width);
+        let y = to_px(element.y, canvas_height);
+        let width = to_px(element.width, canvas_width).min(canvas_width - x);
+        let height = to_px(element.height, canvas_height).min(canvas_height - y);
+
+        if width == 0 || height == 0 {
+            None
+        } else {
+            Some(Self { x, y, width, height })
+        }
+    }
+
+    /// Shrink the rect by `padding` px on every side
+    fn inset(self, padding: f32) -> Option<Self> {
+        let p = padding.max(0.0).round() as u32;
+        if self.width <= p * 2 || self.height <= p * 2 {
+            return None;
+        }
+        Some(Self {
+            x: self.x + p,
+            y: self.y + p,
+            width: self.width - p * 2,
+            height: self.height - p * 2,
+        })
+    }
+}
+
+/// Composite one slide: image boxes first (largest first), then text boxes on top
+pub fn render_slide(
+    layout: &LayoutSettings,
+    preset: &ImageSizePreset,
+    text: &SlideText,
+    image: Option<&RgbaImage>,
+) -> Result<RgbaImage, String> {
+    if preset.width == 0 || preset.height == 0 {
+        return Err(format!("잘못된 이미지 크기입니다: {}x{}", preset.width, preset.height));
+    }
+
+    let mut canvas = RgbaImage::from_pixel(preset.width, preset.height, CANVAS_COLOR);
+
+    let mut image_elements: Vec<(&LayoutElement, BoxRect)> = layout
+        .elements
+        .iter()
+        .filter(|e| e.enabled && e.element_type == "image")
+        .filter_map(|e| BoxRect::from_element(e, preset.width, preset.height).map(|r| (e, r)))
+        .collect();
+    image_elements.sort_by_key(|(_, r)| std::cmp::Reverse(r.width as u64 * r.height as u64));
+
+    // The background box only receives the generated image when it is the sole image box
+    let has_foreground_image = image_elements.iter().any(|(e, _)| e.id != "background");
+
+    for (element, rect) in &image_elements {
+        draw_box_fill(&mut canvas, &element.style, *rect)?;
+
+        if let Some(source) = image {
+            if element.id == "background" && has_foreground_image {
+                continue;
+            }
+            if let Some(inner) = rect.inset(element.style.padding) {
+                draw_image_cover(&mut canvas, source, inner);
+            }
+        }
+    }
+
+    let text_elements: Vec<&LayoutElement> = layout
+        .elements
+        .iter()
+        .filter(|e| e.enabled && e.element_type == "text")
+        .collect();
+
+    for element in text_elements {
+        let Some(content) = text.for_element(element) else { continue };
+        let Some(rect) = BoxRect::from_element(element, preset.width, preset.height) else { continue };
+        draw_text_element(&mut canvas, element, content, rect)?;
+    }
+
+    Ok(canvas)
+}
+
+/// Role defaults matching the layout editor preview, overridden by the element's own style
+fn text_style_for(element: &LayoutElement, inner: BoxRect) -> TextStyle {
+    let style = &element.style;
+    let (role_weight, role_line_height) = match element.id.as_str() {
+        "title" => (700, 1.3),
+        "subtitle" => (600, 1.3),
+        _ => (400, 1.4),
+    };
+
+    let (min_font_size, max_font_size) = match style.font_size {
+        Some(size) => (size, size),
+        None => (
+            style.min_font_size.unwrap_or(14.0),
+            style
+                .max_font_size
+                .unwrap_or_else(|| (inner.height as f32).clamp(14.0, 96.0)),
+        ),
+    };
+
+    TextStyle {
+        font_family: style.font_family.clone(),
+        font_weight: style.font_weight.unwrap_or(role_weight),
+        min_font_size,
+        max_font_size,
+        line_height: style.line_height.unwrap_or(role_line_height),
+        align: TextAlign::parse(&style.align),
+        vertical_align: VerticalAlign::parse(&style.vertical_align),
+    }
+}
+
+/// Draw the "rect" fill that covers the whole element box
+fn draw_box_fill(canvas: &mut RgbaImage, style: &ElementStyle, rect: BoxRect) -> Result<(), String> {
+    if let Some(fill) = style.fill.as_ref().filter(|f| f.shape != "pill") {
+        let color = effects::parse_color(&fill.color, fill.opacity)?;
+        effects::fill_rounded_rect(
+            canvas,
+            rect.x as f32,
+            rect.y as f32,
+            rect.width as f32,
+            rect.height as f32,
+            fill.corner_radius,
+            color,
+        );
+    }
+    Ok(())
+}
+
+/// Fill, shadow, outline and glyphs of one text box, in that order
+fn draw_text_element(canvas: &mut RgbaImage, element: &LayoutElement, content: &str, rect: BoxRect) -> Result<(), String> {
+    let style = &element.style;
+    draw_box_fill(canvas, style, rect)?;
+
+    let Some(inner) = rect.inset(style.padding) else { return Ok(()) };
+    let text_style = text_style_for(element, inner);
+    let stack = FontStack::resolve(text_style.font_family.as_deref(), text_style.font_weight)?;
+    let layout = text::layout_text(&stack, content, &text_style, inner.width as f32, inner.height as f32);
+
+    // Pill background hugging the laid out text, extended by the padding
+    if let Some(fill) = style.fill.as_ref().filter(|f| f.shape == "pill") {
+        let (bx, by, bw, bh) = layout.bounds;
+        let pad = style.padding.max(0.0);
+        let height = bh + pad * 2.0;
+        effects::fill_rounded_rect(
+            canvas,
+            inner.x as f32 + bx - pad,
+            inner.y as f32 + by - pad,
+            bw + pad * 2.0,
+            height,
+            height / 2.0,
+            effects::parse_color(&fill.color, fill.opacity)?,
+        );
+    }
+
+    let stroke_width = style.stroke.as_ref().map(|s| s.width.max(0.0)).unwrap_or(0.0);
+    let shadow_blur = style.shadow.as_ref().map(|s| s.blur.max(0.0)).unwrap_or(0.0);
+
+    // Room around the box so outlines, blur and glyph overhang are not clipped
+    let margin = (stroke_width + shadow_blur * 1.5 + layout.font_size * 0.25).ceil();
+    let raster = text::rasterize(
+        &stack,
+        &layout,
+        inner.width + margin as u32 * 2,
+        inner.height + margin as u32 * 2,
+        margin,
+    );
+    let origin_x = inner.x as i32 - margin as i32;
+    let origin_y = inner.y as i32 - margin as i32;
+
+    let silhouette = raster.silhouette();
+    let outlined = style.stroke.as_ref().map(|_| effects::dilate(&silhouette, stroke_width));
+
+    if let Some(shadow) = &style.shadow {
+        let blurred = effects::blur(outlined.as_ref().unwrap_or(&silhouette), shadow.blur);
+        effects::paint_mask(
+            canvas,
+            &blurred,
+            origin_x + shadow.offset_x.round() as i32,
+            origin_y + shadow.offset_y.round() as i32,
+            effects::parse_color(&shadow.color, shadow.opacity)?,
+        );
+    }
+
+    if let (Some(stroke), Some(outline)) = (&style.stroke, &outlined) {
+        effects::paint_mask(canvas, outline, origin_x, origin_y, effects::parse_color(&stroke.color, 1.0)?);
+    }
+
+    effects::paint_mask(canvas, &raster.mask, origin_x, origin_y, effects::parse_color(&style.text_color, 1.0)?);
+    imageops::overlay(canvas, &raster.color, origin_x as i64, origin_y as i64);
+    Ok(())
+}
+
+/// Check element boxes and styles before they are saved, so rendering never fails on bad settings
+pub fn validate_layout(layout: &LayoutSettings) -> Result<(), String> {
+    if layout.selected_preset_id.trim().is_empty() {
+        return Err("이미지 크기 프리셋이 선택되지 않았습니다.".to_string());
+    }
+
+    let mut seen_ids = std::collections::HashSet::new();
+    for element in &layout.elements {
+        let name = if element.name.is_empty() { &element.id } else { &element.name };
+
+        if !seen_ids.insert(element.id.as_str()) {
+            return Err(format!("중복된 레이아웃 요소 ID입니다: {}", element.id));
+        }
+        if element.element_type != "text" && element.element_type != "image" {
+            return Err(format!("{}: 요소 유형은 text 또는 image여야 합니다.", name));
+        }
+
+        let in_range = |v: f32| (0.0..=100.0).contains(&v);
+        if !in_range(element.x) || !in_range(element.y) || !in_range(element.width) || !in_range(element.height) {
+            return Err(format!("{}: 위치와 크기는 0-100% 범위여야 합니다.", name));
+        }
+        if element.x + element.width > 100.5 || element.y + element.height > 100.5 {
+            return Err(format!("{}: 요소가 캔버스 밖으로 벗어났습니다.", name));
+        }
+
+        validate_style(name, &element.style)?;
+    }
+
+    Ok(())
+}
+
+fn validate_style(name: &str, style: &ElementStyle) -> Result<(), String> {
+    let invalid = |field: &str| Err(format!("{}: {} 값이 올바르지 않습니다.", name, field));
+    let check_color = |color: &str| effects::parse_color(color, 1.0).map(|_| ()).map_err(|e| format!("{}: {}", name, e));
+
+    if let Some(family) = style.font_family.as_deref().filter(|f| !f.trim().is_empty()) {
+        let installed = fonts::system_fonts()
+            .iter()
+            .any(|f| f.family.eq_ignore_ascii_case(family));
+        if !installed {
+            return Err(format!("{}: 설치되지 않은 글꼴입니다: {}", name, family));
+        }
+    }
+    if let Some(weight) = style.font_weight {
+        if !(100..=900).contains(&weight) {
+            return invalid("글꼴 굵기");
+        }
+    }
+    for size in [style.font_size, style.min_font_size, style.max_font_size].into_iter().flatten() {
+        if !(4.0..=400.0).contains(&size) {
+            return invalid("글자 크기");
+        }
+    }
+    if let (Some(min), Some(max)) = (style.min_font_size, style.max_font_size) {
+        if min > max {
+            return Err(format!("{}: 최소 글자 크기가 최대 글자 크기보다 큽니다.", name));
+        }
+    }
+    if let Some(line_height) = style.line_height {
+        if !(0.5..=4.0).contains(&line_height) {
+            return invalid("줄 간격");
+        }
+    }
+    if !matches!(style.align.as_str(), "left" | "center" | "right") {
+        return invalid("가로 정렬");
+    }
+    if !matches!(style.vertical_align.as_str(), "top" | "middle" | "bottom") {
+        return invalid("세로 정렬");
+    }
+    if !(0.0..=200.0).contains(&style.padding) {
+        return invalid("여백");
+    }
+
+    check_color(&style.text_color)?;
+
+    if let Some(stroke) = &style.stroke {
+        check_color(&stroke.color)?;
+        if !(0.0..=40.0).contains(&stroke.width) {
+            return invalid("외곽선 두께");
+        }
+    }
+    if let Some(shadow) = &style.shadow {
+        check_color(&shadow.color)?;
+        if !(0.0..=1.0).contains(&shadow.opacity) {
+            return invalid("그림자 투명도");
+        }
+        if !(0.0..=100.0).contains(&shadow.blur) || shadow.offset_x.abs() > 200.0 || shadow.offset_y.abs() > 200.0 {
+            return invalid("그림자");
+        }
+    }
+    if let Some(fill) = &style.fill {
+        check_color(&fill.color)?;
+        if !(0.0..=1.0).contains(&fill.opacity) {
+            return invalid("배경 투명도");
+        }
+        if fill.shape != "rect" && fill.shape != "pill" {
+            return invalid("배경 모양");
+        }
+        if fill.corner_radius < 0.0 {
+            return invalid("모서리 둥글기");
+        }
+    }
+
+    Ok(())
+}
+
+/// Scale `source` to cover `rect` (center crop) and paste it onto the canvas
+fn draw_image_cover(canvas: &mut RgbaImage, source: &RgbaImage, rect: BoxRect) {
+    let (src_w, src_h) = source.dimensions();
+    if src_w == 0 || src_h == 0 {
+        return;
+    }
+
+    let scale = f32::max(rect.width as f32 / src_w as f32, rect.height as f32 / src_h as f32);
+    let scaled_w = ((src_w as f32 * scale).ceil() as u32).max(rect.width);
+    let scaled_h = ((src_h as f32 * scale).ceil() as u32).max(rect.height);
+
+    let scaled = imageops::resize(source, scaled_w, scaled_h, imageops::FilterType::Lanczos3);
+    let crop_x = (scaled_w - rect.width) / 2;
+    let crop_y = (scaled_h - rect.height) / 2;
+    let cropped = imageops::crop_imm(&scaled, crop_x, crop_y, rect.width, rect.height).to_image();
+
+    imageops::overlay(canvas, &cropped, rect.x as i64, rect.y as i64);
+}
+
+/// Alpha-blend `color` onto the canvas at (x, y) with the given coverage
+pub(crate) fn blend_pixel(canvas: &mut RgbaImage, x: i32, y: i32, color: Rgba<u8>, coverage: f32) {
+    if x < 0 || y < 0 || x as u32 >= canvas.width() || y as u32 >= canvas.height() {
+        return;
+    }
+
+    let alpha = (coverage.clamp(0.0, 1.0) * color[3] as f32 / 255.0).clamp(0.0, 1.0);
+    let pixel = canvas.get_pixel_mut(x as u32, y as u32);
+    for i in 0..3 {
+        pixel[i] = (color[i] as f32 * alpha + pixel[i] as f32 * (1.0 - alpha)).round() as u8;
+    }
+    pixel[3] = (255.0 * alpha + pixel[3] as f32 * (1.0 - alpha)).round() as u8;
+}
+
+/// Decode image bytes (PNG/JPEG/WebP) into RGBA
+pub fn decode_image(bytes: &[u8]) -> Result<RgbaImage, String> {
+    image::load_from_memory(bytes)
+        .map(|img| img.to_rgba8())
+        .map_err(|e| format!("이미지 디코딩 실패: {}", e))
+}
+
+/// Save a rendered slide as PNG, creating the parent directory if needed
+pub fn save_png(slide: &RgbaImage, path: &Path) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("디렉토리 생성 실패: {}", e))?;
+    }
+    slide
+        .save_with_format(path, image::ImageFormat::Png)
+        .map_err(|e| format!("슬라이드 저장 실패: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{FillStyle, StrokeStyle};
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+
+    fn preset(width: u32, height: u32) -> ImageSizePreset {
+        ImageSizePreset { id: "test".to_string(), name: "테스트".to_string(), width, height }
+    }
+
+    fn slide_text() -> SlideText {
+        SlideText {
+            title: "연구원의 연구일지 #1".to_string(),
+            subtitle: "나이아신아마이드".to_string(),
+            short_knowledge: "피부 장벽을 돕습니다.".to_string(),
+        }
+    }
+
+    fn only(element_id: &str) -> LayoutSettings {
+        let mut layout = LayoutSettings::default();
+        layout.elements.retain(|e| e.id == element_id);
+        layout
+    }
+
+    fn element_mut<'a>(layout: &'a mut LayoutSettings, id: &str) -> &'a mut LayoutElement {
+        layout.elements.iter_mut().find(|e| e.id == id).unwrap()
+    }
+
+    fn is_white(pixel: &Rgba<u8>) -> bool {
+        pixel.0 == CANVAS_COLOR.0
+    }
+
+    #[test]
+    fn renders_at_the_preset_size() {
+        let layout = only("hero_image");
+        let image = RgbaImage::from_pixel(40, 30, RED);
+
+        for (width, height) in [(1080, 1350), (1080, 1080), (1200, 628)] {
+            let slide = render_slide(&layout, &preset(width, height), &slide_text(), Some(&image)).unwrap();
+            assert_eq!(slide.dimensions(), (width, height));
+        }
+
+        assert!(render_slide(&layout, &preset(0, 1350), &slide_text(), None).is_err());
+    }
+
+    #[test]
+    fn covers_the_image_box_and_leaves_the_rest_of_the_canvas() {
+        // Hero box: x 50-95%, y 25-75% of 1000x1000
+        let layout = only("hero_image");
+        let image = RgbaImage::from_pixel(40, 30, RED);
+        let slide = render_slide(&layout, &preset(1000, 1000), &slide_text(), Some(&image)).unwrap();
+
+        for (x, y) in [(500, 250), (949, 749), (725, 500)] {
+            assert_eq!(*slide.get_pixel(x, y), RED, "({x}, {y})");
+        }
+        for (x, y) in [(499, 500), (950, 500), (725, 249), (725, 750)] {
+            assert!(is_white(slide.get_pixel(x, y)), "({x}, {y})");
+        }
+    }
+
+    #[test]
+    fn background_gets_the_image_only_when_it_is_the_sole_image_box() {
+        let image = RgbaImage::from_pixel(10, 10, RED);
+        let text = slide_text();
+
+        let mut with_hero = only("hero_image");
+        with_hero.elements.extend(only("background").elements);
+        let slide = render_slide(&with_hero, &preset(100, 100), &text, Some(&image)).unwrap();
+        assert!(is_white(slide.get_pixel(10, 10)));
+
+        let slide = render_slide(&only("background"), &preset(100, 100), &text, Some(&image)).unwrap();
+        assert_eq!(*slide.get_pixel(10, 10), RED);
+    }
+
+    #[test]
+    fn draws_text_inside_its_box() {
+        if fonts::system_fonts().is_empty() {
+            return;
+        }
+        let mut layout = only("subtitle");
+        let subtitle = element_mut(&mut layout, "subtitle");
+        (subtitle.x, subtitle.y, subtitle.width, subtitle.height) = (10.0, 10.0, 50.0, 20.0);
+        subtitle.style.text_color = "#000000".to_string();
+        subtitle.style.fill = Some(FillStyle {
+            color: "#00ff00".to_string(),
+            opacity: 1.0,
+            shape: "rect".to_string(),
+            corner_radius: 0.0,
+        });
+
+        let slide = render_slide(&layout, &preset(200, 200), &slide_text(), None).unwrap();
+        let inside_box = |x: u32, y: u32| (20..120).contains(&x) && (20..60).contains(&y);
+
+        assert!(slide.enumerate_pixels().any(|(x, y, p)| inside_box(x, y) && p[1] < 128));
+        assert!(slide.enumerate_pixels().all(|(x, y, p)| inside_box(x, y) || is_white(p)));
+    }
+
+    #[test]
+    fn accepts_the_default_layout() {
+        assert_eq!(validate_layout(&LayoutSettings::default()), Ok(()));
+    }
+
+    #[test]
+    fn rejects_bad_boxes() {
+        let layout = LayoutSettings { selected_preset_id: " ".to_string(), ..LayoutSettings::default() };
+        assert!(validate_layout(&layout).is_err());
+
+        let mut layout = LayoutSettings::default();
+        layout.elements[1].id = layout.elements[0].id.clone();
+        assert!(validate_layout(&layout).unwrap_err().contains("중복"));
+
+        let mut layout = LayoutSettings::default();
+        layout.elements[0].element_type = "video".to_string();
+        assert!(validate_layout(&layout).unwrap_err().starts_with("제목:"));
+
+        let mut layout = LayoutSettings::default();
+        layout.elements[0].x = -1.0;
+        assert!(validate_layout(&layout).unwrap_err().contains("0-100%"));
+
+        // Small rounding in the editor is tolerated, a box hanging off the canvas is not
+        let mut layout = LayoutSettings::default();
+        element_mut(&mut layout, "title").width = 95.4;
+        assert_eq!(validate_layout(&layout), Ok(()));
+        element_mut(&mut layout, "title").width = 96.0;
+        assert!(validate_layout(&layout).unwrap_err().contains("캔버스 밖"));
+    }
+
+    #[test]
+    fn rejects_bad_styles() {
+        type BreakStyle = fn(&mut ElementStyle);
+        let cases: Vec<(&str, BreakStyle)> = vec![
+            ("설치되지 않은 글꼴", |s| s.font_family = Some("없는 글꼴 이름".to_string())),
+            ("글꼴 굵기", |s| s.font_weight = Some(950)),
+            ("글자 크기", |s| s.font_size = Some(2.0)),
+            ("최소 글자 크기", |s| (s.min_font_size, s.max_font_size) = (Some(40.0), Some(20.0))),
+            ("줄 간격", |s| s.line_height = Some(5.0)),
+            ("가로 정렬", |s| s.align = "justify".to_string()),
+            ("세로 정렬", |s| s.vertical_align = "center".to_string()),
+            ("여백", |s| s.padding = -1.0),
+            ("색상", |s| s.text_color = "red".to_string()),
+            ("외곽선 두께", |s| s.stroke = Some(StrokeStyle { color: "#fff".to_string(), width: 41.0 })),
+            ("배경 모양", |s| {
+                s.fill = Some(FillStyle {
+                    color: "#fff".to_string(),
+                    opacity: 1.0,
+                    shape: "circle".to_string(),
+                    corner_radius: 0.0,
+                })
+            }),
+        ];
+
+        for (expected, break_style) in cases {
+            let mut style = ElementStyle::default();
+            assert_eq!(validate_style("제목", &style), Ok(()));
+            break_style(&mut style);
+            let error = validate_style("제목", &style).unwrap_err();
+            assert!(error.starts_with("제목: ") && error.contains(expected), "{expected}: {error}");
+        }
+    }
+}
@@ -0,0 +1,743 @@
+use super::fonts;
+use ab_glyph::{point, v2, Font, FontArc, GlyphId, GlyphImageFormat, PxScale, ScaleFont};
+use image::{imageops, GrayImage, ImageFormat, Luma, RgbaImage};
+use ttf_parser::gsub::SubstitutionSubtable;
+
+// 기본 글꼴에 없는 글리프(이모지, 기호 등)를 찾을 때 순서대로 시도하는 글꼴
+const FALLBACK_FAMILIES: &[&str] = &[
+    "Segoe UI Emoji",
+    "Segoe UI Symbol",
+    "Apple Color Emoji",
+    "Noto Color Emoji",
+    "Noto Emoji",
+    "Noto Sans Symbols 2",
+    "Noto Sans Symbols",
+    "DejaVu Sans",
+    "Arial Unicode MS",
+];
+
+// 줄 첫머리에 올 수 없는 문장부호 (금칙 처리)
+const NO_LINE_START: &[char] = &[
+    '.', ',', '!', '?', ':', ';', ')', ']', '}', '~', '…', '·', '」', '』', '》', '〉', '”', '’', '%',
+];
+
+const ELLIPSIS: char = '…';
+
+const ZWJ: char = '\u{200D}';
+
+// 클러스터 안의 합자만 찾으므로 이모지 시퀀스를 만드는 기능만 봅니다
+const LIGATURE_FEATURES: &[&[u8; 4]] = &[b"ccmp", b"liga", b"rlig"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+}
+
+impl TextAlign {
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "center" => Self::Center,
+            "right" => Self::Right,
+            _ => Self::Left,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerticalAlign {
+    Top,
+    Middle,
+    Bottom,
+}
+
+impl VerticalAlign {
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "middle" => Self::Middle,
+            "bottom" => Self::Bottom,
+            _ => Self::Top,
+        }
+    }
+}
+
+/// Layout parameters for one text box
+#[derive(Debug, Clone)]
+pub struct TextStyle {
+    pub font_family: Option<String>,
+    pub font_weight: u16,
+    pub min_font_size: f32,
+    pub max_font_size: f32,
+    /// Multiplier of the font size
+    pub line_height: f32,
+    pub align: TextAlign,
+    pub vertical_align: VerticalAlign,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self {
+            font_family: None,
+            font_weight: 400,
+            min_font_size: 14.0,
+            max_font_size: 72.0,
+            line_height: 1.3,
+            align: TextAlign::Left,
+            vertical_align: VerticalAlign::Top,
+        }
+    }
+}
+
+/// Primary font followed by fallbacks; each glyph uses the first font that has it
+pub struct FontStack {
+    fonts: Vec<FontArc>,
+    // Index of each font inside its file, for reading the GSUB table of collections
+    face_indices: Vec<u32>,
+}
+
+impl FontStack {
+    /// Build a stack from the chosen family (if installed), the default Korean font and symbol/emoji fonts
+    pub fn resolve(family: Option<&str>, weight: u16) -> Result<Self, String> {
+        let mut faces: Vec<&fonts::FontFace> = Vec::new();
+
+        if let Some(face) = family.filter(|f| !f.trim().is_empty()).and_then(|f| fonts::find_face(f, weight)) {
+            faces.push(face);
+        }
+        if let Some(face) = fonts::default_face(weight) {
+            faces.push(face);
+        }
+        faces.extend(FALLBACK_FAMILIES.iter().filter_map(|f| fonts::find_face(f, weight)));
+
+        let mut stack: Vec<FontArc> = Vec::new();
+        let mut face_indices = Vec::new();
+        let mut seen = Vec::new();
+        for face in faces {
+            let key = (face.path.clone(), face.index);
+            if seen.contains(&key) {
+                continue;
+            }
+            seen.push(key);
+            match fonts::load_font(face) {
+                Ok(font) => {
+                    stack.push(font);
+                    face_indices.push(face.index);
+                }
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+
+        if stack.is_empty() {
+            return Err("슬라이드에 사용할 한글 글꼴을 찾을 수 없습니다.".to_string());
+        }
+
+        Ok(Self { fonts: stack, face_indices })
+    }
+
+    /// Index of the first font that can draw `c` (the primary font when none can)
+    fn font_for(&self, c: char) -> usize {
+        self.fonts.iter().position(|f| can_draw(f, c)).unwrap_or(0)
+    }
+
+    fn primary(&self) -> &FontArc {
+        &self.fonts[0]
+    }
+}
+
+/// Whether `font` has a glyph for `c` with something to draw: an outline, or a PNG bitmap as in color emoji
+/// fonts (Apple Color Emoji, Noto Color Emoji). A glyph id alone is not enough, since bitmap-only fonts map
+/// characters whose images are in formats that are not drawn here.
+fn can_draw(font: &FontArc, c: char) -> bool {
+    let id = font.glyph_id(c);
+    id != GlyphId(0) && (c.is_whitespace() || font.outline(id).is_some() || png_glyph(font, id, u16::MAX).is_some())
+}
+
+/// The PNG bitmap of `id` from the strike closest to `pixel_size`, if the font stores the glyph that way
+fn png_glyph(font: &FontArc, id: GlyphId, pixel_size: u16) -> Option<v2::GlyphImage<'_>> {
+    font.glyph_raster_image2(id, pixel_size).filter(|image| matches!(image.format, GlyphImageFormat::Png))
+}
+
+/// A user-perceived character: the base char plus trailing modifiers (VS16, ZWJ sequences, skin tones)
+#[derive(Debug, Clone)]
+struct Cluster {
+    base: char,
+    text: String,
+}
+
+fn is_cluster_extender(c: char) -> bool {
+    matches!(c,
+        '\u{FE00}'..='\u{FE0F}'
+        | '\u{200D}'
+        | '\u{20E3}'
+        | '\u{0300}'..='\u{036F}'
+        | '\u{1F3FB}'..='\u{1F3FF}'
+        | '\u{E0020}'..='\u{E007F}'
+    )
+}
+
+fn clusters(text: &str) -> Vec<Cluster> {
+    let mut result: Vec<Cluster> = Vec::new();
+    let mut joining = false;
+
+    for c in text.chars() {
+        if let Some(last) = result.last_mut() {
+            if joining || is_cluster_extender(c) {
+                last.text.push(c);
+                joining = c == '\u{200D}';
+                continue;
+            }
+        }
+        result.push(Cluster { base: c, text: c.to_string() });
+        joining = false;
+    }
+
+    result
+}
+
+#[derive(Debug, Clone)]
+struct ShapedCluster {
+    cluster: Cluster,
+    font_index: usize,
+    /// Glyphs drawn for the cluster, with their x offset from the start of the cluster
+    glyphs: Vec<(GlyphId, f32)>,
+    advance: f32,
+    /// Kerning against the previous cluster; not applied when the cluster starts a line
+    kern: f32,
+}
+
+impl ShapedCluster {
+    fn is_space(&self) -> bool {
+        self.cluster.base.is_whitespace()
+    }
+}
+
+/// One glyph placed relative to the top-left corner of the text box
+#[derive(Debug, Clone)]
+pub struct PlacedGlyph {
+    pub font_index: usize,
+    pub glyph_id: GlyphId,
+    pub x: f32,
+    pub baseline: f32,
+}
+
+/// Result of fitting text into a box
+#[derive(Debug, Clone)]
+pub struct TextLayout {
+    pub font_size: f32,
+    pub lines: Vec<String>,
+    pub glyphs: Vec<PlacedGlyph>,
+    /// True when the text did not fit even at the minimum size and was cut with an ellipsis
+    pub truncated: bool,
+    /// Bounds of the laid out text inside the box (x, y, width, height)
+    pub bounds: (f32, f32, f32, f32),
+}
+
+/// Replace glyph runs with the ligatures the font defines for them, in lookup order. Emoji fonts draw ZWJ
+/// sequences, skin tones, flags and keycaps this way.
+fn substitute_ligatures(font: &FontArc, face_index: u32, glyphs: &mut Vec<GlyphId>) {
+    let Ok(face) = ttf_parser::Face::parse(font.font_data(), face_index) else { return };
+    let Some(gsub) = face.tables().gsub else { return };
+
+    let mut lookup_indices: Vec<u16> = gsub
+        .features
+        .into_iter()
+        .filter(|feature| LIGATURE_FEATURES.iter().any(|tag| feature.tag == ttf_parser::Tag::from_bytes(tag)))
+        .flat_map(|feature| feature.lookup_indices)
+        .collect();
+    lookup_indices.sort_unstable();
+    lookup_indices.dedup();
+
+    for lookup in lookup_indices.into_iter().filter_map(|i| gsub.lookups.get(i)) {
+        for subtable in lookup.subtables.into_iter::<SubstitutionSubtable>() {
+            let SubstitutionSubtable::Ligature(ligatures) = subtable else { continue };
+            let mut i = 0;
+            while i < glyphs.len() {
+                let first = ttf_parser::GlyphId(glyphs[i].0);
+                let set = ligatures.coverage.get(first).and_then(|index| ligatures.ligature_sets.get(index));
+                let rest = &glyphs[i + 1..];
+                let matched = set.into_iter().flatten().find(|ligature| {
+                    ligature.components.len() as usize <= rest.len()
+                        && ligature.components.into_iter().zip(rest).all(|(c, g)| c.0 == g.0)
+                });
+                if let Some(ligature) = matched {
+                    let end = i + 1 + ligature.components.len() as usize;
+                    glyphs.splice(i..end, [GlyphId(ligature.glyph.0)]);
+                }
+                i += 1;
+            }
+        }
+    }
+}
+
+/// Glyphs for a whole cluster. Sequences the font has no ligature for are drawn component by component,
+/// without the joiners and variation selectors.
+fn cluster_glyphs(stack: &FontStack, font_index: usize, cluster: &Cluster) -> Vec<GlyphId> {
+    let font = &stack.fonts[font_index];
+    if cluster.text.chars().nth(1).is_none() {
+        return vec![font.glyph_id(cluster.base)];
+    }
+
+    let mut glyphs: Vec<GlyphId> = cluster
+        .text
+        .chars()
+        .filter(|c| !matches!(c, '\u{FE00}'..='\u{FE0F}'))
+        .map(|c| font.glyph_id(c))
+        .filter(|id| *id != GlyphId(0))
+        .collect();
+    substitute_ligatures(font, stack.face_indices[font_index], &mut glyphs);
+
+    let joiner = font.glyph_id(ZWJ);
+    glyphs.retain(|id| *id != joiner);
+    if glyphs.is_empty() {
+        glyphs.push(font.glyph_id(cluster.base));
+    }
+    glyphs
+}
+
+fn shape(stack: &FontStack, text: &str, scale: PxScale) -> Vec<ShapedCluster> {
+    let mut shaped: Vec<ShapedCluster> = Vec::new();
+
+    for cluster in clusters(text) {
+        let font_index = stack.font_for(cluster.base);
+        let font = stack.fonts[font_index].as_scaled(scale);
+
+        let mut glyphs = Vec::new();
+        let mut advance = 0.0;
+        for id in cluster_glyphs(stack, font_index, &cluster) {
+            glyphs.push((id, advance));
+            advance += font.h_advance(id);
+        }
+
+        let kern = match (shaped.last(), glyphs.first()) {
+            (Some(previous), Some(&(first, _))) if previous.font_index == font_index => previous
+                .glyphs
+                .last()
+                .map(|&(last, _)| font.kern(last, first))
+                .unwrap_or(0.0),
+            _ => 0.0,
+        };
+
+        shaped.push(ShapedCluster { cluster, font_index, glyphs, advance, kern });
+    }
+
+    shaped
+}
+
+/// Width of `items` set side by side. Line breaking and glyph placement both measure with this, so a line
+/// that was measured to fit is drawn at the same width.
+fn line_width(items: &[ShapedCluster]) -> f32 {
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, s)| if i == 0 { s.advance } else { s.kern + s.advance })
+        .sum()
+}
+
+/// The clusters of `line` up to the last visible one; trailing spaces take no room
+fn visible(line: &[ShapedCluster]) -> &[ShapedCluster] {
+    line.iter().rposition(|s| !s.is_space()).map(|i| &line[..=i]).unwrap_or(&[])
+}
+
+/// Break a paragraph into lines: prefer spaces (어절 boundaries), fall back to per-character breaks
+fn break_lines(shaped: Vec<ShapedCluster>, max_width: f32) -> (Vec<Vec<ShapedCluster>>, bool) {
+    let mut lines: Vec<Vec<ShapedCluster>> = Vec::new();
+    let mut overflow = false;
+
+    // Split into words, keeping the trailing space with each word
+    let mut words: Vec<Vec<ShapedCluster>> = Vec::new();
+    let mut current: Vec<ShapedCluster> = Vec::new();
+    for sc in shaped {
+        let is_space = sc.is_space();
+        current.push(sc);
+        if is_space {
+            words.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    let width_of = |items: &[ShapedCluster]| line_width(visible(items));
+
+    let mut line: Vec<ShapedCluster> = Vec::new();
+    for word in words {
+        let mut candidate = line.clone();
+        candidate.extend(word.iter().cloned());
+
+        if width_of(&candidate) <= max_width {
+            line = candidate;
+            continue;
+        }
+
+        // Word does not fit on the current line: start a new line with it
+        if !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+        }
+
+        if width_of(&word) <= max_width {
+            line = word;
+            continue;
+        }
+
+        // A single word wider than the box: break between characters
+        overflow = true;
+        for sc in word {
+            let mut candidate = line.clone();
+            candidate.push(sc.clone());
+            if width_of(&candidate) > max_width && !line.is_empty() {
+                // Keep closing punctuation on the previous line
+                if NO_LINE_START.contains(&sc.cluster.base) {
+                    line.push(sc);
+                    continue;
+                }
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push(sc);
+        }
+    }
+
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+
+    // Move line-leading punctuation back onto the previous line
+    for i in 1..lines.len() {
+        while lines[i].first().map(|s| NO_LINE_START.contains(&s.cluster.base)).unwrap_or(false) {
+            let sc = lines[i].remove(0);
+            lines[i - 1].push(sc);
+        }
+    }
+    // Lines emptied by the move go away; an empty paragraph stays as one blank line
+    if lines.len() > 1 {
+        lines.retain(|l| !l.is_empty());
+    }
+
+    (lines, overflow)
+}
+
+fn line_metrics(stack: &FontStack, font_size: f32, line_height: f32) -> (f32, f32) {
+    let scaled = stack.primary().as_scaled(PxScale::from(font_size));
+    let natural = scaled.ascent() - scaled.descent();
+    (scaled.ascent(), natural.max(font_size * line_height))
+}
+
+fn wrap(stack: &FontStack, text: &str, font_size: f32, max_width: f32) -> (Vec<Vec<ShapedCluster>>, bool) {
+    let scale = PxScale::from(font_size);
+    let mut all_lines = Vec::new();
+    let mut overflow = false;
+
+    for paragraph in text.split('\n') {
+        let (lines, para_overflow) = break_lines(shape(stack, paragraph.trim_end_matches('\r'), scale), max_width);
+        overflow |= para_overflow;
+        all_lines.extend(lines);
+    }
+
+    // Blank lines between paragraphs are kept, but trailing ones would only push the text up
+    while all_lines.last().is_some_and(|line| visible(line).is_empty()) {
+        all_lines.pop();
+    }
+
+    (all_lines, overflow)
+}
+
+/// Lay out `text` in a `width` x `height` box, shrinking from max to min font size until it fits
+pub fn layout_text(stack: &FontStack, text: &str, style: &TextStyle, width: f32, height: f32) -> TextLayout {
+    let min_size = style.min_font_size.max(1.0);
+    let max_size = style.max_font_size.max(min_size);
+
+    let fits = |size: f32| -> bool {
+        let (lines, overflow) = wrap(stack, text, size, width);
+        let (_, line_px) = line_metrics(stack, size, style.line_height);
+        !overflow && lines.len() as f32 * line_px <= height
+    };
+
+    // Binary search for the largest size that fits without breaking words
+    let font_size = if fits(max_size) {
+        max_size
+    } else {
+        let (mut lo, mut hi) = (min_size, max_size);
+        while hi - lo > 0.5 {
+            let mid = (lo + hi) / 2.0;
+            if fits(mid) {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        lo
+    };
+
+    let (mut lines, _) = wrap(stack, text, font_size, width);
+    let (ascent, line_px) = line_metrics(stack, font_size, style.line_height);
+
+    // Still too tall at the minimum size: drop lines and end with an ellipsis
+    let max_lines = ((height / line_px).floor() as usize).max(1);
+    let truncated = lines.len() > max_lines;
+    if truncated {
+        lines.truncate(max_lines);
+        if let Some(last) = lines.last_mut() {
+            let font_index = stack.font_for(ELLIPSIS);
+            let font = &stack.fonts[font_index];
+            let ellipsis_id = font.glyph_id(ELLIPSIS);
+            let ellipsis_advance = font.as_scaled(PxScale::from(font_size)).h_advance(ellipsis_id);
+            while !last.is_empty() && line_width(last) + ellipsis_advance > width {
+                last.pop();
+            }
+            while last.last().map(|s| s.is_space()).unwrap_or(false) {
+                last.pop();
+            }
+            last.push(ShapedCluster {
+                cluster: Cluster { base: ELLIPSIS, text: ELLIPSIS.to_string() },
+                font_index,
+                glyphs: vec![(ellipsis_id, 0.0)],
+                advance: ellipsis_advance,
+                kern: 0.0,
+            });
+        }
+    }
+
+    let block_height = lines.len() as f32 * line_px;
+    let top = match style.vertical_align {
+        VerticalAlign::Top => 0.0,
+        VerticalAlign::Middle => ((height - block_height) / 2.0).max(0.0),
+        VerticalAlign::Bottom => (height - block_height).max(0.0),
+    };
+
+    let mut glyphs = Vec::new();
+    let mut line_strings = Vec::new();
+    let mut min_x = width;
+    let mut max_width: f32 = 0.0;
+
+    for (i, line) in lines.iter().enumerate() {
+        // Trailing spaces do not count toward alignment
+        let visible = visible(line);
+        let line_width = line_width(visible);
+        let start_x = match style.align {
+            TextAlign::Left => 0.0,
+            TextAlign::Center => ((width - line_width) / 2.0).max(0.0),
+            TextAlign::Right => (width - line_width).max(0.0),
+        };
+        let baseline = top + i as f32 * line_px + ascent;
+
+        let mut caret = start_x;
+        for (j, sc) in visible.iter().enumerate() {
+            if j > 0 {
+                caret += sc.kern;
+            }
+            if !sc.is_space() {
+                glyphs.extend(sc.glyphs.iter().map(|&(glyph_id, offset)| PlacedGlyph {
+                    font_index: sc.font_index,
+                    glyph_id,
+                    x: caret + offset,
+                    baseline,
+                }));
+            }
+            caret += sc.advance;
+        }
+
+        min_x = min_x.min(start_x);
+        max_width = max_width.max(line_width);
+        line_strings.push(visible.iter().map(|s| s.cluster.text.as_str()).collect::<String>());
+    }
+
+    if lines.is_empty() {
+        min_x = 0.0;
+    }
+
+    TextLayout {
+        font_size,
+        lines: line_strings,
+        glyphs,
+        truncated,
+        bounds: (min_x, top, max_width, block_height),
+    }
+}
+
+/// Pixels of a laid out text box
+pub struct TextRaster {
+    /// Coverage of outline glyphs, painted in the text color
+    pub mask: GrayImage,
+    /// Color bitmap glyphs (emoji), drawn as they are over the painted text
+    pub color: RgbaImage,
+}
+
+impl TextRaster {
+    /// Coverage of every glyph, bitmaps included, for outlines and shadows
+    pub fn silhouette(&self) -> GrayImage {
+        let mut silhouette = self.mask.clone();
+        for (x, y, pixel) in self.color.enumerate_pixels() {
+            let coverage = silhouette.get_pixel_mut(x, y);
+            coverage[0] = coverage[0].max(pixel[3]);
+        }
+        silhouette
+    }
+}
+
+/// Rasterize a layout into `width` x `height` layers, offsetting glyphs by `margin` px
+pub fn rasterize(stack: &FontStack, layout: &TextLayout, width: u32, height: u32, margin: f32) -> TextRaster {
+    let scale = PxScale::from(layout.font_size);
+    let mut mask = GrayImage::new(width, height);
+    let mut color = RgbaImage::new(width, height);
+
+    for placed in &layout.glyphs {
+        let font = &stack.fonts[placed.font_index];
+        let glyph = placed
+            .glyph_id
+            .with_scale_and_position(scale, point(margin + placed.x, margin + placed.baseline));
+
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            draw_bitmap_glyph(&mut color, font, placed, scale, margin);
+            continue;
+        };
+        let bounds = outlined.px_bounds();
+        outlined.draw(|gx, gy, coverage| {
+            let px = bounds.min.x as i32 + gx as i32;
+            let py = bounds.min.y as i32 + gy as i32;
+            if px < 0 || py < 0 || px as u32 >= width || py as u32 >= height {
+                return;
+            }
+            // Overlapping glyphs keep the strongest coverage
+            let pixel = mask.get_pixel_mut(px as u32, py as u32);
+            let value = (coverage.clamp(0.0, 1.0) * 255.0).round() as u8;
+            *pixel = Luma([pixel[0].max(value)]);
+        });
+    }
+
+    TextRaster { mask, color }
+}
+
+/// Scale a PNG glyph from its strike to the font size and paste it with its bearing from the baseline
+fn draw_bitmap_glyph(canvas: &mut RgbaImage, font: &FontArc, placed: &PlacedGlyph, scale: PxScale, margin: f32) {
+    let em_px = font.units_per_em().unwrap_or(1000.0) * font.as_scaled(scale).h_scale_factor();
+    let Some(image) = png_glyph(font, placed.glyph_id, em_px.ceil() as u16) else { return };
+    let bitmap = match image::load_from_memory_with_format(image.data, ImageFormat::Png) {
+        Ok(bitmap) => bitmap.to_rgba8(),
+        Err(e) => {
+            eprintln!("이모지 이미지 디코딩 실패: {}", e);
+            return;
+        }
+    };
+
+    let strike = em_px / image.pixels_per_em.max(1) as f32;
+    let (w, h) = bitmap.dimensions();
+    let scaled_w = (w as f32 * strike).round().max(1.0) as u32;
+    let scaled_h = (h as f32 * strike).round().max(1.0) as u32;
+    let scaled = imageops::resize(&bitmap, scaled_w, scaled_h, imageops::FilterType::Triangle);
+
+    // The strike's origin is the bitmap's bottom-left corner relative to the baseline, y pointing up
+    let x = margin + placed.x + image.origin.x * strike;
+    let y = margin + placed.baseline - (image.origin.y + h as f32) * strike;
+    imageops::overlay(canvas, &scaled, x.round() as i64, y.round() as i64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 앱에 포함된 글꼴이라 어느 환경에서나 같은 결과가 나옵니다
+    static PAPERLOGY: &[u8] = include_bytes!("../../../public/font/Paperlogy/Paperlogy-4Regular.ttf");
+
+    fn stack_with(extra: &[&str]) -> FontStack {
+        let mut fonts = vec![FontArc::try_from_slice(PAPERLOGY).unwrap()];
+        let mut face_indices = vec![0];
+        for face in extra.iter().filter_map(|family| fonts::find_face(family, 400)) {
+            fonts.push(fonts::load_font(face).unwrap());
+            face_indices.push(face.index);
+        }
+        FontStack { fonts, face_indices }
+    }
+
+    fn lines_of(stack: &FontStack, text: &str, width: f32) -> Vec<String> {
+        let style = TextStyle { min_font_size: 20.0, max_font_size: 20.0, ..TextStyle::default() };
+        layout_text(stack, text, &style, width, 1000.0).lines
+    }
+
+    #[test]
+    fn groups_modifiers_and_joined_emoji_into_one_cluster() {
+        let texts: Vec<String> = clusters("a👍🏽👩\u{200D}💻1\u{FE0F}\u{20E3}e\u{0301}가")
+            .into_iter()
+            .map(|c| c.text)
+            .collect();
+        assert_eq!(texts, ["a", "👍🏽", "👩\u{200D}💻", "1\u{FE0F}\u{20E3}", "e\u{0301}", "가"]);
+    }
+
+    #[test]
+    fn breaks_at_spaces_before_breaking_words() {
+        let stack = stack_with(&[]);
+        let one_word = line_width(&shape(&stack, "연구일지", PxScale::from(20.0)));
+
+        assert_eq!(lines_of(&stack, "연구일지 연구일지", one_word + 1.0), ["연구일지", "연구일지"]);
+
+        let (lines, overflow) = wrap(&stack, "연구일지", 20.0, one_word / 2.0);
+        assert!(overflow);
+        assert!(lines.len() > 1);
+    }
+
+    #[test]
+    fn keeps_closing_punctuation_on_the_previous_line() {
+        let stack = stack_with(&[]);
+        let word = line_width(&shape(&stack, "가나다", PxScale::from(20.0)));
+        assert_eq!(lines_of(&stack, "가나다.", word + 0.5), ["가나다."]);
+    }
+
+    #[test]
+    fn keeps_blank_lines_between_paragraphs_and_drops_trailing_ones() {
+        let stack = stack_with(&[]);
+        assert_eq!(lines_of(&stack, "첫 문단\n\n둘째 문단\n\n", 500.0), ["첫 문단", "", "둘째 문단"]);
+        assert_eq!(lines_of(&stack, "\r\n한 줄\r\n", 500.0), ["", "한 줄"]);
+    }
+
+    #[test]
+    fn measures_lines_the_way_they_are_placed() {
+        let stack = stack_with(&[]);
+        let style = TextStyle { min_font_size: 40.0, max_font_size: 40.0, ..TextStyle::default() };
+        let text = "AVATAR Type";
+        let width = line_width(&shape(&stack, text, PxScale::from(40.0)));
+
+        // Exactly as wide as measured: stays on one line and ends where the measurement says
+        let layout = layout_text(&stack, text, &style, width, 200.0);
+        assert_eq!(layout.lines, [text]);
+        let last = layout.glyphs.last().unwrap();
+        let font = stack.fonts[0].as_scaled(PxScale::from(40.0));
+        assert!((last.x + font.h_advance(last.glyph_id) - width).abs() < 0.01);
+        assert!((layout.bounds.2 - width).abs() < 0.01);
+    }
+
+    #[test]
+    fn falls_back_to_a_font_that_can_draw_the_character() {
+        let stack = stack_with(&["DejaVu Sans"]);
+        assert_eq!(stack.font_for('가'), 0);
+        // Nothing in the stack has it: the primary font draws its missing glyph box
+        assert_eq!(stack.font_for('\u{10FFFD}'), 0);
+        // A blank glyph is only enough for whitespace
+        assert!(can_draw(stack.primary(), ' '));
+
+        if stack.fonts.len() > 1 {
+            assert!(!can_draw(stack.primary(), '☃'));
+            assert_eq!(stack.font_for('☃'), 1);
+        }
+    }
+
+    #[test]
+    fn draws_every_part_of_a_sequence_the_font_has_no_ligature_for() {
+        let stack = stack_with(&[]);
+        let cluster = clusters("a\u{200D}b\u{FE0F}").remove(0);
+        let font = stack.primary();
+        assert_eq!(cluster_glyphs(&stack, 0, &cluster), [font.glyph_id('a'), font.glyph_id('b')]);
+
+        let shaped = shape(&stack, "a\u{200D}b", PxScale::from(20.0));
+        let scaled = font.as_scaled(PxScale::from(20.0));
+        assert_eq!(shaped.len(), 1);
+        assert_eq!(shaped[0].glyphs[1].1, scaled.h_advance(font.glyph_id('a')));
+        assert_eq!(shaped[0].advance, scaled.h_advance(font.glyph_id('a')) + scaled.h_advance(font.glyph_id('b')));
+    }
+
+    #[test]
+    fn truncates_with_an_ellipsis_at_the_minimum_size() {
+        let stack = stack_with(&[]);
+        let style = TextStyle { min_font_size: 20.0, max_font_size: 20.0, ..TextStyle::default() };
+        let layout = layout_text(&stack, "하나\n둘\n셋\n넷", &style, 200.0, 60.0);
+        assert!(layout.truncated);
+        assert_eq!(layout.lines, ["하나", "둘…"]);
+    }
+}
@@ -1,19 +1,30 @@
-use reqwest::Client;
+use super::scheduler::{self, Provider, SendScheduled};
+use super::stream::{self, OnToken};
+use crate::models::{ModelInfo, ModelSettings, ProviderModels};
+use reqwest::{Client, Response};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 pub struct AnthropicService {
     client: Client,
     api_key: String,
+    models: ProviderModels,
 }
 
 #[derive(Debug, Serialize)]
 struct AnthropicRequest {
     model: String,
     max_tokens: u32,
+    temperature: f32,
     messages: Vec<AnthropicMessage>,
     #[serde(skip_serializing_if = "Option::is_none")]
     system: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -27,18 +38,48 @@ struct AnthropicResponse {
     content: Vec<ContentBlock>,
 }
 
+// Streaming event payload; only `content_block_delta` and `error` carry anything we need
+#[derive(Debug, Deserialize)]
+struct StreamEvent {
+    delta: Option<StreamDelta>,
+    error: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamDelta {
+    text: Option<String>,
+    partial_json: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelListResponse {
+    data: Vec<ModelEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelEntry {
+    id: String,
+    display_name: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct ContentBlock {
     #[serde(rename = "type")]
     content_type: String,
     text: Option<String>,
+    input: Option<serde_json::Value>,
 }
 
 impl AnthropicService {
     pub fn new(api_key: &str) -> Self {
+        Self::with_models(api_key, ModelSettings::default().anthropic)
+    }
+
+    pub fn with_models(api_key: &str, models: ProviderModels) -> Self {
         Self {
-            client: Client::new(),
+            client: scheduler::http_client(),
             api_key: api_key.to_string(),
+            models,
         }
     }
 
@@ -47,24 +88,149 @@ impl AnthropicService {
         prompt: &str,
         system_prompt: Option<&str>,
     ) -> Result<String, String> {
-        let request = AnthropicRequest {
-            model: "claude-3-5-sonnet-20241022".to_string(),
-            max_tokens: 4096,
+        let request = self.message_request(prompt, system_prompt, None, false);
+        let result = self.create_message(&request).await?;
+
+        result
+            .content
+            .first()
+            .and_then(|c| c.text.clone())
+            .ok_or_else(|| "응답이 비어있습니다".to_string())
+    }
+
+    /// Force a single tool call whose input schema is `schema`; returns the tool input as JSON text
+    pub async fn generate_structured(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        schema_name: &str,
+        schema: &serde_json::Value,
+    ) -> Result<String, String> {
+        let request = self.message_request(prompt, system_prompt, Some((schema_name, schema)), false);
+        let result = self.create_message(&request).await?;
+
+        result
+            .content
+            .iter()
+            .find(|c| c.content_type == "tool_use")
+            .and_then(|c| c.input.as_ref())
+            .map(|input| input.to_string())
+            .ok_or_else(|| "응답에 구조화된 결과가 없습니다".to_string())
+    }
+
+    /// Like `generate_text`, but streams the reply and passes each token to `on_token`
+    pub async fn stream_text(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        on_token: &mut OnToken<'_>,
+    ) -> Result<String, String> {
+        let request = self.message_request(prompt, system_prompt, None, true);
+        self.stream_message(&request, on_token).await
+    }
+
+    /// Like `generate_structured`; the tool input arrives as `input_json_delta` fragments
+    pub async fn stream_structured(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        schema_name: &str,
+        schema: &serde_json::Value,
+        on_token: &mut OnToken<'_>,
+    ) -> Result<String, String> {
+        let request = self.message_request(prompt, system_prompt, Some((schema_name, schema)), true);
+        self.stream_message(&request, on_token).await
+    }
+
+    /// Text request, optionally forced into a single tool call described by `tool` (name, input schema)
+    fn message_request(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        tool: Option<(&str, &serde_json::Value)>,
+        stream: bool,
+    ) -> AnthropicRequest {
+        AnthropicRequest {
+            model: self.models.text.clone(),
+            max_tokens: self.models.max_tokens,
+            temperature: self.models.temperature.min(1.0), // Anthropic은 0-1만 받습니다
             messages: vec![AnthropicMessage {
                 role: "user".to_string(),
                 content: json!(prompt),
             }],
             system: system_prompt.map(|s| s.to_string()),
-        };
+            tools: tool.map(|(name, schema)| {
+                vec![json!({
+                    "name": name,
+                    "description": "Return the answer as structured data.",
+                    "input_schema": schema
+                })]
+            }),
+            tool_choice: tool.map(|(name, _)| json!({ "type": "tool", "name": name })),
+            stream: stream.then_some(true),
+        }
+    }
+
+    async fn create_message(&self, request: &AnthropicRequest) -> Result<AnthropicResponse, String> {
+        self.post_messages(request)
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("응답 파싱 실패: {}", e))
+    }
+
+    async fn stream_message(
+        &self,
+        request: &AnthropicRequest,
+        on_token: &mut OnToken<'_>,
+    ) -> Result<String, String> {
+        let response = self.post_messages(request).await?;
+
+        let mut text = String::new();
+        stream::read_sse(response, |event, data| {
+            match event {
+                "content_block_delta" => {
+                    let payload: StreamEvent =
+                        serde_json::from_str(data).map_err(|e| format!("스트림 응답 파싱 실패: {}", e))?;
+                    let delta = payload
+                        .delta
+                        .and_then(|d| d.text.or(d.partial_json))
+                        .unwrap_or_default();
+                    if !delta.is_empty() {
+                        text.push_str(&delta);
+                        on_token(&delta);
+                    }
+                }
+                "error" => {
+                    let payload: StreamEvent = serde_json::from_str(data).unwrap_or(StreamEvent {
+                        delta: None,
+                        error: None,
+                    });
+                    let detail = payload.error.map(|e| e.to_string()).unwrap_or_else(|| data.to_string());
+                    return Err(format!("Anthropic API 오류: {}", detail));
+                }
+                "message_stop" => return Ok(false),
+                _ => {}
+            }
+            Ok(true)
+        })
+        .await?;
+
+        if text.is_empty() {
+            return Err("응답이 비어있습니다".to_string());
+        }
+        Ok(text)
+    }
 
+    async fn post_messages(&self, request: &AnthropicRequest) -> Result<Response, String> {
         let response = self
             .client
             .post("https://api.anthropic.com/v1/messages")
             .header("x-api-key", &self.api_key)
             .header("anthropic-version", "2023-06-01")
             .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
+            .json(request)
+            .send_scheduled(Provider::Anthropic)
             .await
             .map_err(|e| format!("Anthropic API 요청 실패: {}", e))?;
 
@@ -72,17 +238,7 @@ impl AnthropicService {
             let error_text = response.text().await.unwrap_or_default();
             return Err(format!("Anthropic API 오류: {}", error_text));
         }
-
-        let result: AnthropicResponse = response
-            .json()
-            .await
-            .map_err(|e| format!("응답 파싱 실패: {}", e))?;
-
-        result
-            .content
-            .first()
-            .and_then(|c| c.text.clone())
-            .ok_or_else(|| "응답이 비어있습니다".to_string())
+        Ok(response)
     }
 
     pub async fn analyze_image_for_prompt(
@@ -93,8 +249,9 @@ impl AnthropicService {
         user_prompt: &str,
     ) -> Result<String, String> {
         let request = AnthropicRequest {
-            model: "claude-3-5-sonnet-20241022".to_string(),
-            max_tokens: 2048,
+            model: self.models.vision.clone(),
+            max_tokens: self.models.max_tokens,
+            temperature: self.models.temperature.min(1.0),
             messages: vec![AnthropicMessage {
                 role: "user".to_string(),
                 content: json!([
@@ -113,6 +270,9 @@ impl AnthropicService {
                 ]),
             }],
             system: Some(system_prompt.to_string()),
+            tools: None,
+            tool_choice: None,
+            stream: None,
         };
 
         let response = self
@@ -122,7 +282,7 @@ impl AnthropicService {
             .header("anthropic-version", "2023-06-01")
             .header("Content-Type", "application/json")
             .json(&request)
-            .send()
+            .send_scheduled(Provider::Anthropic)
             .await
             .map_err(|e| format!("Anthropic Vision API 요청 실패: {}", e))?;
 
@@ -142,4 +302,38 @@ impl AnthropicService {
             .and_then(|c| c.text.clone())
             .ok_or_else(|| "응답이 비어있습니다".to_string())
     }
+
+    /// Models available to this API key
+    pub async fn list_models(&self) -> Result<Vec<ModelInfo>, String> {
+        let response = self
+            .client
+            .get("https://api.anthropic.com/v1/models?limit=1000")
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01")
+            .send_scheduled(Provider::Anthropic)
+            .await
+            .map_err(|e| format!("Anthropic 모델 목록 요청 실패: {}", e))?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(format!("Anthropic 모델 목록 오류: {}", error_text));
+        }
+
+        let result: ModelListResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("응답 파싱 실패: {}", e))?;
+
+        // Every Claude model handles text and image input
+        Ok(result
+            .data
+            .into_iter()
+            .map(|m| ModelInfo {
+                id: m.id,
+                display_name: m.display_name,
+                tasks: vec!["text".to_string(), "vision".to_string()],
+                reasoning: false,
+            })
+            .collect())
+    }
 }
@@ -0,0 +1,598 @@
+use super::scheduler::{self, Provider, SendScheduled};
+use crate::models::{AppSettings, PaperResult};
+use futures_util::future::join_all;
+use reqwest::Client;
+use serde::Deserialize;
+
+// ID 변환기는 한 요청에 200개까지 받습니다
+const IDCONV_BATCH: usize = 200;
+
+/// Identifiers of one article; any of them may be unknown
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ArticleIds {
+    pub pmid: Option<String>,
+    pub pmcid: Option<String>,
+    pub doi: Option<String>,
+}
+
+/// Kind of identifier sent to the PMC ID converter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdKind {
+    Doi,
+    Pmid,
+    Pmcid,
+}
+
+impl IdKind {
+    fn as_param(self) -> &'static str {
+        match self {
+            IdKind::Doi => "doi",
+            IdKind::Pmid => "pmid",
+            IdKind::Pmcid => "pmcid",
+        }
+    }
+}
+
+/// What an enrichment pass found for one article
+#[derive(Debug, Clone, Default)]
+pub struct Enrichment {
+    /// The input ids with the missing ones filled in from the ID converter
+    pub ids: ArticleIds,
+    pub citation_count: Option<u32>,
+    pub open_access_url: Option<String>,
+}
+
+/// Citation counts (OpenCitations), open-access locations (Unpaywall) and PMID/PMCID/DOI
+/// cross-links (PMC ID converter)
+pub struct CitationService {
+    client: Client,
+    opencitations_url: String,
+    unpaywall_url: String,
+    idconv_url: String,
+    tool: Option<String>,
+    email: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdConvResponse {
+    #[serde(default)]
+    records: Vec<IdConvRecord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdConvRecord {
+    /// A string in the v1.0 API, a number in the newer one
+    #[serde(default, deserialize_with = "string_or_number")]
+    pmid: Option<String>,
+    pmcid: Option<String>,
+    doi: Option<String>,
+    /// Set to "error" for ids the converter does not know
+    status: Option<String>,
+}
+
+fn string_or_number<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    Ok(match Option::<serde_json::Value>::deserialize(deserializer)? {
+        Some(serde_json::Value::String(s)) => Some(s),
+        Some(serde_json::Value::Number(n)) => Some(n.to_string()),
+        _ => None,
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct CitationCount {
+    count: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct UnpaywallResponse {
+    best_oa_location: Option<OaLocation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OaLocation {
+    url_for_pdf: Option<String>,
+}
+
+impl CitationService {
+    pub fn new() -> Self {
+        Self {
+            client: scheduler::http_client(),
+            opencitations_url: "https://api.opencitations.net/index/v2".to_string(),
+            unpaywall_url: "https://api.unpaywall.org/v2".to_string(),
+            idconv_url: "https://www.ncbi.nlm.nih.gov/pmc/utils/idconv/v1.0".to_string(),
+            tool: None,
+            email: None,
+        }
+    }
+
+    /// Endpoint overrides and the NCBI tool/contact email from the app settings
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        let defaults = Self::new();
+        let endpoints = &settings.source_endpoints;
+        let base = |url: &Option<String>, default: String| {
+            url.as_deref()
+                .map(|u| u.trim_end_matches('/').to_string())
+                .unwrap_or(default)
+        };
+
+        Self {
+            opencitations_url: base(&endpoints.opencitations, defaults.opencitations_url),
+            unpaywall_url: base(&endpoints.unpaywall, defaults.unpaywall_url),
+            idconv_url: base(&endpoints.idconv, defaults.idconv_url),
+            tool: settings.ncbi.tool.clone(),
+            email: settings.ncbi.email.clone(),
+            client: defaults.client,
+        }
+    }
+
+    /// Fill in missing ids, then look up citation counts and open-access links.
+    /// The enrichments have one entry per input, in order. Citation and open-access lookups that fail
+    /// are logged and left empty; ID converter batches that fail are returned so the caller can report
+    /// that cross-linking was partial.
+    pub async fn enrich(&self, articles: Vec<ArticleIds>) -> (Vec<Enrichment>, Vec<String>) {
+        let (articles, failures) = self.cross_link(articles).await;
+
+        let enrichments = join_all(articles.into_iter().map(|ids| async move {
+            let (citation_count, open_access_url) =
+                tokio::join!(self.citation_count(&ids), self.open_access_url(&ids));
+            Enrichment {
+                citation_count: citation_count
+                    .map_err(|e| eprintln!("인용 수 조회 실패 ({:?}): {}", ids, e))
+                    .ok()
+                    .flatten(),
+                open_access_url: open_access_url
+                    .map_err(|e| eprintln!("오픈 액세스 링크 조회 실패 ({:?}): {}", ids, e))
+                    .ok()
+                    .flatten(),
+                ids,
+            }
+        }))
+        .await;
+        (enrichments, failures)
+    }
+
+    /// Complete each article's ids from the converter, looking up by DOI, else PMID, else PMCID.
+    /// Each kind goes to the converter in its own batches; failed batches are returned as messages.
+    pub async fn cross_link(&self, mut articles: Vec<ArticleIds>) -> (Vec<ArticleIds>, Vec<String>) {
+        let mut lookups: [(IdKind, Vec<String>); 3] =
+            [(IdKind::Doi, vec![]), (IdKind::Pmid, vec![]), (IdKind::Pmcid, vec![])];
+        for article in &articles {
+            if article.pmid.is_some() && article.pmcid.is_some() && article.doi.is_some() {
+                continue;
+            }
+            let lookup = match (&article.doi, &article.pmid, &article.pmcid) {
+                (Some(doi), _, _) => Some((0, normalize_doi(doi))),
+                (None, Some(pmid), _) => Some((1, pmid.clone())),
+                (None, None, Some(pmcid)) => Some((2, pmcid.clone())),
+                (None, None, None) => None,
+            };
+            if let Some((index, id)) = lookup {
+                if !lookups[index].1.contains(&id) {
+                    lookups[index].1.push(id);
+                }
+            }
+        }
+
+        let mut records = Vec::new();
+        let mut failures = Vec::new();
+        for (kind, ids) in &lookups {
+            for batch in ids.chunks(IDCONV_BATCH) {
+                match self.convert_ids(*kind, batch).await {
+                    Ok(found) => records.extend(found),
+                    Err(e) => {
+                        let kind = kind.as_param().to_uppercase();
+                        let message = format!("{} {}건 ID 변환 실패: {}", kind, batch.len(), e);
+                        eprintln!("{}", message);
+                        failures.push(message);
+                    }
+                }
+            }
+        }
+
+        for article in &mut articles {
+            let Some(found) = records.iter().find(|r| same_article(article, r)) else {
+                continue;
+            };
+            article.pmid = article.pmid.take().or_else(|| found.pmid.clone());
+            article.pmcid = article.pmcid.take().or_else(|| found.pmcid.clone());
+            article.doi = article.doi.take().or_else(|| found.doi.clone());
+        }
+        (articles, failures)
+    }
+
+    /// Look up ids of one kind in the PMC ID converter; ids it does not know are left out
+    pub async fn convert_ids(&self, kind: IdKind, ids: &[String]) -> Result<Vec<ArticleIds>, String> {
+        let mut url = format!(
+            "{}/?ids={}&idtype={}&format=json",
+            self.idconv_url,
+            urlencoding::encode(&ids.join(",")),
+            kind.as_param()
+        );
+        for (key, value) in [("tool", &self.tool), ("email", &self.email)] {
+            if let Some(value) = value {
+                url.push_str(&format!("&{}={}", key, urlencoding::encode(value)));
+            }
+        }
+
+        let response = self
+            .client
+            .get(&url)
+            .send_scheduled(Provider::Ncbi)
+            .await
+            .map_err(|e| format!("ID 변환 요청 실패: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("ID 변환 API 오류: {}", response.status()));
+        }
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| format!("ID 변환 응답 읽기 실패: {}", e))?;
+        parse_idconv(&body)
+    }
+
+    /// Number of citing works in the OpenCitations index, by DOI or else PMID
+    pub async fn citation_count(&self, ids: &ArticleIds) -> Result<Option<u32>, String> {
+        let id = match (&ids.doi, &ids.pmid) {
+            (Some(doi), _) => format!("doi:{}", normalize_doi(doi)),
+            (None, Some(pmid)) => format!("pmid:{}", pmid),
+            (None, None) => return Ok(None),
+        };
+        let url = format!("{}/citation-count/{}", self.opencitations_url, id);
+
+        let response = self
+            .client
+            .get(&url)
+            .send_scheduled(Provider::OpenCitations)
+            .await
+            .map_err(|e| format!("OpenCitations 요청 실패: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("OpenCitations API 오류: {}", response.status()));
+        }
+
+        let counts: Vec<CitationCount> = response
+            .json()
+            .await
+            .map_err(|e| format!("OpenCitations 응답 파싱 실패: {}", e))?;
+
+        Ok(counts.first().and_then(|c| c.count.trim().parse().ok()))
+    }
+
+    /// A legal free PDF: Unpaywall's best location when a contact email is configured,
+    /// otherwise the PMC copy for articles deposited there
+    pub async fn open_access_url(&self, ids: &ArticleIds) -> Result<Option<String>, String> {
+        let pmc_pdf = ids
+            .pmcid
+            .as_ref()
+            .map(|pmcid| format!("https://pmc.ncbi.nlm.nih.gov/articles/{}/pdf/", pmcid));
+
+        let (Some(doi), Some(email)) = (&ids.doi, &self.email) else {
+            return Ok(pmc_pdf);
+        };
+        let url = format!(
+            "{}/{}?email={}",
+            self.unpaywall_url,
+            urlencoding::encode(&normalize_doi(doi)),
+            urlencoding::encode(email)
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .send_scheduled(Provider::Unpaywall)
+            .await
+            .map_err(|e| format!("Unpaywall 요청 실패: {}", e))?;
+
+        // Unpaywall에 없는 DOI는 404로 응답합니다
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(pmc_pdf);
+        }
+        if !response.status().is_success() {
+            return Err(format!("Unpaywall API 오류: {}", response.status()));
+        }
+
+        let result: UnpaywallResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Unpaywall 응답 파싱 실패: {}", e))?;
+
+        Ok(result
+            .best_oa_location
+            .and_then(|l| l.url_for_pdf)
+            .or(pmc_pdf))
+    }
+}
+
+impl Default for CitationService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Records of an ID converter JSON response, without the ones flagged as errors
+fn parse_idconv(body: &str) -> Result<Vec<ArticleIds>, String> {
+    let result: IdConvResponse =
+        serde_json::from_str(body).map_err(|e| format!("ID 변환 응답 파싱 실패: {}", e))?;
+
+    Ok(result
+        .records
+        .into_iter()
+        .filter(|r| r.status.as_deref() != Some("error"))
+        .map(|r| ArticleIds {
+            pmid: r.pmid,
+            pmcid: r.pmcid,
+            doi: r.doi,
+        })
+        .collect())
+}
+
+/// Bare lower-case DOI ("https://doi.org/10.1000/ABC" -> "10.1000/abc")
+pub fn normalize_doi(doi: &str) -> String {
+    let doi = doi.trim();
+    let doi = ["https://doi.org/", "http://doi.org/", "https://dx.doi.org/", "http://dx.doi.org/", "doi:"]
+        .iter()
+        .find_map(|prefix| {
+            doi.get(..prefix.len())
+                .filter(|head| head.eq_ignore_ascii_case(prefix))
+                .map(|_| &doi[prefix.len()..])
+        })
+        .unwrap_or(doi);
+    doi.to_lowercase()
+}
+
+fn same_article(a: &ArticleIds, b: &ArticleIds) -> bool {
+    let same = |x: &Option<String>, y: &Option<String>| matches!((x, y), (Some(x), Some(y)) if x == y);
+    let same_doi = match (&a.doi, &b.doi) {
+        (Some(x), Some(y)) => normalize_doi(x) == normalize_doi(y),
+        _ => false,
+    };
+    same_doi || same(&a.pmid, &b.pmid) || same(&a.pmcid, &b.pmcid)
+}
+
+fn paper_ids(paper: &PaperResult) -> ArticleIds {
+    ArticleIds {
+        pmid: paper.pmid.clone(),
+        pmcid: paper.pmcid.clone(),
+        doi: paper.doi.clone(),
+    }
+}
+
+// 이보다 짧은 제목("Introduction" 등)은 제목만으로 같은 논문이라 보지 않습니다
+const MIN_TITLE_MATCH_LEN: usize = 20;
+
+/// Lower-case letters and digits only, single-spaced, for comparing titles across sources
+pub fn normalize_title(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Same DOI, PMID or PMCID, or the same (long enough) title
+pub fn is_duplicate(a: &PaperResult, b: &PaperResult) -> bool {
+    if same_article(&paper_ids(a), &paper_ids(b)) {
+        return true;
+    }
+    let title = normalize_title(&a.title);
+    title.len() >= MIN_TITLE_MATCH_LEN && title == normalize_title(&b.title)
+}
+
+/// Fill `existing`'s missing fields from a duplicate record
+pub fn merge_into(existing: &mut PaperResult, paper: PaperResult) {
+    if !existing.source.split(" + ").any(|s| s == paper.source) {
+        existing.source = format!("{} + {}", existing.source, paper.source);
+    }
+    existing.citation_count = existing.citation_count.max(paper.citation_count);
+    existing.pmid = existing.pmid.take().or(paper.pmid);
+    existing.pmcid = existing.pmcid.take().or(paper.pmcid);
+    existing.doi = existing.doi.take().or(paper.doi);
+    existing.url = existing.url.take().or(paper.url);
+    existing.open_access_url = existing.open_access_url.take().or(paper.open_access_url);
+    existing.journal = existing.journal.take().or(paper.journal);
+    existing.country = existing.country.take().or(paper.country);
+    existing.evidence = existing.evidence.take().or(paper.evidence);
+    if existing.abstract_text.is_empty() {
+        existing.abstract_text = paper.abstract_text;
+        existing.abstract_sections = paper.abstract_sections;
+    }
+    if existing.authors.is_empty() {
+        existing.authors = paper.authors;
+    }
+    if existing.publication_date.is_empty() {
+        existing.publication_date = paper.publication_date;
+    }
+    for (target, extra) in [
+        (&mut existing.mesh_terms, paper.mesh_terms),
+        (&mut existing.keywords, paper.keywords),
+        (&mut existing.publication_types, paper.publication_types),
+    ] {
+        if target.is_empty() {
+            *target = extra;
+        }
+    }
+}
+
+/// Collapse duplicate records (see `is_duplicate`). The first record of each group is kept
+/// and its missing fields are filled from the later ones, so list PubMed results first.
+pub fn merge_duplicates(papers: Vec<PaperResult>) -> Vec<PaperResult> {
+    let mut merged: Vec<PaperResult> = Vec::new();
+
+    for paper in papers {
+        match merged.iter_mut().find(|m| is_duplicate(m, &paper)) {
+            Some(existing) => merge_into(existing, paper),
+            None => merged.push(paper),
+        }
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{method, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn service(server: &MockServer) -> CitationService {
+        let mut settings = AppSettings::default();
+        settings.source_endpoints.idconv = Some(format!("{}/idconv/", server.uri()));
+        CitationService::from_settings(&settings)
+    }
+
+    fn ids(pmid: Option<&str>, pmcid: Option<&str>, doi: Option<&str>) -> ArticleIds {
+        ArticleIds {
+            pmid: pmid.map(String::from),
+            pmcid: pmcid.map(String::from),
+            doi: doi.map(String::from),
+        }
+    }
+
+    fn paper(id: &str, title: &str, source: &str, doi: Option<&str>, pmid: Option<&str>) -> PaperResult {
+        serde_json::from_value(json!({
+            "id": id,
+            "title": title,
+            "authors": [],
+            "abstract_text": "",
+            "publication_date": "",
+            "source": source,
+            "citation_count": null,
+            "doi": doi,
+            "pmid": pmid,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_idconv_records_and_skips_errors() {
+        let body = r#"{
+            "status": "ok",
+            "records": [
+                {"pmcid": "PMC3531190", "pmid": "23193287", "doi": "10.1093/nar/gks1195"},
+                {"pmcid": "PMC7000001", "pmid": 31000001, "doi": "10.1000/xyz"},
+                {"doi": "10.9999/unknown", "status": "error", "errmsg": "invalid article id"}
+            ]
+        }"#;
+
+        let records = parse_idconv(body).unwrap();
+        assert_eq!(
+            records,
+            vec![
+                ids(Some("23193287"), Some("PMC3531190"), Some("10.1093/nar/gks1195")),
+                ids(Some("31000001"), Some("PMC7000001"), Some("10.1000/xyz")),
+            ]
+        );
+        assert!(parse_idconv("<html>busy</html>").is_err());
+    }
+
+    #[tokio::test]
+    async fn cross_link_batches_by_id_kind() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("idtype", "doi"))
+            .and(query_param("ids", "10.1000/abc,10.1000/def"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "records": [{"pmid": "111", "pmcid": "PMC111", "doi": "10.1000/abc"}]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(query_param("idtype", "pmid"))
+            .and(query_param("ids", "222"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "records": [{"pmid": "222", "pmcid": "PMC222", "doi": "10.1000/ghi"}]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let articles = vec![
+            ids(None, None, Some("https://doi.org/10.1000/ABC")),
+            ids(Some("222"), None, None),
+            ids(None, None, Some("10.1000/def")),
+            ids(Some("333"), Some("PMC333"), Some("10.1000/complete")),
+        ];
+        let (linked, failures) = service(&server).cross_link(articles).await;
+
+        assert!(failures.is_empty(), "{failures:?}");
+        assert_eq!(linked[0], ids(Some("111"), Some("PMC111"), Some("https://doi.org/10.1000/ABC")));
+        assert_eq!(linked[1], ids(Some("222"), Some("PMC222"), Some("10.1000/ghi")));
+        assert_eq!(linked[2], ids(None, None, Some("10.1000/def")));
+        assert_eq!(linked[3], ids(Some("333"), Some("PMC333"), Some("10.1000/complete")));
+    }
+
+    #[tokio::test]
+    async fn cross_link_reports_failed_batches() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("idtype", "doi"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "records": [{"pmid": "111", "doi": "10.1000/abc"}]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(query_param("idtype", "pmid"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let articles = vec![ids(None, None, Some("10.1000/abc")), ids(Some("222"), None, None)];
+        let (linked, failures) = service(&server).cross_link(articles).await;
+
+        assert_eq!(linked[0].pmid.as_deref(), Some("111"));
+        assert_eq!(linked[1], ids(Some("222"), None, None));
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].starts_with("PMID 1건"), "{}", failures[0]);
+    }
+
+    #[test]
+    fn normalizes_doi_prefixes_and_case() {
+        assert_eq!(normalize_doi(" https://doi.org/10.1000/ABC "), "10.1000/abc");
+        assert_eq!(normalize_doi("DOI:10.1000/x"), "10.1000/x");
+        assert_eq!(normalize_doi("10.1000/y"), "10.1000/y");
+    }
+
+    #[test]
+    fn merges_records_sharing_an_id_and_fills_gaps() {
+        let mut pubmed = paper("p1", "Niacinamide and the skin barrier", "PubMed", None, Some("111"));
+        pubmed.authors = vec!["Kim J".to_string()];
+        let title = "Niacinamide and the skin barrier";
+        let mut crossref = paper("c1", title, "CrossRef", Some("10.1000/ABC"), Some("111"));
+        crossref.citation_count = Some(12);
+        crossref.journal = Some("J Cosmet Sci".to_string());
+        crossref.authors = vec!["Someone Else".to_string()];
+        let other = paper("c2", "Retinol in photoaging", "CrossRef", Some("10.1000/other"), None);
+
+        let merged = merge_duplicates(vec![pubmed, crossref, other]);
+
+        assert_eq!(merged.len(), 2);
+        let first = &merged[0];
+        assert_eq!(first.id, "p1");
+        assert_eq!(first.source, "PubMed + CrossRef");
+        assert_eq!(first.doi.as_deref(), Some("10.1000/ABC"));
+        assert_eq!(first.citation_count, Some(12));
+        assert_eq!(first.journal.as_deref(), Some("J Cosmet Sci"));
+        assert_eq!(first.authors, vec!["Kim J".to_string()]);
+    }
+
+    #[test]
+    fn matches_duplicates_by_doi_case_and_long_titles_only() {
+        let a = paper("a", "A", "PubMed", Some("10.1000/ABC"), None);
+        let b = paper("b", "B", "CrossRef", Some("https://doi.org/10.1000/abc"), None);
+        assert!(is_duplicate(&a, &b));
+
+        let long_a = paper("a", "Topical niacinamide reduces sebum: a trial", "PubMed", None, None);
+        let long_b = paper("b", "Topical Niacinamide Reduces Sebum - A Trial", "CrossRef", None, None);
+        assert!(is_duplicate(&long_a, &long_b));
+
+        let short_a = paper("a", "Introduction", "PubMed", None, None);
+        let short_b = paper("b", "Introduction", "CrossRef", None, None);
+        assert!(!is_duplicate(&short_a, &short_b));
+    }
+}
@@ -0,0 +1,266 @@
+// Ingredient pairing rules: which ingredients work well together, which are fine despite what is often
+// said, and which need care when layered. Every rule carries the source it is based on.
+
+use super::dataset::{self, ActiveDataset, CitesSource, Dataset, Source, Sources};
+use super::ingredients::IngredientDictionary;
+use crate::models::{
+    Compatibility, Ingredient, IngredientPairing, InteractionDataset, InteractionRule, InteractionSource,
+};
+use std::sync::Arc;
+
+/// A rule with its group names expanded to dictionary ids
+struct PairingRule {
+    first: Vec<String>,
+    second: Vec<String>,
+    rule: InteractionRule,
+    source: InteractionSource,
+}
+
+impl PairingRule {
+    /// Whether the rule covers `a` and `b`, in either order
+    fn covers(&self, a: &str, b: &str) -> bool {
+        let has = |side: &[String], id: &str| side.iter().any(|s| s == id);
+        (has(&self.first, a) && has(&self.second, b)) || (has(&self.first, b) && has(&self.second, a))
+    }
+
+    /// Ids on the other side of the rule from `id`
+    fn partners(&self, id: &str) -> &[String] {
+        if self.first.iter().any(|s| s == id) {
+            &self.second
+        } else if self.second.iter().any(|s| s == id) {
+            &self.first
+        } else {
+            &[]
+        }
+    }
+
+    fn pairing(&self, first: &Ingredient, second: &Ingredient) -> IngredientPairing {
+        IngredientPairing {
+            first: first.clone(),
+            second: second.clone(),
+            compatibility: self.rule.compatibility,
+            rationale: self.rule.rationale.clone(),
+            advice: self.rule.advice.clone(),
+            reference: self.rule.reference.clone(),
+            source: self.source.clone(),
+        }
+    }
+}
+
+pub struct CompatibilityEngine {
+    rules: Vec<PairingRule>,
+}
+
+impl CompatibilityEngine {
+    pub fn new(dataset: InteractionDataset) -> Self {
+        let sources = Sources::new(dataset.sources);
+        let expand = |names: &[String]| -> Vec<String> {
+            names
+                .iter()
+                .flat_map(|name| dataset.groups.get(name).cloned().unwrap_or_else(|| vec![name.clone()]))
+                .collect()
+        };
+
+        let rules = dataset
+            .rules
+            .into_iter()
+            .filter_map(|rule| {
+                let source = sources.cited_by(&rule)?.clone();
+                Some(PairingRule {
+                    first: expand(&rule.first),
+                    second: expand(&rule.second),
+                    rule,
+                    source,
+                })
+            })
+            .collect();
+
+        Self { rules }
+    }
+
+    /// Every rule that applies to two of the given ingredients
+    pub fn pairings(&self, ingredients: &[Ingredient]) -> Vec<IngredientPairing> {
+        let mut pairings = Vec::new();
+        for (i, first) in ingredients.iter().enumerate() {
+            for second in &ingredients[i + 1..] {
+                if first.id == second.id {
+                    continue;
+                }
+                pairings.extend(
+                    self.rules
+                        .iter()
+                        .filter(|rule| rule.covers(&first.id, &second.id))
+                        .map(|rule| rule.pairing(first, second)),
+                );
+            }
+        }
+        sort_pairings(&mut pairings);
+        pairings
+    }
+
+    /// What the rules say about `ingredient` with anything else in the dictionary
+    pub fn partners(&self, ingredient: &Ingredient, dictionary: &IngredientDictionary) -> Vec<IngredientPairing> {
+        let mut pairings: Vec<IngredientPairing> = self
+            .rules
+            .iter()
+            .flat_map(|rule| {
+                rule.partners(&ingredient.id)
+                    .iter()
+                    .filter(|id| **id != ingredient.id)
+                    .filter_map(|id| dictionary.entries().iter().find(|e| e.id == *id))
+                    .map(|partner| rule.pairing(ingredient, partner))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        sort_pairings(&mut pairings);
+        pairings
+    }
+}
+
+/// Cautionary pairs first, then synergistic, then neutral
+fn sort_pairings(pairings: &mut [IngredientPairing]) {
+    pairings.sort_by_key(|p| match p.compatibility {
+        Compatibility::Cautionary => 0,
+        Compatibility::Synergistic => 1,
+        Compatibility::Neutral => 2,
+    });
+}
+
+pub fn parse_dataset(json: &str) -> Result<InteractionDataset, String> {
+    serde_json::from_str(json).map_err(|e| format!("성분 조합 데이터 형식이 올바르지 않습니다: {}", e))
+}
+
+/// Sources are replaced by id, groups by name; rules are added
+impl Dataset for InteractionDataset {
+    type Index = CompatibilityEngine;
+
+    const SEED: &'static str = include_str!("../../data/interactions.json");
+    const LABEL: &'static str = "성분 조합 데이터";
+
+    fn parse(json: &str) -> Result<Self, String> {
+        parse_dataset(json)
+    }
+
+    fn merge(&mut self, incoming: Self) {
+        dataset::merge_sources(&mut self.sources, incoming.sources);
+        self.groups.extend(incoming.groups);
+        self.rules.extend(incoming.rules);
+    }
+
+    fn build(self) -> CompatibilityEngine {
+        CompatibilityEngine::new(self)
+    }
+}
+
+impl Source for InteractionSource {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl CitesSource for InteractionRule {
+    fn source_id(&self) -> &str {
+        &self.source_id
+    }
+
+    fn subject(&self) -> String {
+        format!("{} + {}", self.first.join("/"), self.second.join("/"))
+    }
+}
+
+static ACTIVE: ActiveDataset<InteractionDataset> = ActiveDataset::new();
+
+/// The pairing rules in use (the bundled data; there is no import for them yet)
+pub fn engine() -> Arc<CompatibilityEngine> {
+    ACTIVE.get()
+}
+
+/// Pairings as prompt lines for content planning; pairings from the same rule share a line, e.g.
+/// "- [주의가 필요한 조합] 레티놀 + 글라이콜릭애씨드/락틱애씨드: ... (출처: Zasada M, ...)"
+pub fn prompt_lines(pairings: &[IngredientPairing]) -> Vec<String> {
+    let mut grouped: Vec<(&IngredientPairing, Vec<&str>)> = Vec::new();
+    for p in pairings {
+        let same_rule = grouped.iter_mut().find(|(g, _)| {
+            g.first.id == p.first.id && g.compatibility == p.compatibility && g.rationale == p.rationale
+        });
+        match same_rule {
+            Some((_, partners)) => partners.push(display_name(&p.second)),
+            None => grouped.push((p, vec![display_name(&p.second)])),
+        }
+    }
+
+    grouped
+        .into_iter()
+        .map(|(p, partners)| {
+            let kind = match p.compatibility {
+                Compatibility::Synergistic => "함께 쓰면 좋은 조합",
+                Compatibility::Neutral => "함께 써도 괜찮은 조합",
+                Compatibility::Cautionary => "주의가 필요한 조합",
+            };
+            let advice = p.advice.as_ref().map(|a| format!(" {}.", a)).unwrap_or_default();
+            format!(
+                "- [{}] {} + {}: {}.{} (출처: {})",
+                kind,
+                display_name(&p.first),
+                partners.join("/"),
+                p.rationale,
+                advice,
+                p.source.title
+            )
+        })
+        .collect()
+}
+
+fn display_name(ingredient: &Ingredient) -> &str {
+    if ingredient.korean_name.is_empty() {
+        &ingredient.inci_name
+    } else {
+        &ingredient.korean_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::ingredients;
+
+    fn ingredient(id: &str) -> Ingredient {
+        ingredients::dictionary().entries().iter().find(|i| i.id == id).cloned().unwrap()
+    }
+
+    #[test]
+    fn seed_rules_cite_known_sources_and_dictionary_ids() {
+        let dataset = parse_dataset(InteractionDataset::SEED).unwrap();
+        assert!(dataset::check_sources(&dataset.rules, &dataset.sources, &[]).is_ok());
+
+        let dictionary = ingredients::dictionary();
+        let known = |id: &String| dictionary.entries().iter().any(|i| i.id == *id);
+        let expanded = |names: &[String]| -> Vec<String> {
+            names.iter().flat_map(|n| dataset.groups.get(n).cloned().unwrap_or_else(|| vec![n.clone()])).collect()
+        };
+        for rule in &dataset.rules {
+            let ids = [expanded(&rule.first), expanded(&rule.second)].concat();
+            assert!(ids.iter().all(known), "{}", rule.subject());
+        }
+    }
+
+    #[test]
+    fn finds_rules_through_groups_in_either_order() {
+        let pairings = engine().pairings(&[ingredient("glycolic-acid"), ingredient("retinol")]);
+        let cautionary = pairings.iter().find(|p| p.compatibility == Compatibility::Cautionary).unwrap();
+        assert_eq!(cautionary.source.id, "zasada-2019");
+    }
+
+    #[test]
+    fn drops_rules_without_a_source() {
+        let dataset = parse_dataset(
+            r#"{"sources": [], "rules": [
+                {"first": ["retinol"], "second": ["glycolic-acid"], "compatibility": "cautionary",
+                 "rationale": "자극", "source_id": "missing"}
+            ]}"#,
+        )
+        .unwrap();
+        let engine = CompatibilityEngine::new(dataset);
+        assert!(engine.pairings(&[ingredient("retinol"), ingredient("glycolic-acid")]).is_empty());
+    }
+}
@@ -1,8 +1,10 @@
+use super::scheduler::{self, Provider, SendScheduled};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
 pub struct CrossRefService {
     client: Client,
+    base_url: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -24,6 +26,8 @@ struct CrossRefResponse {
 #[derive(Debug, Deserialize)]
 struct CrossRefMessage {
     items: Option<Vec<CrossRefItem>>,
+    #[serde(rename = "total-results")]
+    total_results: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -54,14 +58,58 @@ struct CrossRefDate {
 impl CrossRefService {
     pub fn new() -> Self {
         Self {
-            client: Client::new(),
+            client: scheduler::http_client(),
+            base_url: "https://api.crossref.org".to_string(),
         }
     }
 
+    /// Point the service at another CrossRef-compatible host (e.g. a local fixture server)
+    pub fn with_base_url(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            ..Self::new()
+        }
+    }
+
+    /// Number of works matching the query that were published between `from` and `until` (YYYY-MM-DD)
+    pub async fn count(&self, query: &str, from: &str, until: &str) -> Result<u64, String> {
+        let url = format!(
+            "{}/works?query={}&filter=from-pub-date:{},until-pub-date:{}&rows=0",
+            self.base_url,
+            urlencoding::encode(query),
+            from,
+            until
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .header("User-Agent", "MomsInsta/1.0 (mailto:contact@example.com)")
+            .send_scheduled(Provider::CrossRef)
+            .await
+            .map_err(|e| format!("CrossRef API 요청 실패: {}", e))?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(format!("CrossRef API 오류: {}", error_text));
+        }
+
+        let result: CrossRefResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("응답 파싱 실패: {}", e))?;
+
+        result
+            .message
+            .total_results
+            .ok_or_else(|| "CrossRef 검색 결과 수를 읽을 수 없습니다".to_string())
+    }
+
     /// Search CrossRef for academic papers and conference proceedings
     pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<ConferenceResult>, String> {
         let url = format!(
-            "https://api.crossref.org/works?query={}&rows={}&select=DOI,title,author,container-title,published,URL",
+            "{}/works?query={}&rows={}&select=DOI,title,author,container-title,published,URL",
+            self.base_url,
             urlencoding::encode(query),
             limit
         );
@@ -70,7 +118,7 @@ impl CrossRefService {
             .client
             .get(&url)
             .header("User-Agent", "MomsInsta/1.0 (mailto:contact@example.com)")
-            .send()
+            .send_scheduled(Provider::CrossRef)
             .await
             .map_err(|e| format!("CrossRef API 요청 실패: {}", e))?;
 
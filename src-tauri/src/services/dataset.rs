@@ -0,0 +1,259 @@
+// Bundled JSON datasets (ingredient dictionary, regulatory lists, pairing rules) and the user's imports
+// merged over them: loading, the copy in use, and the rule that every rule must cite a source.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+use tokio::sync::{Mutex, MutexGuard};
+
+/// A data file format with bundled seed data that the user's imports are merged into
+pub trait Dataset: Default + Serialize {
+    /// What lookups run on, built from the merged data
+    type Index: Send + Sync;
+
+    /// The bundled data file
+    const SEED: &'static str;
+    /// Name used in messages, e.g. "규제 데이터"
+    const LABEL: &'static str;
+
+    fn parse(json: &str) -> Result<Self, String>;
+
+    /// Merge `incoming` over `self`; what it replaces is up to the dataset
+    fn merge(&mut self, incoming: Self);
+
+    fn build(self) -> Self::Index;
+}
+
+/// The bundled data; a broken seed file is reported and treated as empty
+pub fn seed<D: Dataset>() -> D {
+    D::parse(D::SEED).unwrap_or_else(|e| {
+        eprintln!("{} 기본 데이터 파싱 실패: {}", D::LABEL, e);
+        D::default()
+    })
+}
+
+/// The bundled data with `user_data` merged over it
+pub fn merged<D: Dataset>(user_data: D) -> D {
+    let mut dataset = seed::<D>();
+    dataset.merge(user_data);
+    dataset
+}
+
+/// The user's imports stored at `path`; empty until something is imported
+pub fn read_user_data<D: Dataset>(path: &Path) -> Result<D, String> {
+    if !path.exists() {
+        return Ok(D::default());
+    }
+    let json = fs::read_to_string(path).map_err(|e| format!("{} 읽기 실패: {}", D::LABEL, e))?;
+    D::parse(&json)
+}
+
+pub fn write_user_data<D: Dataset>(path: &Path, dataset: &D) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(dataset).map_err(|e| format!("{} 직렬화 실패: {}", D::LABEL, e))?;
+    fs::write(path, json).map_err(|e| format!("{} 저장 실패: {}", D::LABEL, e))
+}
+
+/// The index in use: built from the seed data until the user's data is installed
+pub struct ActiveDataset<D: Dataset> {
+    index: OnceLock<RwLock<Arc<D::Index>>>,
+    // 사용자 데이터는 처음 쓸 때 한 번만 읽어 반영합니다
+    user_data_loaded: AtomicBool,
+    // 읽고-합치고-저장하는 사이에 다른 가져오기가 끼어들지 않도록 직렬화합니다
+    store_lock: Mutex<()>,
+}
+
+impl<D: Dataset> ActiveDataset<D> {
+    pub const fn new() -> Self {
+        Self {
+            index: OnceLock::new(),
+            user_data_loaded: AtomicBool::new(false),
+            store_lock: Mutex::const_new(()),
+        }
+    }
+
+    fn slot(&self) -> &RwLock<Arc<D::Index>> {
+        self.index.get_or_init(|| RwLock::new(Arc::new(seed::<D>().build())))
+    }
+
+    pub fn get(&self) -> Arc<D::Index> {
+        self.slot().read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replace the index in use with the seed data plus `user_data`
+    pub fn install(&self, user_data: D) -> Arc<D::Index> {
+        let index = Arc::new(merged(user_data).build());
+        *self.slot().write().unwrap_or_else(|e| e.into_inner()) = index.clone();
+        self.user_data_loaded.store(true, Ordering::Release);
+        index
+    }
+
+    /// The index with the user's data, which `read_user_data` supplies the first time only
+    pub fn load(&self, read_user_data: impl FnOnce() -> Result<D, String>) -> Result<Arc<D::Index>, String> {
+        if self.user_data_loaded.load(Ordering::Acquire) {
+            return Ok(self.get());
+        }
+        Ok(self.install(read_user_data()?))
+    }
+
+    /// Held while an import reads, merges and saves the user's data
+    pub async fn lock_store(&self) -> MutexGuard<'_, ()> {
+        self.store_lock.lock().await
+    }
+}
+
+// ============================================
+// Sources
+// ============================================
+
+/// A regulation, paper or review that rules cite by id
+pub trait Source: Clone {
+    fn id(&self) -> &str;
+}
+
+/// A rule that is only worth reporting with the source it is based on
+pub trait CitesSource {
+    fn source_id(&self) -> &str;
+
+    /// What the rule is about, for messages
+    fn subject(&self) -> String;
+}
+
+/// A dataset's sources by id
+pub struct Sources<S> {
+    by_id: HashMap<String, S>,
+}
+
+impl<S: Source> Sources<S> {
+    pub fn new(sources: Vec<S>) -> Self {
+        Self {
+            by_id: sources.into_iter().map(|s| (s.id().to_string(), s)).collect(),
+        }
+    }
+
+    /// The source `rule` cites. None when it is not defined, and then the rule must not be handed out
+    /// as evidence: every fact and pairing shown to the user has to say where it comes from.
+    pub fn cited_by(&self, rule: &impl CitesSource) -> Option<&S> {
+        self.by_id.get(rule.source_id())
+    }
+}
+
+/// Every rule must cite a source defined alongside it or in `known_sources`
+pub fn check_sources<S: Source, R: CitesSource>(rules: &[R], sources: &[S], known_sources: &[S]) -> Result<(), String> {
+    let defined = |id: &str| sources.iter().chain(known_sources).any(|s| s.id() == id);
+    match rules.iter().find(|rule| !defined(rule.source_id())) {
+        Some(rule) => Err(format!("출처가 정의되지 않은 규칙이 있습니다: {} ({})", rule.source_id(), rule.subject())),
+        None => Ok(()),
+    }
+}
+
+/// Incoming sources replace existing ones with the same id
+pub fn merge_sources<S: Source>(base: &mut Vec<S>, incoming: Vec<S>) {
+    for source in incoming {
+        match base.iter_mut().find(|s| s.id() == source.id()) {
+            Some(existing) => *existing = source,
+            None => base.push(source),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::sync::atomic::AtomicUsize;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Note {
+        id: String,
+    }
+
+    impl Source for Note {
+        fn id(&self) -> &str {
+            &self.id
+        }
+    }
+
+    #[derive(Default, Serialize, Deserialize)]
+    struct Notes {
+        sources: Vec<Note>,
+    }
+
+    impl CitesSource for Note {
+        fn source_id(&self) -> &str {
+            &self.id
+        }
+
+        fn subject(&self) -> String {
+            format!("note {}", self.id)
+        }
+    }
+
+    impl Dataset for Notes {
+        type Index = Vec<String>;
+
+        const SEED: &'static str = r#"{"sources": [{"id": "a"}, {"id": "b"}]}"#;
+        const LABEL: &'static str = "메모";
+
+        fn parse(json: &str) -> Result<Self, String> {
+            serde_json::from_str(json).map_err(|e| e.to_string())
+        }
+
+        fn merge(&mut self, incoming: Self) {
+            merge_sources(&mut self.sources, incoming.sources);
+        }
+
+        fn build(self) -> Vec<String> {
+            self.sources.into_iter().map(|s| s.id).collect()
+        }
+    }
+
+    fn notes(ids: &[&str]) -> Vec<Note> {
+        ids.iter().map(|id| Note { id: id.to_string() }).collect()
+    }
+
+    #[test]
+    fn merges_user_data_over_the_seed() {
+        let merged = merged(Notes { sources: notes(&["b", "c"]) });
+        assert_eq!(merged.build(), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn reads_the_user_file_only_once() {
+        let active: ActiveDataset<Notes> = ActiveDataset::new();
+        assert_eq!(*active.get(), ["a", "b"]);
+
+        let reads = AtomicUsize::new(0);
+        let read = || {
+            reads.fetch_add(1, Ordering::SeqCst);
+            Ok(Notes { sources: notes(&["c"]) })
+        };
+        assert_eq!(*active.load(read).unwrap(), ["a", "b", "c"]);
+        assert_eq!(*active.load(read).unwrap(), ["a", "b", "c"]);
+        assert_eq!(reads.load(Ordering::SeqCst), 1);
+
+        active.install(Notes { sources: notes(&["d"]) });
+        assert_eq!(*active.get(), ["a", "b", "d"]);
+    }
+
+    #[test]
+    fn a_failed_read_can_be_retried() {
+        let active: ActiveDataset<Notes> = ActiveDataset::new();
+        assert!(active.load(|| Err("읽기 실패".to_string())).is_err());
+        assert_eq!(*active.load(|| Ok(Notes::default())).unwrap(), ["a", "b"]);
+    }
+
+    #[test]
+    fn rules_must_cite_a_defined_or_known_source() {
+        let rules = notes(&["a", "x"]);
+        let error = check_sources(&rules, &notes(&["a"]), &[]).unwrap_err();
+        assert!(error.ends_with("x (note x)"), "{error}");
+        assert!(check_sources(&rules, &notes(&["a"]), &notes(&["x"])).is_ok());
+
+        let sources = Sources::new(notes(&["a"]));
+        assert!(sources.cited_by(&rules[0]).is_some());
+        assert!(sources.cited_by(&rules[1]).is_none());
+    }
+}
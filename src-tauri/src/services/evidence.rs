@@ -0,0 +1,290 @@
+use crate::models::EvidenceAssessment;
+
+// Study designs, strongest first
+pub const DESIGN_META_ANALYSIS: &str = "meta_analysis";
+pub const DESIGN_SYSTEMATIC_REVIEW: &str = "systematic_review";
+pub const DESIGN_RCT: &str = "randomized_controlled_trial";
+pub const DESIGN_CLINICAL_TRIAL: &str = "clinical_trial";
+pub const DESIGN_COHORT: &str = "cohort";
+pub const DESIGN_CASE_CONTROL: &str = "case_control";
+pub const DESIGN_CROSS_SECTIONAL: &str = "cross_sectional";
+pub const DESIGN_CASE_REPORT: &str = "case_report";
+pub const DESIGN_REVIEW: &str = "narrative_review";
+pub const DESIGN_EXPERIMENTAL: &str = "experimental";
+pub const DESIGN_UNKNOWN: &str = "unknown";
+
+pub const SUBJECT_HUMAN: &str = "human";
+pub const SUBJECT_ANIMAL: &str = "animal";
+pub const SUBJECT_IN_VITRO: &str = "in_vitro";
+pub const SUBJECT_UNKNOWN: &str = "unknown";
+
+// 이보다 오래된 연구는 한 등급 낮춥니다
+const STALE_AFTER_YEARS: i32 = 15;
+// 이보다 작은 인체 시험은 한 등급 낮춥니다
+const SMALL_SAMPLE: u32 = 20;
+
+/// What the grader needs to know about a paper
+pub struct PaperEvidenceInput<'a> {
+    pub title: &'a str,
+    pub abstract_text: &'a str,
+    pub publication_types: &'a [String],
+    pub mesh_terms: &'a [String],
+    pub year: &'a str,
+}
+
+/// Grade a paper from its PubMed publication types and MeSH headings, falling back to
+/// wording in the title and abstract when the record is not indexed yet.
+///
+/// A: meta-analysis/systematic review, or an adequately sized RCT, in humans
+/// B: other controlled human studies (small RCTs, trials, cohort, case-control)
+/// C: cross-sectional studies, case reports, narrative reviews, unclassified human studies
+/// D: animal and in-vitro work, or nothing to go on
+/// Papers older than 15 years drop one grade.
+pub fn assess(paper: &PaperEvidenceInput<'_>, current_year: i32) -> EvidenceAssessment {
+    let text = format!("{} {}", paper.title, paper.abstract_text).to_lowercase();
+    let study_design = study_design(paper.publication_types, paper.mesh_terms, &text);
+    let subject = subject(study_design, paper.mesh_terms, &text);
+    let sample_size = if subject == SUBJECT_HUMAN { sample_size(&text) } else { None };
+    let year = paper.year.get(..4).and_then(|y| y.parse::<i32>().ok());
+
+    let mut grade = base_grade(study_design, subject);
+    if study_design == DESIGN_RCT && sample_size.is_some_and(|n| n < SMALL_SAMPLE) {
+        grade = lower(grade);
+    }
+    if year.is_some_and(|y| current_year - y > STALE_AFTER_YEARS) {
+        grade = lower(grade);
+    }
+
+    EvidenceAssessment {
+        grade: grade.to_string(),
+        study_design: study_design.to_string(),
+        subject: subject.to_string(),
+        sample_size,
+        year,
+        weak: is_weak_grade(grade),
+    }
+}
+
+/// C and D grades cannot carry a claim on their own
+pub fn is_weak_grade(grade: &str) -> bool {
+    matches!(grade, "C" | "D")
+}
+
+/// Strongest grade in a set ("A" sorts first)
+pub fn best_grade<'a>(grades: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    grades.into_iter().min()
+}
+
+fn study_design(publication_types: &[String], mesh_terms: &[String], text: &str) -> &'static str {
+    let has_type = |name: &str| publication_types.iter().any(|t| t.eq_ignore_ascii_case(name));
+    let has_mesh = |name: &str| mesh_terms.iter().any(|t| t.eq_ignore_ascii_case(name));
+
+    if has_type("Meta-Analysis") {
+        return DESIGN_META_ANALYSIS;
+    }
+    if has_type("Systematic Review") {
+        return DESIGN_SYSTEMATIC_REVIEW;
+    }
+    if has_type("Randomized Controlled Trial") {
+        return DESIGN_RCT;
+    }
+    if publication_types.iter().any(|t| t.starts_with("Clinical Trial") || t == "Controlled Clinical Trial") {
+        return DESIGN_CLINICAL_TRIAL;
+    }
+    if has_mesh("Cohort Studies") || has_mesh("Prospective Studies") || has_mesh("Longitudinal Studies") {
+        return DESIGN_COHORT;
+    }
+    if has_mesh("Case-Control Studies") {
+        return DESIGN_CASE_CONTROL;
+    }
+    if has_mesh("Cross-Sectional Studies") {
+        return DESIGN_CROSS_SECTIONAL;
+    }
+    if has_type("Case Reports") {
+        return DESIGN_CASE_REPORT;
+    }
+    if has_type("Review") {
+        return DESIGN_REVIEW;
+    }
+
+    // 아직 색인되지 않은 최신 논문은 본문 표현으로 추정합니다
+    let mentions = |phrases: &[&str]| mentions(text, phrases);
+    if mentions(&["meta-analysis", "meta analysis"]) {
+        DESIGN_META_ANALYSIS
+    } else if mentions(&["systematic review"]) {
+        DESIGN_SYSTEMATIC_REVIEW
+    } else if mentions(&["randomized", "randomised"]) && mentions(&["controlled", "placebo", "vehicle"]) {
+        DESIGN_RCT
+    } else if mentions(&["clinical trial", "clinical study", "split-face", "double-blind"]) {
+        DESIGN_CLINICAL_TRIAL
+    } else if mentions(&["cohort"]) {
+        DESIGN_COHORT
+    } else if mentions(&["case-control", "case control"]) {
+        DESIGN_CASE_CONTROL
+    } else if mentions(&["cross-sectional", "survey"]) {
+        DESIGN_CROSS_SECTIONAL
+    } else if mentions(&["case report", "we report a case", "we present a case"]) {
+        DESIGN_CASE_REPORT
+    } else if mentions(&["this review", "we review", "narrative review"]) {
+        DESIGN_REVIEW
+    } else if mentions(&["in vitro", "cell line", "cultured", "mice", "rats", "murine", "in vivo"]) {
+        DESIGN_EXPERIMENTAL
+    } else {
+        DESIGN_UNKNOWN
+    }
+}
+
+fn subject(design: &str, mesh_terms: &[String], text: &str) -> &'static str {
+    let has_mesh = |names: &[&str]| mesh_terms.iter().any(|t| names.iter().any(|n| t.eq_ignore_ascii_case(n)));
+    let mentions = |phrases: &[&str]| mentions(text, phrases);
+
+    // 인체 대상 설계는 MeSH에 동물이 함께 있어도 인체로 봅니다
+    if matches!(design, DESIGN_RCT | DESIGN_CLINICAL_TRIAL | DESIGN_COHORT | DESIGN_CASE_CONTROL | DESIGN_CROSS_SECTIONAL | DESIGN_CASE_REPORT) {
+        return SUBJECT_HUMAN;
+    }
+
+    let animal = has_mesh(&["Animals", "Mice", "Rats", "Disease Models, Animal", "Swine", "Rabbits"]);
+    let in_vitro = has_mesh(&["In Vitro Techniques", "Cells, Cultured", "Cell Line", "Keratinocytes", "Fibroblasts"]);
+    let human = has_mesh(&["Humans"]);
+
+    if human && !animal && !in_vitro {
+        return SUBJECT_HUMAN;
+    }
+    if animal {
+        return SUBJECT_ANIMAL;
+    }
+    if in_vitro {
+        return SUBJECT_IN_VITRO;
+    }
+    if human {
+        return SUBJECT_HUMAN;
+    }
+
+    if mentions(&["in vitro", "cell line", "cultured", "keratinocytes", "fibroblasts"]) {
+        SUBJECT_IN_VITRO
+    } else if mentions(&["mice", "rats", "murine", "in vivo", "animal model"]) {
+        SUBJECT_ANIMAL
+    } else if mentions(&["participants", "volunteers", "patients", "subjects", "women", "men"]) {
+        SUBJECT_HUMAN
+    } else {
+        SUBJECT_UNKNOWN
+    }
+}
+
+/// Whether any phrase occurs as whole words ("men" must not match "treatment"); a trailing plural
+/// "s" is allowed so "cohort" still finds "cohorts"
+fn mentions(text: &str, phrases: &[&str]) -> bool {
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric());
+    phrases.iter().any(|phrase| {
+        text.match_indices(phrase).any(|(start, _)| {
+            let before = text[..start].chars().next_back();
+            let rest = &text[start + phrase.len()..];
+            let rest = rest.strip_prefix('s').unwrap_or(rest);
+            !is_word(before) && !is_word(rest.chars().next())
+        })
+    })
+}
+
+/// Number of human participants from phrases like "n = 42" or "42 healthy volunteers"
+fn sample_size(text: &str) -> Option<u32> {
+    const PARTICIPANT_WORDS: &[&str] = &[
+        "participants", "subjects", "volunteers", "patients", "women", "men", "adults", "individuals", "children",
+    ];
+    const SKIPPABLE: &[&str] = &["healthy", "female", "male", "korean", "asian", "adult", "eligible", "enrolled"];
+
+    let words: Vec<&str> = text
+        .split(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | ',' | ';' | ':'))
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    for (i, word) in words.iter().enumerate() {
+        // "n = 42", "n=42"
+        let after_n = match *word {
+            "n" if words.get(i + 1) == Some(&"=") => words.get(i + 2).copied(),
+            w if w.starts_with("n=") => Some(&w[2..]),
+            _ => None,
+        };
+        if let Some(n) = after_n.and_then(parse_count) {
+            return Some(n);
+        }
+
+        if let Some(n) = parse_count(word) {
+            let noun = words[i + 1..]
+                .iter()
+                .take(3)
+                .find(|w| !SKIPPABLE.contains(w))
+                .map(|w| w.trim_end_matches('.'));
+            if noun.is_some_and(|w| PARTICIPANT_WORDS.contains(&w)) {
+                return Some(n);
+            }
+        }
+    }
+    None
+}
+
+fn parse_count(word: &str) -> Option<u32> {
+    word.trim_end_matches('.').parse::<u32>().ok().filter(|n| (2..100_000).contains(n))
+}
+
+fn base_grade(design: &str, subject: &str) -> &'static str {
+    if subject == SUBJECT_ANIMAL || subject == SUBJECT_IN_VITRO {
+        return "D";
+    }
+    match design {
+        DESIGN_META_ANALYSIS | DESIGN_SYSTEMATIC_REVIEW | DESIGN_RCT => "A",
+        DESIGN_CLINICAL_TRIAL | DESIGN_COHORT | DESIGN_CASE_CONTROL => "B",
+        DESIGN_UNKNOWN if subject == SUBJECT_UNKNOWN => "D",
+        DESIGN_EXPERIMENTAL => "D",
+        _ => "C",
+    }
+}
+
+fn lower(grade: &str) -> &'static str {
+    match grade {
+        "A" => "B",
+        "B" => "C",
+        _ => "D",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grade(title: &str, abstract_text: &str) -> EvidenceAssessment {
+        let paper = PaperEvidenceInput {
+            title,
+            abstract_text,
+            publication_types: &[],
+            mesh_terms: &[],
+            year: "2022",
+        };
+        assess(&paper, 2025)
+    }
+
+    #[test]
+    fn matches_whole_words_only() {
+        assert!(!mentions("forty women were enrolled", &["men"]));
+        assert!(!mentions("treatment with a supplement reduced pigmentation", &["men"]));
+        assert!(mentions("twelve men and women", &["men"]));
+        assert!(mentions("three prospective cohorts", &["cohort"]));
+        assert!(mentions("(men)", &["men"]));
+    }
+
+    #[test]
+    fn cell_work_mentioning_treatment_is_not_human() {
+        let assessment = grade(
+            "Niacinamide inhibits melanosome transfer",
+            "Melanin content decreased after treatment; the supplement lowered pigmentation in b16 melanoma cells.",
+        );
+        assert_ne!(assessment.subject, SUBJECT_HUMAN);
+        assert!(assessment.weak);
+    }
+
+    #[test]
+    fn participant_wording_is_human() {
+        let assessment = grade("Topical niacinamide in adult women", "In total 42 women applied the cream twice daily.");
+        assert_eq!(assessment.subject, SUBJECT_HUMAN);
+        assert_eq!(assessment.sample_size, Some(42));
+    }
+}
@@ -1,10 +1,14 @@
-use reqwest::Client;
+use super::scheduler::{self, Provider, SendScheduled};
+use super::stream::{self, OnToken};
+use crate::models::{ModelInfo, ModelSettings, ProviderModels};
+use reqwest::{Client, Response};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 pub struct GoogleService {
     client: Client,
     api_key: String,
+    models: ProviderModels,
 }
 
 // Gemini API Request/Response types
@@ -42,6 +46,10 @@ struct GenerationConfig {
     temperature: f32,
     #[serde(rename = "maxOutputTokens")]
     max_output_tokens: u32,
+    #[serde(rename = "responseMimeType", skip_serializing_if = "Option::is_none")]
+    response_mime_type: Option<String>,
+    #[serde(rename = "responseJsonSchema", skip_serializing_if = "Option::is_none")]
+    response_json_schema: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -54,6 +62,19 @@ struct Candidate {
     content: GeminiContent,
 }
 
+// streamGenerateContent chunk; the last one may carry only finishReason/usage
+#[derive(Debug, Deserialize)]
+struct GeminiStreamChunk {
+    #[serde(default)]
+    candidates: Vec<StreamCandidate>,
+    error: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamCandidate {
+    content: Option<GeminiContent>,
+}
+
 // Image generation request for Gemini Imagen
 #[derive(Debug, Serialize)]
 struct ImagenRequest {
@@ -77,6 +98,21 @@ struct ImagenParameters {
 }
 
 // Gemini native image generation response (gemini-2.0-flash with image output)
+#[derive(Debug, Deserialize)]
+struct ModelListResponse {
+    #[serde(default)]
+    models: Vec<ModelEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ModelEntry {
+    name: String,
+    display_name: Option<String>,
+    #[serde(default)]
+    supported_generation_methods: Vec<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct GeminiImageResponse {
     candidates: Option<Vec<GeminiImageCandidate>>,
@@ -141,9 +177,14 @@ struct ImagenPrediction {
 
 impl GoogleService {
     pub fn new(api_key: &str) -> Self {
+        Self::with_models(api_key, ModelSettings::default().google)
+    }
+
+    pub fn with_models(api_key: &str, models: ProviderModels) -> Self {
         Self {
-            client: Client::new(),
+            client: scheduler::http_client(),
             api_key: api_key.to_string(),
+            models,
         }
     }
 
@@ -153,6 +194,106 @@ impl GoogleService {
         prompt: &str,
         system_prompt: Option<&str>,
     ) -> Result<String, String> {
+        self.generate_content(prompt, system_prompt, None).await
+    }
+
+    /// Generate JSON constrained to `schema` with Gemini's structured output mode
+    pub async fn generate_structured(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        schema: &serde_json::Value,
+    ) -> Result<String, String> {
+        self.generate_content(prompt, system_prompt, Some(schema)).await
+    }
+
+    /// Like `generate_text`, but streams the reply and passes each token to `on_token`
+    pub async fn stream_text(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        on_token: &mut OnToken<'_>,
+    ) -> Result<String, String> {
+        self.stream_content(prompt, system_prompt, None, on_token).await
+    }
+
+    /// Like `generate_structured`, but streams the JSON text as it is produced
+    pub async fn stream_structured(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        schema: &serde_json::Value,
+        on_token: &mut OnToken<'_>,
+    ) -> Result<String, String> {
+        self.stream_content(prompt, system_prompt, Some(schema), on_token).await
+    }
+
+    async fn generate_content(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        response_schema: Option<&serde_json::Value>,
+    ) -> Result<String, String> {
+        let request = self.content_request(prompt, system_prompt, response_schema);
+        let response = self.post_content("generateContent", &request).await?;
+
+        let result: GeminiResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("응답 파싱 실패: {}", e))?;
+
+        result
+            .candidates
+            .first()
+            .and_then(|c| c.content.parts.first())
+            .and_then(|p| p.text.clone())
+            .ok_or_else(|| "응답이 비어있습니다".to_string())
+    }
+
+    async fn stream_content(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        response_schema: Option<&serde_json::Value>,
+        on_token: &mut OnToken<'_>,
+    ) -> Result<String, String> {
+        let request = self.content_request(prompt, system_prompt, response_schema);
+        let response = self.post_content("streamGenerateContent", &request).await?;
+
+        let mut text = String::new();
+        stream::read_sse(response, |_, data| {
+            let chunk: GeminiStreamChunk =
+                serde_json::from_str(data).map_err(|e| format!("스트림 응답 파싱 실패: {}", e))?;
+            if let Some(error) = chunk.error {
+                return Err(format!("Gemini API 오류: {}", error));
+            }
+
+            let parts = chunk
+                .candidates
+                .first()
+                .and_then(|c| c.content.as_ref())
+                .map(|c| c.parts.as_slice())
+                .unwrap_or_default();
+            for delta in parts.iter().filter_map(|p| p.text.as_deref()) {
+                text.push_str(delta);
+                on_token(delta);
+            }
+            Ok(true)
+        })
+        .await?;
+
+        if text.is_empty() {
+            return Err("응답이 비어있습니다".to_string());
+        }
+        Ok(text)
+    }
+
+    fn content_request(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        response_schema: Option<&serde_json::Value>,
+    ) -> GeminiRequest {
         let mut contents = vec![];
 
         // Add system instruction if provided
@@ -181,25 +322,31 @@ impl GoogleService {
             role: Some("user".to_string()),
         });
 
-        let request = GeminiRequest {
+        GeminiRequest {
             contents,
             generation_config: Some(GenerationConfig {
-                temperature: 0.7,
-                max_output_tokens: 4096,
+                temperature: self.models.temperature,
+                max_output_tokens: self.models.max_tokens,
+                response_mime_type: response_schema.map(|_| "application/json".to_string()),
+                response_json_schema: response_schema.cloned(),
             }),
-        };
+        }
+    }
 
+    /// POST to `models/{text model}:{method}`; streaming methods get SSE framing via `alt=sse`
+    async fn post_content(&self, method: &str, request: &GeminiRequest) -> Result<Response, String> {
+        let alt = if method.starts_with("stream") { "alt=sse&" } else { "" };
         let url = format!(
-            "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.0-flash:generateContent?key={}",
-            self.api_key
+            "https://generativelanguage.googleapis.com/v1beta/models/{}:{}?{}key={}",
+            self.models.text, method, alt, self.api_key
         );
 
         let response = self
             .client
             .post(&url)
             .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
+            .json(request)
+            .send_scheduled(Provider::Google)
             .await
             .map_err(|e| format!("Gemini API 요청 실패: {}", e))?;
 
@@ -207,18 +354,7 @@ impl GoogleService {
             let error_text = response.text().await.unwrap_or_default();
             return Err(format!("Gemini API 오류: {}", error_text));
         }
-
-        let result: GeminiResponse = response
-            .json()
-            .await
-            .map_err(|e| format!("응답 파싱 실패: {}", e))?;
-
-        result
-            .candidates
-            .first()
-            .and_then(|c| c.content.parts.first())
-            .and_then(|p| p.text.clone())
-            .ok_or_else(|| "응답이 비어있습니다".to_string())
+        Ok(response)
     }
 
     /// Generate image using Gemini API
@@ -228,8 +364,8 @@ impl GoogleService {
         prompt: &str,
         aspect_ratio: &str,
     ) -> Result<String, String> {
-        // Default to Imagen 4 Standard
-        self.generate_image_with_model(prompt, aspect_ratio, "imagen-4.0-generate-001", None).await
+        let model = self.models.image.as_deref().unwrap_or("imagen-4.0-generate-001");
+        self.generate_image_with_model(prompt, aspect_ratio, model, None).await
     }
 
     /// Generate image using specified model
@@ -315,7 +451,7 @@ impl GoogleService {
             .post(&url)
             .header("Content-Type", "application/json")
             .json(&request_body)
-            .send()
+            .send_scheduled(Provider::Google)
             .await
             .map_err(|e| format!("{} API 요청 실패: {}", model_name, e))?;
 
@@ -386,7 +522,7 @@ impl GoogleService {
             .post(&url)
             .header("Content-Type", "application/json")
             .json(&request_body)
-            .send()
+            .send_scheduled(Provider::Google)
             .await
             .map_err(|e| format!("Gemini 이미지 API 요청 실패: {}", e))?;
 
@@ -464,7 +600,7 @@ impl GoogleService {
             .post(&url)
             .header("Content-Type", "application/json")
             .json(&request_body)
-            .send()
+            .send_scheduled(Provider::Google)
             .await
             .map_err(|e| format!("Imagen API 요청 실패: {}", e))?;
 
@@ -568,14 +704,16 @@ impl GoogleService {
         let request = GeminiRequest {
             contents,
             generation_config: Some(GenerationConfig {
-                temperature: 0.7,
-                max_output_tokens: 2048,
+                temperature: self.models.temperature,
+                max_output_tokens: self.models.max_tokens,
+                response_mime_type: None,
+                response_json_schema: None,
             }),
         };
 
         let url = format!(
-            "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.0-flash:generateContent?key={}",
-            self.api_key
+            "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}",
+            self.models.vision, self.api_key
         );
 
         let response = self
@@ -583,7 +721,7 @@ impl GoogleService {
             .post(&url)
             .header("Content-Type", "application/json")
             .json(&request)
-            .send()
+            .send_scheduled(Provider::Google)
             .await
             .map_err(|e| format!("Gemini Vision API 요청 실패: {}", e))?;
 
@@ -605,6 +743,54 @@ impl GoogleService {
             .ok_or_else(|| "응답이 비어있습니다".to_string())
     }
 
+    /// Models available to this API key
+    pub async fn list_models(&self) -> Result<Vec<ModelInfo>, String> {
+        let url = format!(
+            "https://generativelanguage.googleapis.com/v1beta/models?pageSize=1000&key={}",
+            self.api_key
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .send_scheduled(Provider::Google)
+            .await
+            .map_err(|e| format!("Gemini 모델 목록 요청 실패: {}", e))?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(format!("Gemini 모델 목록 오류: {}", error_text));
+        }
+
+        let result: ModelListResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("응답 파싱 실패: {}", e))?;
+
+        Ok(result
+            .models
+            .into_iter()
+            .filter_map(|m| {
+                let id = m.name.trim_start_matches("models/").to_string();
+                let methods = &m.supported_generation_methods;
+                let tasks: Vec<String> = if id.starts_with("imagen") || id.contains("-image") {
+                    vec!["image".to_string()]
+                } else if methods.iter().any(|g| g == "generateContent") && !id.contains("embedding") {
+                    vec!["text".to_string(), "vision".to_string()]
+                } else {
+                    vec![]
+                };
+
+                (!tasks.is_empty()).then_some(ModelInfo {
+                    id,
+                    display_name: m.display_name,
+                    tasks,
+                    reasoning: false,
+                })
+            })
+            .collect())
+    }
+
     /// Search web using Google Custom Search API
     pub async fn search_web(&self, query: &str, cx: &str) -> Result<Vec<SearchResult>, String> {
         let url = format!(
@@ -617,7 +803,7 @@ impl GoogleService {
         let response = self
             .client
             .get(&url)
-            .send()
+            .send_scheduled(Provider::Google)
             .await
             .map_err(|e| format!("Google Search API 요청 실패: {}", e))?;
 
@@ -0,0 +1,466 @@
+// Local ingredient dictionary: INCI names, Korean standard names (표준 성분명), aliases and
+// CAS/EC numbers. The bundled seed data is merged with entries the user imported.
+
+use super::dataset::{ActiveDataset, Dataset};
+use super::matching;
+use crate::models::{Ingredient, IngredientDataset, IngredientMatch};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// 검색 결과에 넣을 최소 점수
+const MIN_LOOKUP_SCORE: f64 = 0.5;
+
+// 자유 입력을 한 성분으로 확정할 최소 점수 (접두어 "나이아신" 정도는 통과)
+const MIN_RESOLVE_SCORE: f64 = 0.75;
+
+// PubMed 검색에 쓸 별칭 수 (INCI명 포함)
+const MAX_SEARCH_NAMES: usize = 5;
+
+pub struct IngredientDictionary {
+    entries: Vec<Ingredient>,
+    /// Normalized name, CAS or EC number → entry index
+    index: HashMap<String, usize>,
+}
+
+impl IngredientDictionary {
+    pub fn new(entries: Vec<Ingredient>) -> Self {
+        let mut index = HashMap::new();
+        for (i, ingredient) in entries.iter().enumerate() {
+            for name in all_names(ingredient) {
+                // 같은 이름이 여러 성분에 있으면 먼저 나온 성분이 가져갑니다
+                index.entry(name_key(name)).or_insert(i);
+            }
+        }
+        Self { entries, index }
+    }
+
+    pub fn entries(&self) -> &[Ingredient] {
+        &self.entries
+    }
+
+    /// Entry whose INCI name, Korean name, alias, CAS or EC number is exactly `name`
+    /// (ignoring case, spacing and punctuation)
+    pub fn get_by_name(&self, name: &str) -> Option<&Ingredient> {
+        let stripped = matching::tokenize(name).concat();
+        self.index
+            .get(&name_key(name))
+            .or_else(|| self.index.get(&stripped))
+            .map(|&i| &self.entries[i])
+    }
+
+    /// The ingredient a free-text query means: an exact name, else the clear best fuzzy match
+    pub fn resolve(&self, query: &str) -> Option<&Ingredient> {
+        if let Some(ingredient) = self.get_by_name(query) {
+            return Some(ingredient);
+        }
+
+        let matches = self.scored(query);
+        match matches.as_slice() {
+            [(best, score), rest @ ..] if *score >= MIN_RESOLVE_SCORE => {
+                // 점수가 같은 후보가 둘 이상이면 ("비타민") 하나로 정하지 않습니다
+                let tied = rest.first().is_some_and(|(_, next)| *next >= *score);
+                (!tied).then(|| &self.entries[best.0])
+            }
+            _ => None,
+        }
+    }
+
+    /// Entries resembling the query, best first: exact names, then prefixes, substrings and
+    /// near spellings (compared letter by letter for Hangul, so small typos still match)
+    pub fn lookup(&self, query: &str, limit: usize) -> Vec<IngredientMatch> {
+        self.scored(query)
+            .into_iter()
+            .take(limit)
+            .map(|((i, name), score)| IngredientMatch {
+                ingredient: self.entries[i].clone(),
+                matched_name: name.to_string(),
+                score,
+            })
+            .collect()
+    }
+
+    /// Entries with a name starting with `prefix`; a Hangul syllable still being typed
+    /// ("나이아ㅅ") matches by its letters
+    pub fn autocomplete(&self, prefix: &str, limit: usize) -> Vec<IngredientMatch> {
+        let typed = matching::decompose_hangul(&name_key(prefix));
+        if typed.is_empty() {
+            return vec![];
+        }
+
+        let mut matches: Vec<((usize, &str), f64)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, ingredient)| {
+                display_names(ingredient)
+                    .filter_map(|name| {
+                        let letters = matching::decompose_hangul(&name_key(name));
+                        letters.starts_with(&typed).then(|| {
+                            // 짧은 이름일수록 입력과 가깝습니다
+                            let score = typed.chars().count() as f64 / letters.chars().count() as f64;
+                            ((i, name), score)
+                        })
+                    })
+                    .max_by(|a, b| a.1.total_cmp(&b.1))
+            })
+            .collect();
+
+        matches.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0 .1.cmp(b.0 .1)));
+        matches
+            .into_iter()
+            .take(limit)
+            .map(|((i, name), score)| IngredientMatch {
+                ingredient: self.entries[i].clone(),
+                matched_name: name.to_string(),
+                score,
+            })
+            .collect()
+    }
+
+    /// (entry index, best matching name) and score for every entry above the lookup threshold
+    fn scored(&self, query: &str) -> Vec<((usize, &str), f64)> {
+        let query_key = name_key(query);
+        if query_key.is_empty() {
+            return vec![];
+        }
+        let query_letters = matching::decompose_hangul(&query_key);
+
+        let mut matches: Vec<((usize, &str), f64)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, ingredient)| {
+                all_names(ingredient)
+                    .map(|name| ((i, name), name_score(&query_key, &query_letters, name)))
+                    .max_by(|a, b| a.1.total_cmp(&b.1))
+            })
+            .filter(|(_, score)| *score >= MIN_LOOKUP_SCORE)
+            .collect();
+
+        matches.sort_by(|a, b| b.1.total_cmp(&a.1));
+        matches
+    }
+}
+
+fn name_score(query_key: &str, query_letters: &str, name: &str) -> f64 {
+    let key = name_key(name);
+    if key.is_empty() {
+        return 0.0;
+    }
+    if key == query_key {
+        return 1.0;
+    }
+
+    let coverage = query_key.chars().count() as f64 / key.chars().count() as f64;
+    if query_key.chars().count() >= 2 {
+        if key.starts_with(query_key) {
+            return 0.75 + 0.2 * coverage;
+        }
+        if key.contains(query_key) {
+            return 0.55 + 0.2 * coverage;
+        }
+    }
+
+    // 오타: 글자 단위(한글은 자모 단위)로 얼마나 겹치는지
+    let letters = matching::decompose_hangul(&key);
+    0.9 * matching::bigram_similarity(query_key, &key).max(matching::bigram_similarity(query_letters, &letters))
+}
+
+/// Lower-case, punctuation and spacing removed: "Vitamin B-3" → "vitaminb3", "98-92-0" → "98920"
+fn name_key(name: &str) -> String {
+    matching::normalize(name).replace(' ', "")
+}
+
+/// INCI name, Korean name and aliases
+fn display_names(ingredient: &Ingredient) -> impl Iterator<Item = &str> {
+    std::iter::once(ingredient.inci_name.as_str())
+        .chain(std::iter::once(ingredient.korean_name.as_str()))
+        .chain(ingredient.aliases.iter().map(String::as_str))
+        .filter(|name| !name.trim().is_empty())
+}
+
+/// Display names plus CAS and EC numbers
+fn all_names(ingredient: &Ingredient) -> impl Iterator<Item = &str> {
+    display_names(ingredient)
+        .chain(ingredient.cas_numbers.iter().map(String::as_str))
+        .chain(ingredient.ec_numbers.iter().map(String::as_str))
+}
+
+/// Every name of the ingredient (INCI, Korean, aliases), for keyword matching
+pub fn names(ingredient: &Ingredient) -> Vec<String> {
+    display_names(ingredient).map(str::to_string).collect()
+}
+
+/// English names worth sending to PubMed: the INCI name first, then aliases. Short
+/// abbreviations are left out since they are ambiguous there ("BHA" is also an antioxidant).
+pub fn search_names(ingredient: &Ingredient) -> Vec<String> {
+    display_names(ingredient)
+        .filter(|name| !name.chars().any(|c| ('가'..='힣').contains(&c)) && name.chars().count() > 4)
+        .take(MAX_SEARCH_NAMES)
+        .map(str::to_string)
+        .collect()
+}
+
+// ============================================
+// Seed data and imports
+// ============================================
+
+/// Imported entries are added, or replace the seed entry they describe
+impl Dataset for IngredientDataset {
+    type Index = IngredientDictionary;
+
+    const SEED: &'static str = include_str!("../../data/ingredients.json");
+    const LABEL: &'static str = "성분 사전";
+
+    fn parse(json: &str) -> Result<Self, String> {
+        Ok(IngredientDataset {
+            version: None,
+            ingredients: parse_dataset(json)?,
+        })
+    }
+
+    fn merge(&mut self, incoming: Self) {
+        merge_entries(&mut self.ingredients, incoming.ingredients);
+    }
+
+    fn build(self) -> IngredientDictionary {
+        IngredientDictionary::new(self.ingredients)
+    }
+}
+
+/// Read a dataset: `{"ingredients": [...]}` or a bare array of entries. Names are trimmed and
+/// missing ids are derived from the INCI name.
+pub fn parse_dataset(json: &str) -> Result<Vec<Ingredient>, String> {
+    let entries = match serde_json::from_str::<IngredientDataset>(json) {
+        Ok(dataset) => dataset.ingredients,
+        Err(dataset_error) => serde_json::from_str::<Vec<Ingredient>>(json)
+            .map_err(|_| format!("성분 데이터 형식이 올바르지 않습니다: {}", dataset_error))?,
+    };
+
+    entries
+        .into_iter()
+        .enumerate()
+        .map(|(i, ingredient)| clean_entry(ingredient).map_err(|e| format!("{}번째 성분: {}", i + 1, e)))
+        .collect()
+}
+
+fn clean_entry(ingredient: Ingredient) -> Result<Ingredient, String> {
+    let clean_list = |values: Vec<String>| -> Vec<String> {
+        let mut cleaned: Vec<String> = Vec::new();
+        for value in values.into_iter().map(|v| v.trim().to_string()) {
+            if !value.is_empty() && !cleaned.contains(&value) {
+                cleaned.push(value);
+            }
+        }
+        cleaned
+    };
+
+    let inci_name = ingredient.inci_name.trim().to_string();
+    if inci_name.is_empty() {
+        return Err("INCI명이 없습니다".to_string());
+    }
+    let id = match ingredient.id.trim() {
+        "" => slug(&inci_name),
+        id => id.to_string(),
+    };
+
+    Ok(Ingredient {
+        id,
+        inci_name,
+        korean_name: ingredient.korean_name.trim().to_string(),
+        aliases: clean_list(ingredient.aliases),
+        cas_numbers: clean_list(ingredient.cas_numbers),
+        ec_numbers: clean_list(ingredient.ec_numbers),
+        functions: clean_list(ingredient.functions),
+    })
+}
+
+/// "Palmitoyl Pentapeptide-4" → "palmitoyl-pentapeptide-4"
+fn slug(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Whether `incoming` describes an entry already in `entries` (same id or INCI name)
+pub fn find_entry(entries: &[Ingredient], incoming: &Ingredient) -> Option<usize> {
+    let inci = name_key(&incoming.inci_name);
+    entries
+        .iter()
+        .position(|e| e.id == incoming.id || name_key(&e.inci_name) == inci)
+}
+
+/// Add new entries and replace existing ones (keeping their id); returns (added, updated)
+pub fn merge_entries(entries: &mut Vec<Ingredient>, incoming: Vec<Ingredient>) -> (usize, usize) {
+    let (mut added, mut updated) = (0, 0);
+    for ingredient in incoming {
+        match find_entry(entries, &ingredient) {
+            Some(i) => {
+                let id = entries[i].id.clone();
+                entries[i] = Ingredient { id, ..ingredient };
+                updated += 1;
+            }
+            None => {
+                entries.push(ingredient);
+                added += 1;
+            }
+        }
+    }
+    (added, updated)
+}
+
+// ============================================
+// Active dictionary
+// ============================================
+
+static ACTIVE: ActiveDataset<IngredientDataset> = ActiveDataset::new();
+
+/// The seed data plus the user's entries once they are loaded or installed
+pub fn active() -> &'static ActiveDataset<IngredientDataset> {
+    &ACTIVE
+}
+
+/// The dictionary in use: seed data until the user's entries are installed
+pub fn dictionary() -> Arc<IngredientDictionary> {
+    ACTIVE.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENTRIES: &str = r#"{"ingredients": [
+        {"id": "niacinamide", "inci_name": "Niacinamide", "korean_name": "나이아신아마이드",
+         "aliases": ["Nicotinamide", "니코틴아마이드", "Vitamin B3", "비타민B3"], "cas_numbers": ["98-92-0"]},
+        {"id": "niacin", "inci_name": "Niacin", "korean_name": "나이아신", "aliases": ["Nicotinic Acid"]},
+        {"id": "retinol", "inci_name": "Retinol", "korean_name": "레티놀", "aliases": ["Vitamin A", "비타민A"]},
+        {"id": "ascorbic-acid", "inci_name": "Ascorbic Acid", "korean_name": "아스코빅애씨드",
+         "aliases": ["Vitamin C", "비타민C"]}
+    ]}"#;
+
+    fn dictionary() -> IngredientDictionary {
+        IngredientDictionary::new(parse_dataset(ENTRIES).unwrap())
+    }
+
+    fn entry(id: &str, inci_name: &str) -> Ingredient {
+        Ingredient {
+            id: id.to_string(),
+            inci_name: inci_name.to_string(),
+            korean_name: String::new(),
+            aliases: vec![],
+            cas_numbers: vec![],
+            ec_numbers: vec![],
+            functions: vec![],
+        }
+    }
+
+    fn ids(matches: &[IngredientMatch]) -> Vec<&str> {
+        matches.iter().map(|m| m.ingredient.id.as_str()).collect()
+    }
+
+    #[test]
+    fn resolves_exact_names_and_clear_best_matches() {
+        let dictionary = dictionary();
+        let resolve = |query: &str| dictionary.resolve(query).map(|i| i.id.as_str());
+
+        assert_eq!(resolve("나이아신아마이드가"), Some("niacinamide"));
+        assert_eq!(resolve("VITAMIN b-3"), Some("niacinamide"));
+        assert_eq!(resolve("98-92-0"), Some("niacinamide"));
+        assert_eq!(resolve("나이아신"), Some("niacin"));
+        assert_eq!(resolve("레티"), Some("retinol"));
+        // "비타민A"와 "비타민C"가 같은 점수라 하나로 정하지 않습니다
+        assert_eq!(resolve("비타민"), None);
+        assert_eq!(resolve("글리세린"), None);
+        assert_eq!(resolve("  "), None);
+    }
+
+    #[test]
+    fn looks_up_exact_names_before_prefixes_and_typos() {
+        let dictionary = dictionary();
+
+        let matches = dictionary.lookup("나이아신", 10);
+        assert_eq!(ids(&matches), ["niacin", "niacinamide"]);
+        assert_eq!((matches[0].matched_name.as_str(), matches[0].score), ("나이아신", 1.0));
+        assert_eq!(matches[1].matched_name, "나이아신아마이드");
+        assert_eq!(dictionary.lookup("나이아신", 1).len(), 1);
+
+        // 자모 단위로 비교하므로 받침 하나 틀린 것도 찾습니다
+        let typo = dictionary.lookup("레티눌", 10);
+        assert_eq!(ids(&typo), ["retinol"]);
+        assert!(typo[0].score < 1.0);
+
+        assert!(dictionary.lookup("zzz", 10).is_empty());
+    }
+
+    #[test]
+    fn autocompletes_syllables_still_being_typed() {
+        let dictionary = dictionary();
+
+        let matches = dictionary.autocomplete("나이아ㅅ", 10);
+        assert_eq!(ids(&matches), ["niacin", "niacinamide"]);
+        assert_eq!(matches[1].matched_name, "나이아신아마이드");
+        assert_eq!(ids(&dictionary.autocomplete("나이아시", 10)), ["niacin", "niacinamide"]);
+        assert_eq!(ids(&dictionary.autocomplete("레ㅌ", 10)), ["retinol"]);
+
+        // 짧은 이름이 먼저, 점수가 같으면 이름 순
+        let vitamins = dictionary.autocomplete("vitamin", 10);
+        let names: Vec<&str> = vitamins.iter().map(|m| m.matched_name.as_str()).collect();
+        assert_eq!(names, ["Vitamin A", "Vitamin C", "Vitamin B3"]);
+        assert_eq!(dictionary.autocomplete("vitamin", 2).len(), 2);
+
+        assert!(dictionary.autocomplete("", 10).is_empty());
+        assert!(dictionary.autocomplete("나이아ㅋ", 10).is_empty());
+    }
+
+    #[test]
+    fn parses_bare_arrays_and_derives_ids() {
+        let entries = parse_dataset(
+            r#"[{"inci_name": " Palmitoyl Pentapeptide-4 ", "aliases": ["Matrixyl", " ", "Matrixyl"]},
+                {"id": "custom", "inci_name": "Bakuchiol"}]"#,
+        )
+        .unwrap();
+        assert_eq!(entries[0].id, "palmitoyl-pentapeptide-4");
+        assert_eq!(entries[0].inci_name, "Palmitoyl Pentapeptide-4");
+        assert_eq!(entries[0].aliases, ["Matrixyl"]);
+        assert_eq!(entries[1].id, "custom");
+
+        assert_eq!(parse_dataset(ENTRIES).unwrap().len(), 4);
+
+        let error = parse_dataset(r#"[{"inci_name": "Retinol"}, {"inci_name": " "}]"#).unwrap_err();
+        assert!(error.starts_with("2번째 성분"), "{error}");
+        assert!(parse_dataset(r#"{"items": []}"#).unwrap_err().starts_with("성분 데이터 형식이 올바르지 않습니다"));
+    }
+
+    #[test]
+    fn merges_entries_by_id_or_inci_name() {
+        let mut entries = vec![entry("niacinamide", "Niacinamide"), entry("retinol", "Retinol")];
+        let mut renamed = entry("retinol-2", "RETINOL");
+        renamed.korean_name = "레티놀".to_string();
+
+        let counts = merge_entries(
+            &mut entries,
+            vec![renamed, entry("niacinamide", "Nicotinamide"), entry("bakuchiol", "Bakuchiol")],
+        );
+        assert_eq!(counts, (1, 2));
+        let merged: Vec<(&str, &str)> = entries.iter().map(|e| (e.id.as_str(), e.inci_name.as_str())).collect();
+        // 기존 항목의 id는 그대로 둡니다
+        assert_eq!(merged, [("niacinamide", "Nicotinamide"), ("retinol", "RETINOL"), ("bakuchiol", "Bakuchiol")]);
+        assert_eq!(entries[1].korean_name, "레티놀");
+    }
+
+    #[test]
+    fn searches_under_english_names_only() {
+        let mut ingredient = entry("salicylic-acid", "Salicylic Acid");
+        ingredient.korean_name = "살리실릭애씨드".to_string();
+        let aliases = ["BHA", "살리실산", "2-Hydroxybenzoic Acid", "Orthohydroxybenzoic Acid", "Salicylate"];
+        ingredient.aliases = aliases.iter().chain(&["Spiroylic Acid", "Salicylic"]).map(|a| a.to_string()).collect();
+
+        assert_eq!(
+            search_names(&ingredient),
+            ["Salicylic Acid", "2-Hydroxybenzoic Acid", "Orthohydroxybenzoic Acid", "Salicylate", "Spiroylic Acid"]
+        );
+        assert_eq!(names(&ingredient).len(), 9);
+    }
+}
@@ -0,0 +1,552 @@
+// Whole-product ingredient lists (전성분) as pasted from packaging or read from a photo: split into entries,
+// resolved against the ingredient dictionary and checked against the regulatory data and pairing rules.
+
+use super::compatibility::CompatibilityEngine;
+use super::ingredients::IngredientDictionary;
+use super::matching;
+use super::regulation::RegulatoryDatabase;
+use crate::models::project::{
+    ProjectLabelAnalysis, ProjectLabelCombination, ProjectLabelEntry, ProjectResearchItem, ProjectResearchReport,
+    ProjectSourceReference,
+};
+use crate::models::{
+    BoundingBox, Compatibility, Ingredient, LabelCombination, LabelEntry, LabelFlag, LabelOcrEntry, LabelOcrResult,
+    LabelReport, RegulatoryFact, RegulatoryList, RegulatoryStatus,
+};
+use chrono::Utc;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// Headings printed in front of the list ("전성분:", "Ingredients:")
+const HEADINGS: &[&str] = &["전성분", "성분", "ingredients", "ingredient", "composition"];
+
+// 확인되지 않은 항목에 비슷한 성분명을 제안할 최소 점수
+const MIN_SUGGESTION_SCORE: f64 = 0.75;
+
+/// Split a pasted ingredient list into entries. Commas, semicolons and middle dots separate entries,
+/// except inside parentheses and between digits ("1,2-Hexanediol"). Line breaks separate entries only
+/// in a list without any of those; otherwise a name wrapped onto the next line is joined with a space.
+pub fn split_label(text: &str) -> Vec<String> {
+    let chars: Vec<char> = strip_heading(text).chars().collect();
+    let mut delimiters = Vec::new();
+    let mut line_breaks = Vec::new();
+    let mut depth = 0usize;
+
+    for (i, &c) in chars.iter().enumerate() {
+        match c {
+            '(' | '[' | '（' => depth += 1,
+            ')' | ']' | '）' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        if depth > 0 {
+            continue;
+        }
+
+        match c {
+            ',' | '，' => {
+                let between_digits = i > 0
+                    && chars[i - 1].is_ascii_digit()
+                    && chars.get(i + 1).is_some_and(|next| next.is_ascii_digit());
+                if !between_digits {
+                    delimiters.push(i);
+                }
+            }
+            ';' | '、' | '·' | '•' => delimiters.push(i),
+            '\n' | '\r' => line_breaks.push(i),
+            _ => {}
+        }
+    }
+
+    let separators = if delimiters.is_empty() { line_breaks } else { delimiters };
+    let mut entries = Vec::new();
+    let mut current = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if separators.binary_search(&i).is_ok() {
+            entries.push(std::mem::take(&mut current));
+        } else if matches!(c, '\n' | '\r') {
+            current.push(' ');
+        } else {
+            current.push(c);
+        }
+    }
+    entries.push(current);
+
+    entries.iter().map(|e| clean_entry(e)).filter(|e| !e.is_empty()).collect()
+}
+
+fn strip_heading(text: &str) -> &str {
+    let Some((head, rest)) = text.split_once([':', '：']) else {
+        return text;
+    };
+    let words = matching::tokenize(head);
+    let is_heading = words.len() <= 3 && words.iter().any(|w| HEADINGS.contains(&w.as_str()));
+    if is_heading {
+        rest
+    } else {
+        text
+    }
+}
+
+/// "Niacinamide (5%)*" → "Niacinamide"
+fn clean_entry(entry: &str) -> String {
+    let mut cleaned = String::new();
+    let mut group = String::new();
+    let mut depth = 0usize;
+
+    // 괄호 안이 함량 표시이면 괄호째 버립니다
+    for c in entry.chars() {
+        match c {
+            '(' | '（' => {
+                depth += 1;
+                group.push(c);
+            }
+            ')' | '）' if depth > 0 => {
+                depth -= 1;
+                group.push(c);
+                if depth == 0 {
+                    if !group.contains('%') {
+                        cleaned.push_str(&group);
+                    }
+                    group.clear();
+                }
+            }
+            _ if depth > 0 => group.push(c),
+            _ => cleaned.push(c),
+        }
+    }
+    cleaned.push_str(&group);
+
+    cleaned
+        .split_whitespace()
+        .filter(|word| !word.ends_with('%'))
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_matches(|c: char| matches!(c, '*' | '†' | '‡' | '.' | '-' | ' '))
+        .to_string()
+}
+
+/// The dictionary entry a label name means. "Water(Aqua)" and "정제수(물)" are tried as a whole,
+/// then by the name outside and inside the parentheses. Only exact names and aliases count: a near
+/// spelling is often a different ingredient ("Isobutylparaben", "Butylparaben").
+fn resolve_entry<'a>(dictionary: &'a IngredientDictionary, name: &str) -> Option<&'a Ingredient> {
+    let mut candidates = vec![name.to_string()];
+    if let Some((outside, inside)) = name.split_once(['(', '（']) {
+        candidates.push(outside.to_string());
+        candidates.extend(
+            inside
+                .trim_end_matches([')', '）'])
+                .split(['/', ',', '('])
+                .map(|part| part.trim_end_matches([')', '）']).to_string()),
+        );
+    }
+
+    candidates
+        .iter()
+        .map(|c| c.trim())
+        .filter(|c| !c.is_empty())
+        .find_map(|c| dictionary.get_by_name(c))
+}
+
+/// INCI name of the closest dictionary entry for a name that did not resolve, shown as "did you mean";
+/// no regulatory facts are looked up for it
+fn suggestion(dictionary: &IngredientDictionary, name: &str) -> Option<String> {
+    dictionary
+        .lookup(name, 1)
+        .into_iter()
+        .find(|m| m.score >= MIN_SUGGESTION_SCORE)
+        .map(|m| m.ingredient.inci_name)
+}
+
+fn flags(ingredient: &Ingredient, facts: &[RegulatoryFact]) -> Vec<LabelFlag> {
+    let mut flags = Vec::new();
+    let mut add = |flag: LabelFlag| {
+        if !flags.contains(&flag) {
+            flags.push(flag);
+        }
+    };
+
+    if ingredient.functions.iter().any(|f| f == "향료") {
+        add(LabelFlag::Fragrance);
+    }
+    for fact in facts {
+        match (fact.list, fact.status) {
+            (RegulatoryList::MfdsAllergen, _) => add(LabelFlag::Allergen),
+            (RegulatoryList::PregnancyCaution, _) => add(LabelFlag::PregnancyCaution),
+            (RegulatoryList::InfantCaution, _) => add(LabelFlag::InfantCaution),
+            (_, RegulatoryStatus::Prohibited) => add(LabelFlag::Prohibited),
+            (_, RegulatoryStatus::Restricted) => add(LabelFlag::Restricted),
+            (_, RegulatoryStatus::Caution) => {}
+        }
+    }
+    flags
+}
+
+/// Cautionary pairs among the resolved entries, named as printed on the label
+fn combinations(entries: &[LabelEntry], engine: &CompatibilityEngine) -> Vec<LabelCombination> {
+    let ingredients: Vec<Ingredient> = entries.iter().filter_map(|e| e.ingredient.clone()).collect();
+    let label_name = |ingredient: &Ingredient| {
+        entries
+            .iter()
+            .find(|e| e.ingredient.as_ref().is_some_and(|i| i.id == ingredient.id))
+            .map(|e| e.name.clone())
+            .unwrap_or_else(|| ingredient.inci_name.clone())
+    };
+
+    engine
+        .pairings(&ingredients)
+        .into_iter()
+        .filter(|p| p.compatibility == Compatibility::Cautionary)
+        .map(|p| LabelCombination {
+            ingredients: vec![label_name(&p.first), label_name(&p.second)],
+            reason: p.rationale,
+            advice: p.advice,
+            source: p.source,
+        })
+        .collect()
+}
+
+/// Resolve and check every entry of a pasted ingredient list
+pub fn analyze(
+    text: &str,
+    product_name: Option<String>,
+    dictionary: &IngredientDictionary,
+    database: &RegulatoryDatabase,
+    engine: &CompatibilityEngine,
+) -> LabelReport {
+    let entries: Vec<LabelEntry> = split_label(text)
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let ingredient = resolve_entry(dictionary, &name).cloned();
+            let regulatory_facts = ingredient.as_ref().map(|i| database.facts(&i.id)).unwrap_or_default();
+            let flags = ingredient.as_ref().map(|i| flags(i, &regulatory_facts)).unwrap_or_default();
+            let suggestion = if ingredient.is_none() { suggestion(dictionary, &name) } else { None };
+            LabelEntry {
+                position: i + 1,
+                name,
+                ingredient,
+                suggestion,
+                flags,
+                regulatory_facts,
+            }
+        })
+        .collect();
+
+    let unresolved = entries
+        .iter()
+        .filter(|e| e.ingredient.is_none())
+        .map(|e| e.name.clone())
+        .collect();
+    let combinations = combinations(&entries, engine);
+    let summary = summarize(product_name.as_deref(), &entries, &combinations);
+
+    LabelReport {
+        product_name,
+        entries,
+        unresolved,
+        combinations,
+        summary,
+    }
+}
+
+fn summarize(product_name: Option<&str>, entries: &[LabelEntry], combinations: &[LabelCombination]) -> String {
+    let resolved = entries.iter().filter(|e| e.ingredient.is_some()).count();
+    let mut summary = format!(
+        "{}전성분 {}개 중 {}개 확인",
+        product_name.map(|n| format!("{} ", n)).unwrap_or_default(),
+        entries.len(),
+        resolved
+    );
+
+    let labels = [
+        (LabelFlag::Prohibited, "사용 금지"),
+        (LabelFlag::Restricted, "사용 제한"),
+        (LabelFlag::Fragrance, "향료"),
+        (LabelFlag::Allergen, "알레르기 유발 성분"),
+        (LabelFlag::PregnancyCaution, "임신 중 주의"),
+        (LabelFlag::InfantCaution, "영유아 주의"),
+    ];
+    let flagged: Vec<String> = labels
+        .iter()
+        .filter_map(|(flag, label)| {
+            let names: Vec<&str> = entries
+                .iter()
+                .filter(|e| e.flags.contains(flag))
+                .map(|e| e.name.as_str())
+                .collect();
+            (!names.is_empty()).then(|| format!("{} {}개({})", label, names.len(), names.join(", ")))
+        })
+        .collect();
+
+    if !flagged.is_empty() {
+        summary.push_str(". ");
+        summary.push_str(&flagged.join(", "));
+    }
+    if !combinations.is_empty() {
+        summary.push_str(&format!(". 주의할 조합 {}건", combinations.len()));
+    }
+    summary
+}
+
+// ============================================
+// Label photos
+// ============================================
+
+// 이보다 낮은 신뢰도로 읽힌 항목은 사진과 대조해 봐야 합니다
+const MIN_OCR_CONFIDENCE: f64 = 0.7;
+
+/// Shape the vision model must return for a label photo (also the source of its JSON schema)
+#[derive(Deserialize, JsonSchema)]
+pub struct LabelOcrOutput {
+    /// Ingredient list entries in label order; other text on the package is left out
+    entries: Vec<LabelOcrEntryOutput>,
+}
+
+impl LabelOcrOutput {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct LabelOcrEntryOutput {
+    /// The entry exactly as printed
+    text: String,
+    /// How legible the entry was, 0 to 1
+    #[schemars(range(min = 0.0, max = 1.0))]
+    confidence: f64,
+    /// Where the entry is, as fractions of the image size; null when unsure
+    #[serde(default)]
+    bounding_box: Option<BoundingBoxOutput>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct BoundingBoxOutput {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+/// Entries read from a label photo, cleaned and resolved against the dictionary. An entry the model read
+/// as several ingredients is split; the parts share its confidence and box. `image_size` lets boxes given
+/// in pixels be turned into fractions.
+pub fn read_ocr_output(
+    output: LabelOcrOutput,
+    image_size: Option<(u32, u32)>,
+    dictionary: &IngredientDictionary,
+) -> LabelOcrResult {
+    let mut entries: Vec<LabelOcrEntry> = Vec::new();
+    for read in output.entries {
+        let confidence = read.confidence.clamp(0.0, 1.0);
+        let bounding_box = read.bounding_box.and_then(|b| to_bounding_box(b, image_size));
+
+        for name in split_label(&read.text) {
+            let ingredient = resolve_entry(dictionary, &name).cloned();
+            entries.push(LabelOcrEntry {
+                position: entries.len() + 1,
+                text: read.text.trim().to_string(),
+                needs_review: confidence < MIN_OCR_CONFIDENCE || ingredient.is_none(),
+                name,
+                ingredient,
+                confidence,
+                bounding_box,
+            });
+        }
+    }
+
+    let label_text = entries.iter().map(|e| e.name.as_str()).collect::<Vec<_>>().join(", ");
+    LabelOcrResult { entries, label_text }
+}
+
+/// Fractions of the image, clipped to it; None for empty boxes or pixel boxes of an image of unknown size
+fn to_bounding_box(b: BoundingBoxOutput, image_size: Option<(u32, u32)>) -> Option<BoundingBox> {
+    let in_pixels = [b.x, b.y, b.width, b.height].iter().any(|v| *v > 1.0);
+    let (x, y, width, height) = match (in_pixels, image_size) {
+        (false, _) => (b.x, b.y, b.width, b.height),
+        (true, Some((w, h))) if w > 0 && h > 0 => {
+            let (w, h) = (w as f64, h as f64);
+            (b.x / w, b.y / h, b.width / w, b.height / h)
+        }
+        (true, _) => return None,
+    };
+
+    let x = x.clamp(0.0, 1.0);
+    let y = y.clamp(0.0, 1.0);
+    let width = width.min(1.0 - x);
+    let height = height.min(1.0 - y);
+    (width > 0.0 && height > 0.0).then_some(BoundingBox { x, y, width, height })
+}
+
+// ============================================
+// Saving as project research
+// ============================================
+
+/// "식품의약품안전처 별표 2: 최대 1% (보존제로서)"
+fn fact_note(fact: &RegulatoryFact) -> String {
+    let mut note = fact.source.issuer.clone();
+    if let Some(reference) = &fact.reference {
+        note.push_str(&format!(" {}", reference));
+    }
+    let detail: Vec<String> = fact
+        .max_concentration
+        .iter()
+        .map(|max| format!("최대 {}", max))
+        .chain(fact.conditions.iter().map(|c| format!("({})", c)))
+        .collect();
+    if !detail.is_empty() {
+        note.push_str(&format!(": {}", detail.join(" ")));
+    }
+    note
+}
+
+fn to_project_analysis(report: &LabelReport) -> ProjectLabelAnalysis {
+    ProjectLabelAnalysis {
+        product_name: report.product_name.clone(),
+        entries: report
+            .entries
+            .iter()
+            .map(|e| ProjectLabelEntry {
+                position: e.position,
+                name: e.name.clone(),
+                inci_name: e.ingredient.as_ref().map(|i| i.inci_name.clone()),
+                korean_name: e.ingredient.as_ref().map(|i| i.korean_name.clone()),
+                flags: e.flags.clone(),
+                notes: e.regulatory_facts.iter().map(fact_note).collect(),
+            })
+            .collect(),
+        combinations: report
+            .combinations
+            .iter()
+            .map(|c| ProjectLabelCombination {
+                ingredients: c.ingredients.clone(),
+                reason: c.reason.clone(),
+                advice: c.advice.clone(),
+                source: Some(c.source.title.clone()),
+            })
+            .collect(),
+        unresolved: report.unresolved.clone(),
+    }
+}
+
+/// A project research item holding the report; the regulations it cites become its sources
+pub fn to_research_item(report: &LabelReport) -> ProjectResearchItem {
+    let mut sources: Vec<ProjectSourceReference> = Vec::new();
+    for fact in report.entries.iter().flat_map(|e| &e.regulatory_facts) {
+        if sources.iter().any(|s| s.id == fact.source.id) {
+            continue;
+        }
+        sources.push(ProjectSourceReference {
+            id: fact.source.id.clone(),
+            title: fact.source.title.clone(),
+            url: fact.source.url.clone().unwrap_or_default(),
+            source_type: "website".to_string(),
+            cited_in: "research".to_string(),
+        });
+    }
+
+    for combination in &report.combinations {
+        if sources.iter().any(|s| s.id == combination.source.id) {
+            continue;
+        }
+        sources.push(ProjectSourceReference {
+            id: combination.source.id.clone(),
+            title: combination.source.title.clone(),
+            url: combination.source.url.clone().unwrap_or_default(),
+            source_type: "paper".to_string(),
+            cited_in: "research".to_string(),
+        });
+    }
+
+    let now = Utc::now().to_rfc3339();
+    ProjectResearchItem {
+        id: format!("research_{}", Uuid::new_v4().simple()),
+        prompt: report.entries.iter().map(|e| e.name.as_str()).collect::<Vec<_>>().join(", "),
+        title: report.product_name.clone().unwrap_or_else(|| "전성분 분석".to_string()),
+        summary: report.summary.clone(),
+        full_report: ProjectResearchReport {
+            ingredient_analysis: None,
+            papers: vec![],
+            sources,
+            label_analysis: Some(to_project_analysis(report)),
+        },
+        created_at: now.clone(),
+        updated_at: now,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{compatibility, ingredients, regulation};
+
+    fn report(text: &str) -> LabelReport {
+        analyze(
+            text,
+            None,
+            &ingredients::dictionary(),
+            &regulation::database(),
+            &compatibility::engine(),
+        )
+    }
+
+    #[test]
+    fn splits_on_delimiters_outside_parentheses_and_numbers() {
+        assert_eq!(
+            split_label("전성분: 정제수(물), 1,2-헥산다이올; 나이아신아마이드 (5%)· 향료*"),
+            vec!["정제수(물)", "1,2-헥산다이올", "나이아신아마이드", "향료"]
+        );
+    }
+
+    #[test]
+    fn joins_names_wrapped_onto_the_next_line() {
+        assert_eq!(
+            split_label("Water, Glycerin, Sodium Hyaluronate\nCrosspolymer, Butylene\r\nGlycol"),
+            vec!["Water", "Glycerin", "Sodium Hyaluronate Crosspolymer", "Butylene Glycol"]
+        );
+    }
+
+    #[test]
+    fn splits_on_line_breaks_when_there_are_no_other_delimiters() {
+        assert_eq!(
+            split_label("Ingredients:\nWater\r\nGlycerin\n\n1,2-Hexanediol\n"),
+            vec!["Water", "Glycerin", "1,2-Hexanediol"]
+        );
+    }
+
+    #[test]
+    fn resolves_exact_names_and_aliases() {
+        let report = report("글리세린(Glycerol), Nicotinamide, Butylparaben, 부틸렌글라이콜, 메칠파라벤");
+        let ids: Vec<Option<&str>> =
+            report.entries.iter().map(|e| e.ingredient.as_ref().map(|i| i.id.as_str())).collect();
+        let expected = ["glycerin", "niacinamide", "butylparaben", "butylene-glycol", "methylparaben"];
+        assert_eq!(ids, expected.map(Some));
+        assert!(report.unresolved.is_empty());
+    }
+
+    #[test]
+    fn near_misses_stay_unresolved_without_regulatory_facts() {
+        let near_misses = ["Isobutylparaben", "Pentylene Glycol", "펜틸렌글라이콜", "에틸파라벤"];
+        let report = report(&near_misses.join(", "));
+        for (entry, name) in report.entries.iter().zip(near_misses) {
+            let resolved = entry.ingredient.as_ref().map(|i| i.id.as_str());
+            assert!(
+                !matches!(resolved, Some("butylparaben" | "butylene-glycol" | "methylparaben")),
+                "{name} resolved to {resolved:?}"
+            );
+            if entry.ingredient.is_none() {
+                assert!(entry.regulatory_facts.is_empty() && entry.flags.is_empty(), "{name}");
+                assert!(report.unresolved.contains(&entry.name), "{name}");
+            }
+        }
+    }
+
+    #[test]
+    fn suggests_similar_names_for_unknown_entries() {
+        let report = report("Pentylene Glycol, 부틸렌글라이콜");
+        assert!(report.entries[0].ingredient.is_none());
+        assert_eq!(report.entries[0].suggestion.as_deref(), Some("Butylene Glycol"));
+        assert!(report.entries[1].suggestion.is_none());
+    }
+}
@@ -0,0 +1,491 @@
+use super::citation::{self, ArticleIds, CitationService};
+use super::crossref::{ConferenceResult, CrossRefService};
+use super::evidence::{self, PaperEvidenceInput};
+use super::google::{GoogleService, SearchResult};
+use super::ingredients;
+use super::matching::KeywordMatcher;
+use super::pubmed::{PaperInfo, PubMedQuery, PubMedService};
+use crate::models::{
+    AppSettings, LiteratureSearchResult, PaperResult, PaperSearchFilters, ScholarlyRecord, SourceFailure,
+};
+use chrono::{Datelike, Utc};
+use uuid::Uuid;
+
+pub const SOURCE_PUBMED: &str = "pubmed";
+pub const SOURCE_CROSSREF: &str = "crossref";
+pub const SOURCE_WEB: &str = "web";
+
+// 점수 가중치: 관련도 / 근거 수준 / 최신성
+const RELEVANCE_WEIGHT: f64 = 0.5;
+const EVIDENCE_WEIGHT: f64 = 0.3;
+const RECENCY_WEIGHT: f64 = 0.2;
+// 최신성 점수가 절반이 되는 기간 (년)
+const RECENCY_HALF_LIFE_YEARS: f64 = 5.0;
+// OpenCitations는 초당 3건까지라 인용 수와 오픈 액세스 링크는 상위 결과만 채웁니다
+const MAX_ENRICHED_RECORDS: usize = 20;
+
+/// PubMed record in the common paper shape, graded for evidence strength
+pub fn from_pubmed(p: PaperInfo) -> PaperResult {
+    let evidence = evidence::assess(
+        &PaperEvidenceInput {
+            title: &p.title,
+            abstract_text: &p.abstract_text,
+            publication_types: &p.publication_types,
+            mesh_terms: &p.mesh_terms,
+            year: &p.year,
+        },
+        Utc::now().year(),
+    );
+
+    PaperResult {
+        id: Uuid::new_v4().to_string(),
+        url: Some(format!("https://pubmed.ncbi.nlm.nih.gov/{}/", p.pmid)),
+        title: p.title,
+        authors: p.authors,
+        abstract_text: p.abstract_text,
+        publication_date: p.year,
+        source: "PubMed".to_string(),
+        citation_count: None,
+        pmid: Some(p.pmid),
+        pmcid: p.pmcid,
+        doi: p.doi,
+        open_access_url: None,
+        journal: p.journal,
+        abstract_sections: p.abstract_sections,
+        mesh_terms: p.mesh_terms,
+        keywords: p.keywords,
+        publication_types: p.publication_types,
+        country: p.country,
+        evidence: Some(evidence),
+    }
+}
+
+/// CrossRef work in the common paper shape (no abstract; graded from the title alone)
+pub fn from_crossref(c: ConferenceResult) -> PaperResult {
+    let evidence = text_evidence(&c.title, "", &c.published_date);
+    PaperResult {
+        id: c.id,
+        title: c.title,
+        authors: c.authors,
+        abstract_text: String::new(),
+        publication_date: c.published_date,
+        source: "CrossRef".to_string(),
+        citation_count: None,
+        doi: c.doi,
+        pmid: None,
+        pmcid: None,
+        url: c.url,
+        open_access_url: None,
+        journal: Some(c.source).filter(|s| s != "Unknown"),
+        abstract_sections: vec![],
+        mesh_terms: vec![],
+        keywords: vec![],
+        publication_types: vec![],
+        country: None,
+        evidence: Some(evidence),
+    }
+}
+
+/// Web page in the common paper shape; DOI/PMID links are recognized so they merge with
+/// the scholarly sources
+pub fn from_web(r: SearchResult) -> PaperResult {
+    let snippet = r.snippet.unwrap_or_default();
+    let evidence = text_evidence(&r.title, &snippet, "");
+    PaperResult {
+        id: Uuid::new_v4().to_string(),
+        doi: doi_from_url(&r.link),
+        pmid: pmid_from_url(&r.link),
+        title: r.title,
+        authors: vec![],
+        abstract_text: snippet,
+        publication_date: String::new(),
+        source: "Web".to_string(),
+        citation_count: None,
+        pmcid: None,
+        url: Some(r.link),
+        open_access_url: None,
+        journal: None,
+        abstract_sections: vec![],
+        mesh_terms: vec![],
+        keywords: vec![],
+        publication_types: vec![],
+        country: None,
+        evidence: Some(evidence),
+    }
+}
+
+fn text_evidence(title: &str, abstract_text: &str, date: &str) -> crate::models::EvidenceAssessment {
+    evidence::assess(
+        &PaperEvidenceInput {
+            title,
+            abstract_text,
+            publication_types: &[],
+            mesh_terms: &[],
+            year: date,
+        },
+        Utc::now().year(),
+    )
+}
+
+fn doi_from_url(url: &str) -> Option<String> {
+    // ASCII 소문자 변환만 바이트 위치를 그대로 유지합니다
+    let lower = url.to_ascii_lowercase();
+    let start = lower.find("doi.org/")? + "doi.org/".len();
+    let doi = urlencoding::decode(&url[start..]).ok()?.into_owned();
+    doi.starts_with("10.").then_some(doi)
+}
+
+fn pmid_from_url(url: &str) -> Option<String> {
+    let rest = url.split("pubmed.ncbi.nlm.nih.gov/").nth(1)?;
+    let id: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    (!id.is_empty()).then_some(id)
+}
+
+/// Fill citation counts, open-access links and missing ids in place.
+/// Returns the ID converter failures, i.e. why some ids may still be missing.
+pub async fn enrich_papers(service: &CitationService, papers: &mut [PaperResult]) -> Vec<String> {
+    let ids: Vec<ArticleIds> = papers
+        .iter()
+        .map(|p| ArticleIds {
+            pmid: p.pmid.clone(),
+            pmcid: p.pmcid.clone(),
+            doi: p.doi.clone(),
+        })
+        .collect();
+    let (enrichments, failures) = service.enrich(ids).await;
+
+    for (paper, found) in papers.iter_mut().zip(enrichments) {
+        paper.pmid = found.ids.pmid;
+        paper.pmcid = found.ids.pmcid;
+        paper.doi = found.ids.doi;
+        paper.citation_count = paper.citation_count.or(found.citation_count);
+        paper.open_access_url = paper.open_access_url.take().or(found.open_access_url);
+    }
+    failures
+}
+
+/// Searches PubMed, CrossRef and (when configured) Google CSE at once and returns one ranked list
+pub struct LiteratureSearch {
+    pubmed: PubMedService,
+    crossref: CrossRefService,
+    citations: CitationService,
+    /// Google API key and search engine id; `None` when web search is not set up
+    web: Option<(String, String)>,
+}
+
+impl LiteratureSearch {
+    pub fn from_settings(settings: &AppSettings, google_cx: Option<&str>) -> Self {
+        let web = match (settings.api_keys.google.as_deref(), google_cx) {
+            (Some(key), Some(cx)) if !key.trim().is_empty() && !cx.trim().is_empty() => {
+                Some((key.to_string(), cx.to_string()))
+            }
+            _ => None,
+        };
+
+        Self {
+            pubmed: PubMedService::from_settings(settings),
+            crossref: match &settings.source_endpoints.crossref {
+                Some(url) => CrossRefService::with_base_url(url),
+                None => CrossRefService::new(),
+            },
+            citations: CitationService::from_settings(settings),
+            web,
+        }
+    }
+
+    /// Every source that can run with the current settings
+    pub fn enabled_sources(&self) -> Vec<String> {
+        let mut sources = vec![SOURCE_PUBMED.to_string(), SOURCE_CROSSREF.to_string()];
+        if self.web.is_some() {
+            sources.push(SOURCE_WEB.to_string());
+        }
+        sources
+    }
+
+    /// Query `sources` concurrently (`limit` results each), merge duplicates and rank.
+    /// A source that errors, or is requested without being configured, is reported in `failures`.
+    pub async fn search(
+        &self,
+        keyword: &str,
+        limit: u32,
+        filters: &PaperSearchFilters,
+        sources: &[String],
+    ) -> LiteratureSearchResult {
+        let wants = |name: &str| sources.iter().any(|s| s == name);
+
+        let pubmed = async {
+            if !wants(SOURCE_PUBMED) {
+                return None;
+            }
+            // 한글 성분명은 사전의 영문명으로 찾습니다
+            let names = ingredients::dictionary()
+                .resolve(keyword)
+                .map(ingredients::search_names)
+                .filter(|names| !names.is_empty())
+                .unwrap_or_else(|| vec![keyword.trim().to_string()]);
+            let query = PubMedQuery::ingredient_names(&names).page(0, limit).filters(filters);
+            Some(
+                self.pubmed
+                    .search_query(&query)
+                    .await
+                    .map(|papers| papers.into_iter().map(from_pubmed).collect::<Vec<_>>()),
+            )
+        };
+        let crossref = async {
+            if !wants(SOURCE_CROSSREF) {
+                return None;
+            }
+            Some(
+                self.crossref
+                    .search(keyword, limit as usize)
+                    .await
+                    .map(|works| works.into_iter().map(from_crossref).collect::<Vec<_>>()),
+            )
+        };
+        let web = async {
+            if !wants(SOURCE_WEB) {
+                return None;
+            }
+            let Some((api_key, cx)) = &self.web else {
+                return Some(Err("Google API 키와 검색 엔진 ID(cx)가 설정되지 않았습니다.".to_string()));
+            };
+            Some(
+                GoogleService::new(api_key)
+                    .search_web(keyword, cx)
+                    .await
+                    .map(|items| items.into_iter().map(from_web).collect::<Vec<_>>()),
+            )
+        };
+
+        let (pubmed, crossref, web) = tokio::join!(pubmed, crossref, web);
+
+        let mut searched = Vec::new();
+        let mut failures = Vec::new();
+        let mut batches = Vec::new();
+        // PubMed을 먼저 두어 중복을 합칠 때 PubMed 메타데이터가 남도록 합니다
+        for (source, result) in [(SOURCE_PUBMED, pubmed), (SOURCE_CROSSREF, crossref), (SOURCE_WEB, web)] {
+            let Some(result) = result else {
+                continue;
+            };
+            searched.push(source.to_string());
+            match result {
+                Ok(papers) => batches.push((source, papers)),
+                Err(error) => failures.push(SourceFailure {
+                    source: source.to_string(),
+                    error,
+                }),
+            }
+        }
+
+        let mut records = rank(batches, keyword, Utc::now().year());
+
+        let enriched = records.len().min(MAX_ENRICHED_RECORDS);
+        let mut papers: Vec<PaperResult> = records[..enriched].iter().map(|r| r.paper.clone()).collect();
+        // ID 변환이 일부 실패하면 교차 연결이 불완전하다는 것을 알립니다
+        failures.extend(
+            enrich_papers(&self.citations, &mut papers)
+                .await
+                .into_iter()
+                .map(|error| SourceFailure {
+                    source: "idconv".to_string(),
+                    error,
+                }),
+        );
+        for (record, paper) in records.iter_mut().zip(papers) {
+            record.paper = paper;
+        }
+
+        LiteratureSearchResult {
+            records,
+            searched,
+            failures,
+        }
+    }
+}
+
+/// Merge the per-source result lists and order them by score.
+/// Relevance combines the keyword match (synonyms included) with the best position any source gave the record.
+pub fn rank(batches: Vec<(&str, Vec<PaperResult>)>, keyword: &str, current_year: i32) -> Vec<ScholarlyRecord> {
+    let matcher = KeywordMatcher::new(keyword);
+
+    // (record, sources, best position score)
+    let mut groups: Vec<(PaperResult, Vec<String>, f64)> = Vec::new();
+    for (source, papers) in batches {
+        let total = papers.len().max(1) as f64;
+        for (position, paper) in papers.into_iter().enumerate() {
+            let position_score = 1.0 - position as f64 / total;
+            match groups.iter_mut().find(|(g, _, _)| citation::is_duplicate(g, &paper)) {
+                Some((existing, found_in, best)) => {
+                    citation::merge_into(existing, paper);
+                    if !found_in.iter().any(|s| s == source) {
+                        found_in.push(source.to_string());
+                    }
+                    *best = best.max(position_score);
+                }
+                None => groups.push((paper, vec![source.to_string()], position_score)),
+            }
+        }
+    }
+
+    let mut records: Vec<ScholarlyRecord> = groups
+        .into_iter()
+        .map(|(paper, found_in, position_score)| {
+            let relevance = 0.6 * matcher.score(&paper.title, &paper.abstract_text) + 0.4 * position_score;
+            let score = RELEVANCE_WEIGHT * relevance
+                + EVIDENCE_WEIGHT * evidence_score(&paper)
+                + RECENCY_WEIGHT * recency_score(&paper, current_year);
+            ScholarlyRecord { paper, found_in, score }
+        })
+        .collect();
+
+    records.sort_by(|a, b| b.score.total_cmp(&a.score));
+    records
+}
+
+fn evidence_score(paper: &PaperResult) -> f64 {
+    match paper.evidence.as_ref().map(|e| e.grade.as_str()) {
+        Some("A") => 1.0,
+        Some("B") => 0.75,
+        Some("C") => 0.5,
+        Some(_) => 0.25,
+        None => 0.3,
+    }
+}
+
+fn recency_score(paper: &PaperResult, current_year: i32) -> f64 {
+    let year = paper
+        .publication_date
+        .get(..4)
+        .and_then(|y| y.parse::<i32>().ok());
+    match year {
+        Some(year) => {
+            let age = (current_year - year).max(0) as f64;
+            0.5_f64.powf(age / RECENCY_HALF_LIFE_YEARS)
+        }
+        // 날짜를 모르는 결과(웹 문서 등)는 중간보다 낮게 둡니다
+        None => 0.3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paper(source: &str, title: &str, doi: Option<&str>, date: &str) -> PaperResult {
+        PaperResult {
+            id: Uuid::new_v4().to_string(),
+            title: title.to_string(),
+            authors: vec![],
+            abstract_text: String::new(),
+            publication_date: date.to_string(),
+            source: source.to_string(),
+            citation_count: None,
+            doi: doi.map(str::to_string),
+            pmid: None,
+            pmcid: None,
+            url: None,
+            open_access_url: None,
+            journal: None,
+            abstract_sections: vec![],
+            mesh_terms: vec![],
+            keywords: vec![],
+            publication_types: vec![],
+            country: None,
+            evidence: None,
+        }
+    }
+
+    fn titles(records: &[ScholarlyRecord]) -> Vec<&str> {
+        records.iter().map(|r| r.paper.title.as_str()).collect()
+    }
+
+    #[test]
+    fn merges_the_same_paper_from_several_sources() {
+        let mut from_pubmed = paper("PubMed", "Retinol and photoaging: a controlled trial", Some("10.1000/a"), "2020");
+        from_pubmed.journal = Some("Skin Journal".to_string());
+        let mut from_crossref = paper("CrossRef", "Retinol & photoaging - a controlled trial", Some("10.1000/A"), "");
+        from_crossref.citation_count = Some(12);
+        let batches = vec![
+            (SOURCE_PUBMED, vec![from_pubmed, paper("PubMed", "Topical retinol in acne vulgaris", None, "2019")]),
+            (SOURCE_CROSSREF, vec![from_crossref]),
+            // DOI가 없어도 제목이 같으면 같은 논문입니다
+            (SOURCE_WEB, vec![paper("Web", "Topical retinol in acne vulgaris", None, "")]),
+        ];
+
+        let records = rank(batches, "retinol", 2025);
+        assert_eq!(records.len(), 2);
+
+        let trial = records.iter().find(|r| r.paper.doi.as_deref() == Some("10.1000/a")).unwrap();
+        assert_eq!(trial.found_in, [SOURCE_PUBMED, SOURCE_CROSSREF]);
+        assert_eq!(trial.paper.source, "PubMed + CrossRef");
+        // PubMed 메타데이터를 기준으로 빈 곳만 채웁니다
+        assert_eq!(trial.paper.title, "Retinol and photoaging: a controlled trial");
+        assert_eq!(trial.paper.journal.as_deref(), Some("Skin Journal"));
+        assert_eq!(trial.paper.citation_count, Some(12));
+
+        let acne = records.iter().find(|r| r.paper.title == "Topical retinol in acne vulgaris").unwrap();
+        assert_eq!(acne.found_in, [SOURCE_PUBMED, SOURCE_WEB]);
+        assert_eq!(acne.paper.publication_date, "2019");
+    }
+
+    #[test]
+    fn scores_relevance_evidence_and_recency() {
+        let batches = vec![(SOURCE_CROSSREF, vec![paper("CrossRef", "Retinol cream", None, "2025")])];
+        let records = rank(batches, "retinol", 2025);
+        // 관련도 0.6 × 2/3(제목만 일치) + 0.4 × 1(첫 순위), 근거 미상 0.3, 올해 논문 1.0
+        let expected = RELEVANCE_WEIGHT * (0.6 * 2.0 / 3.0 + 0.4) + EVIDENCE_WEIGHT * 0.3 + RECENCY_WEIGHT;
+        assert!((records[0].score - expected).abs() < 1e-9, "{}", records[0].score);
+
+        let batches = vec![
+            (SOURCE_PUBMED, vec![paper("PubMed", "Moisturizer use in winter", None, "2025")]),
+            (SOURCE_CROSSREF, vec![paper("CrossRef", "Retinol cream tolerability", None, "2005")]),
+            (SOURCE_WEB, vec![paper("Web", "Retinol cream tolerability study", None, "2025")]),
+        ];
+        assert_eq!(
+            titles(&rank(batches, "retinol", 2025)),
+            ["Retinol cream tolerability study", "Retinol cream tolerability", "Moisturizer use in winter"]
+        );
+
+        let mut graded = paper("PubMed", "Retinol cream trial", None, "2025");
+        graded.evidence = Some(text_evidence("Retinol cream: a meta-analysis", "", "2025"));
+        let batches = vec![(SOURCE_WEB, vec![paper("Web", "Retinol cream study", None, "2025"), graded])];
+        // 순위가 뒤여도 근거 수준이 높으면 앞섭니다
+        let ranked = rank(batches, "retinol", 2025);
+        assert_eq!(titles(&ranked), ["Retinol cream trial", "Retinol cream study"]);
+    }
+
+    #[test]
+    fn a_later_position_counts_less_unless_another_source_ranks_it_first() {
+        let (first, second) = ("Retinol serum in mature skin", "Retinol serum in sensitive skin");
+        let pubmed = || {
+            let papers = vec![paper("PubMed", first, None, "2025"), paper("PubMed", second, None, "2025")];
+            (SOURCE_PUBMED, papers)
+        };
+        assert_eq!(titles(&rank(vec![pubmed()], "retinol", 2025)), [first, second]);
+
+        let batches = vec![pubmed(), (SOURCE_CROSSREF, vec![paper("CrossRef", second, None, "2025")])];
+        let records = rank(batches, "retinol", 2025);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].score, records[1].score);
+    }
+
+    #[test]
+    fn reads_doi_from_links() {
+        assert_eq!(doi_from_url("https://doi.org/10.1000/abc").as_deref(), Some("10.1000/abc"));
+        assert_eq!(doi_from_url("https://DX.DOI.ORG/10.1000/A%2FB").as_deref(), Some("10.1000/A/B"));
+        assert_eq!(doi_from_url("https://example.com/paper"), None);
+    }
+
+    #[test]
+    fn reads_doi_after_non_ascii_text_without_panicking() {
+        // 'İ' lower-cases to two chars, which used to shift the byte offset
+        assert_eq!(doi_from_url("https://İİİ.example/doi.org/10.1000/x").as_deref(), Some("10.1000/x"));
+        assert_eq!(doi_from_url("https://ẞ.example/DOI.org/10.1000/한글").as_deref(), Some("10.1000/한글"));
+    }
+
+    #[test]
+    fn reads_pmid_from_links() {
+        assert_eq!(pmid_from_url("https://pubmed.ncbi.nlm.nih.gov/12345678/").as_deref(), Some("12345678"));
+        assert_eq!(pmid_from_url("https://pubmed.ncbi.nlm.nih.gov/"), None);
+    }
+}
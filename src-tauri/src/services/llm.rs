@@ -0,0 +1,357 @@
+use crate::error::AppError;
+use crate::models::{ApiKeys, AppSettings, ModelInfo, ModelSettings};
+use crate::services::anthropic::AnthropicService;
+use crate::services::google::GoogleService;
+use crate::services::openai::OpenAIService;
+use crate::services::stream::OnToken;
+use crate::services::structured::OutputSchema;
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Text, vision and JSON generation shared by every LLM backend
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    async fn generate_text(&self, prompt: &str, system_prompt: Option<&str>) -> Result<String, String>;
+
+    async fn analyze_image(
+        &self,
+        base64_image: &str,
+        mime_type: &str,
+        system_prompt: &str,
+        user_prompt: &str,
+    ) -> Result<String, String>;
+
+    /// Models the provider offers for this API key
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, String>;
+
+    /// Raw JSON reply constrained to `schema`. Providers override this with their native
+    /// structured-output mode; the fallback only describes the schema in the prompt.
+    async fn generate_structured(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        schema: &OutputSchema,
+    ) -> Result<String, String> {
+        let system_prompt = format!(
+            "{}\n\n다음 JSON 스키마에 맞는 JSON만 출력하세요:\n{}",
+            system_prompt.unwrap_or_default(),
+            schema.schema
+        );
+        self.generate_text(prompt, Some(system_prompt.trim_start())).await
+    }
+
+    /// `generate_text` that hands each partial token to `on_token` as it arrives.
+    /// The fallback delivers the whole reply as a single token.
+    async fn stream_text(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        on_token: &mut OnToken<'_>,
+    ) -> Result<String, String> {
+        let text = self.generate_text(prompt, system_prompt).await?;
+        on_token(&text);
+        Ok(text)
+    }
+
+    /// Streaming counterpart of `generate_structured`
+    async fn stream_structured(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        schema: &OutputSchema,
+        on_token: &mut OnToken<'_>,
+    ) -> Result<String, String> {
+        let text = self.generate_structured(prompt, system_prompt, schema).await?;
+        on_token(&text);
+        Ok(text)
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OpenAIService {
+    async fn generate_text(&self, prompt: &str, system_prompt: Option<&str>) -> Result<String, String> {
+        OpenAIService::generate_text(self, prompt, system_prompt).await
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, String> {
+        OpenAIService::list_models(self).await
+    }
+
+    async fn generate_structured(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        schema: &OutputSchema,
+    ) -> Result<String, String> {
+        OpenAIService::generate_structured(self, prompt, system_prompt, &schema.name, &schema.schema).await
+    }
+
+    async fn stream_text(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        on_token: &mut OnToken<'_>,
+    ) -> Result<String, String> {
+        OpenAIService::stream_text(self, prompt, system_prompt, on_token).await
+    }
+
+    async fn stream_structured(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        schema: &OutputSchema,
+        on_token: &mut OnToken<'_>,
+    ) -> Result<String, String> {
+        OpenAIService::stream_structured(self, prompt, system_prompt, &schema.name, &schema.schema, on_token).await
+    }
+
+    async fn analyze_image(
+        &self,
+        base64_image: &str,
+        mime_type: &str,
+        system_prompt: &str,
+        user_prompt: &str,
+    ) -> Result<String, String> {
+        self.analyze_image_for_prompt(base64_image, mime_type, system_prompt, user_prompt)
+            .await
+    }
+}
+
+#[async_trait]
+impl LlmProvider for AnthropicService {
+    async fn generate_text(&self, prompt: &str, system_prompt: Option<&str>) -> Result<String, String> {
+        AnthropicService::generate_text(self, prompt, system_prompt).await
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, String> {
+        AnthropicService::list_models(self).await
+    }
+
+    async fn generate_structured(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        schema: &OutputSchema,
+    ) -> Result<String, String> {
+        AnthropicService::generate_structured(self, prompt, system_prompt, &schema.name, &schema.schema).await
+    }
+
+    async fn stream_text(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        on_token: &mut OnToken<'_>,
+    ) -> Result<String, String> {
+        AnthropicService::stream_text(self, prompt, system_prompt, on_token).await
+    }
+
+    async fn stream_structured(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        schema: &OutputSchema,
+        on_token: &mut OnToken<'_>,
+    ) -> Result<String, String> {
+        AnthropicService::stream_structured(self, prompt, system_prompt, &schema.name, &schema.schema, on_token).await
+    }
+
+    async fn analyze_image(
+        &self,
+        base64_image: &str,
+        mime_type: &str,
+        system_prompt: &str,
+        user_prompt: &str,
+    ) -> Result<String, String> {
+        self.analyze_image_for_prompt(base64_image, mime_type, system_prompt, user_prompt)
+            .await
+    }
+}
+
+#[async_trait]
+impl LlmProvider for GoogleService {
+    async fn generate_text(&self, prompt: &str, system_prompt: Option<&str>) -> Result<String, String> {
+        GoogleService::generate_text(self, prompt, system_prompt).await
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, String> {
+        GoogleService::list_models(self).await
+    }
+
+    async fn analyze_image(
+        &self,
+        base64_image: &str,
+        mime_type: &str,
+        system_prompt: &str,
+        user_prompt: &str,
+    ) -> Result<String, String> {
+        self.analyze_image_for_prompt(base64_image, mime_type, system_prompt, user_prompt)
+            .await
+    }
+
+    async fn generate_structured(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        schema: &OutputSchema,
+    ) -> Result<String, String> {
+        GoogleService::generate_structured(self, prompt, system_prompt, &schema.schema).await
+    }
+
+    async fn stream_text(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        on_token: &mut OnToken<'_>,
+    ) -> Result<String, String> {
+        GoogleService::stream_text(self, prompt, system_prompt, on_token).await
+    }
+
+    async fn stream_structured(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        schema: &OutputSchema,
+        on_token: &mut OnToken<'_>,
+    ) -> Result<String, String> {
+        GoogleService::stream_structured(self, prompt, system_prompt, &schema.schema, on_token).await
+    }
+}
+
+/// One registered backend: its name(s), where its key lives in settings, and how to build it
+struct ProviderEntry {
+    names: &'static [&'static str],
+    api_key: fn(&ApiKeys) -> Option<&String>,
+    create: fn(&str, &ModelSettings) -> Box<dyn LlmProvider>,
+}
+
+// 새 LLM 제공자는 여기에만 추가하면 됩니다
+const PROVIDERS: &[ProviderEntry] = &[
+    ProviderEntry {
+        names: &["openai"],
+        api_key: |keys| keys.openai.as_ref(),
+        create: |key, models| Box::new(OpenAIService::with_models(key, models.openai.clone())),
+    },
+    ProviderEntry {
+        names: &["anthropic"],
+        api_key: |keys| keys.anthropic.as_ref(),
+        create: |key, models| Box::new(AnthropicService::with_models(key, models.anthropic.clone())),
+    },
+    ProviderEntry {
+        names: &["google", "gemini"],
+        api_key: |keys| keys.google.as_ref(),
+        create: |key, models| Box::new(GoogleService::with_models(key, models.google.clone())),
+    },
+];
+
+fn find_entry(name: &str) -> Result<&'static ProviderEntry, AppError> {
+    let name = name.trim().to_lowercase();
+    PROVIDERS
+        .iter()
+        .find(|entry| entry.names.contains(&name.as_str()))
+        .ok_or(AppError::InvalidProvider(name))
+}
+
+/// Build a provider by name with an explicit API key and model choices
+pub fn create_provider(name: &str, api_key: &str, models: &ModelSettings) -> Result<Box<dyn LlmProvider>, AppError> {
+    let entry = find_entry(name)?;
+    if api_key.trim().is_empty() {
+        return Err(AppError::MissingApiKey(entry.names[0].to_string()));
+    }
+    Ok((entry.create)(api_key, models))
+}
+
+/// Providers configured in the app settings (`ApiSelection`, stored API keys and model choices)
+pub struct LlmRegistry {
+    default_provider: String,
+    api_keys: ApiKeys,
+    model_settings: ModelSettings,
+}
+
+impl LlmRegistry {
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        Self {
+            default_provider: settings.api_selection.content_generation.clone(),
+            api_keys: settings.api_keys.clone(),
+            model_settings: settings.model_settings.clone(),
+        }
+    }
+
+    /// Provider by name (falls back to the selected one), using `api_key` or the stored key
+    pub fn resolve(&self, name: Option<&str>, api_key: Option<&str>) -> Result<Box<dyn LlmProvider>, AppError> {
+        let name = name
+            .filter(|n| !n.trim().is_empty())
+            .unwrap_or(&self.default_provider);
+        let entry = find_entry(name)?;
+
+        let key = api_key
+            .filter(|k| !k.trim().is_empty())
+            .or_else(|| (entry.api_key)(&self.api_keys).map(|k| k.as_str()))
+            .unwrap_or_default();
+        create_provider(name, key, &self.model_settings)
+    }
+}
+
+/// Pull the first JSON object/array out of a model reply (code fences and chatter around it are ignored)
+pub fn extract_json_value(text: &str) -> Result<Value, String> {
+    let trimmed = text.trim();
+    if let Ok(value) = serde_json::from_str(trimmed) {
+        return Ok(value);
+    }
+
+    let start = trimmed
+        .find(['{', '['])
+        .ok_or_else(|| "응답에서 JSON을 찾을 수 없습니다.".to_string())?;
+    let close = if trimmed[start..].starts_with('{') { '}' } else { ']' };
+    let end = trimmed
+        .rfind(close)
+        .filter(|end| *end > start)
+        .ok_or_else(|| "응답에서 JSON을 찾을 수 없습니다.".to_string())?;
+
+    serde_json::from_str(&trimmed[start..=end]).map_err(|e| format!("JSON 파싱 실패: {}", e))
+}
+
+/// Provider that replays recorded replies in order and remembers the prompts it was sent
+#[cfg(test)]
+pub struct ScriptedLlm {
+    replies: std::sync::Mutex<std::collections::VecDeque<Result<String, String>>>,
+    pub prompts: std::sync::Mutex<Vec<String>>,
+}
+
+#[cfg(test)]
+impl ScriptedLlm {
+    pub fn new(replies: impl IntoIterator<Item = Result<&'static str, &'static str>>) -> Self {
+        Self {
+            replies: std::sync::Mutex::new(
+                replies.into_iter().map(|r| r.map(str::to_string).map_err(str::to_string)).collect(),
+            ),
+            prompts: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+
+    fn reply(&self, prompt: &str) -> Result<String, String> {
+        self.prompts.lock().unwrap().push(prompt.to_string());
+        self.replies.lock().unwrap().pop_front().unwrap_or_else(|| Err("응답이 더 없습니다".to_string()))
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl LlmProvider for ScriptedLlm {
+    async fn generate_text(&self, prompt: &str, _system_prompt: Option<&str>) -> Result<String, String> {
+        self.reply(prompt)
+    }
+
+    async fn analyze_image(
+        &self,
+        _base64_image: &str,
+        _mime_type: &str,
+        _system_prompt: &str,
+        user_prompt: &str,
+    ) -> Result<String, String> {
+        self.reply(user_prompt)
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, String> {
+        Ok(vec![])
+    }
+}
@@ -0,0 +1,408 @@
+// Keyword matching for Korean and English text: Hangul normalization, particle (조사) stripping
+// and ingredient synonym expansion, so "나이아신아마이드" also finds "나이아신아마이드가" and "niacinamide".
+
+use super::ingredients;
+use std::collections::HashSet;
+
+/// Particles and endings stripped from Korean words, longest first
+const PARTICLES: &[&str] = &[
+    "으로부터", "에서부터", "에게서", "이라는", "으로", "에서", "에게", "한테", "까지", "부터", "처럼", "보다",
+    "이랑", "라는", "이나", "과의", "와의", "에는", "에도", "은", "는", "이", "가", "을", "를", "의", "에",
+    "와", "과", "도", "만", "로", "랑", "나", "들",
+];
+
+// 조사를 떼고도 이만큼은 남아야 합니다 ("오이"의 "이"를 떼지 않도록)
+const MIN_STEM_CHARS: usize = 2;
+
+/// Words whose last syllables only look like a particle ("달팽이" is not "달팽" + "이").
+/// A particle is never cut out of them, also at the end of a compound ("유기농바나나").
+const PARTICLE_LIKE_ENDINGS: &[&str] = &[
+    "달팽이", "고양이", "어린이", "아사이", "알로에", "아보카도", "바나나", "피부과", "성형외과",
+];
+
+// 이 정도 겹치는 단어까지는 여러 단어짜리 동의어로 봅니다 ("centella asiatica extract")
+const MAX_SYNONYM_WORDS: usize = 4;
+
+// 동의어로 찾은 경우 원래 검색어로 찾은 것보다 조금 낮게 칩니다
+const SYNONYM_WEIGHT: f64 = 0.9;
+
+// 한글 검색어가 띄어쓰기 없이 다른 단어에 붙어 있는 경우 ("나이아신아마이드크림")
+const COMPACT_MATCH_WEIGHT: f64 = 0.9;
+
+const TITLE_WEIGHT: f64 = 2.0;
+const BODY_WEIGHT: f64 = 1.0;
+
+// ============================================
+// Normalization
+// ============================================
+
+const S_BASE: u32 = 0xAC00;
+const L_BASE: u32 = 0x1100;
+const V_BASE: u32 = 0x1161;
+const T_BASE: u32 = 0x11A7;
+const L_COUNT: u32 = 19;
+const V_COUNT: u32 = 21;
+const T_COUNT: u32 = 28;
+const S_COUNT: u32 = L_COUNT * V_COUNT * T_COUNT;
+
+/// Compose decomposed Hangul (NFD, as macOS file names and some feeds send it) into syllables (NFC).
+/// Other characters are left as they are.
+pub fn compose_hangul(text: &str) -> String {
+    let mut result: Vec<char> = Vec::with_capacity(text.len());
+
+    for c in text.chars() {
+        let code = c as u32;
+        if let Some(last) = result.last_mut() {
+            let last_code = *last as u32;
+
+            // 초성 + 중성
+            if (L_BASE..L_BASE + L_COUNT).contains(&last_code) && (V_BASE..V_BASE + V_COUNT).contains(&code) {
+                if let Some(composed) =
+                    char::from_u32(S_BASE + ((last_code - L_BASE) * V_COUNT + (code - V_BASE)) * T_COUNT)
+                {
+                    *last = composed;
+                    continue;
+                }
+            }
+
+            // 받침 없는 음절 + 종성
+            let s_index = last_code.wrapping_sub(S_BASE);
+            if s_index < S_COUNT && s_index.is_multiple_of(T_COUNT) && (T_BASE + 1..T_BASE + T_COUNT).contains(&code) {
+                if let Some(composed) = char::from_u32(last_code + (code - T_BASE)) {
+                    *last = composed;
+                    continue;
+                }
+            }
+        }
+        result.push(c);
+    }
+
+    result.into_iter().collect()
+}
+
+// 입력 중인 자음(호환 자모)을 초성으로 바꿀 때 쓰는 순서
+const COMPAT_INITIALS: &str = "ㄱㄲㄴㄷㄸㄹㅁㅂㅃㅅㅆㅇㅈㅉㅊㅋㅌㅍㅎ";
+
+/// Split Hangul syllables into their letters (NFD), so text can be compared letter by letter.
+/// A lone consonant as typed on a keyboard ("ㅅ") becomes the matching initial.
+pub fn decompose_hangul(text: &str) -> String {
+    let mut result = String::with_capacity(text.len() * 3);
+    for c in text.chars() {
+        let s_index = (c as u32).wrapping_sub(S_BASE);
+        if s_index < S_COUNT {
+            let jamo = [
+                Some(L_BASE + s_index / (V_COUNT * T_COUNT)),
+                Some(V_BASE + (s_index % (V_COUNT * T_COUNT)) / T_COUNT),
+                Some(s_index % T_COUNT).filter(|t| *t > 0).map(|t| T_BASE + t),
+            ];
+            result.extend(jamo.into_iter().flatten().filter_map(char::from_u32));
+        } else if let Some(i) = COMPAT_INITIALS.chars().position(|initial| initial == c) {
+            result.extend(char::from_u32(L_BASE + i as u32));
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// NFC, lowercase, punctuation to spaces, single spaces
+pub fn normalize(text: &str) -> String {
+    compose_hangul(text)
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn is_hangul(c: char) -> bool {
+    ('가'..='힣').contains(&c)
+}
+
+/// Drop a trailing particle from a Korean word ("나이아신아마이드가" → "나이아신아마이드").
+/// Words that are not all Hangul are returned unchanged.
+pub fn strip_particle(word: &str) -> &str {
+    if word.is_empty() || !word.chars().all(is_hangul) {
+        return word;
+    }
+    let length = word.chars().count();
+
+    PARTICLES
+        .iter()
+        .find(|p| {
+            word.ends_with(*p)
+                && length - p.chars().count() >= MIN_STEM_CHARS
+                && !splits_particle_like_word(word, word.len() - p.len())
+        })
+        .map(|p| &word[..word.len() - p.len()])
+        .unwrap_or(word)
+}
+
+/// Whether cutting `word` at byte `at` would split one of `PARTICLE_LIKE_ENDINGS` ("알로|에는")
+fn splits_particle_like_word(word: &str, at: usize) -> bool {
+    let (stem, rest) = word.split_at(at);
+    PARTICLE_LIKE_ENDINGS
+        .iter()
+        .any(|w| w.char_indices().skip(1).any(|(i, _)| stem.ends_with(&w[..i]) && rest.starts_with(&w[i..])))
+}
+
+/// Normalized words with particles stripped
+pub fn tokenize(text: &str) -> Vec<String> {
+    normalize(text)
+        .split(' ')
+        .filter(|w| !w.is_empty())
+        .map(|w| strip_particle(w).to_string())
+        .collect()
+}
+
+// ============================================
+// Synonyms
+// ============================================
+
+/// Other names of an ingredient in the dictionary, normalized; empty when it is not one
+pub fn synonyms(name: &str) -> Vec<String> {
+    let key = tokenize(name).concat();
+    match ingredients::dictionary().get_by_name(name) {
+        Some(ingredient) => ingredients::names(ingredient)
+            .iter()
+            .map(|s| normalize(s))
+            .filter(|s| tokenize(s).concat() != key)
+            .collect(),
+        None => vec![],
+    }
+}
+
+// ============================================
+// Matching
+// ============================================
+
+/// One way of writing a concept: its words and how much a hit counts
+struct Variant {
+    words: Vec<String>,
+    compact: String,
+    has_hangul: bool,
+    weight: f64,
+}
+
+impl Variant {
+    fn new(text: &str, weight: f64) -> Self {
+        let words = tokenize(text);
+        let compact = words.concat();
+        let has_hangul = compact.chars().any(is_hangul);
+        Self {
+            words,
+            compact,
+            has_hangul,
+            weight,
+        }
+    }
+
+    /// How well this variant occurs in a tokenized field (0 when it does not)
+    fn find(&self, field: &Field) -> f64 {
+        if self.words.is_empty() {
+            return 0.0;
+        }
+        if field.words.windows(self.words.len()).any(|w| w == self.words.as_slice()) {
+            self.weight
+        } else if self.has_hangul && field.compact.contains(&self.compact) {
+            self.weight * COMPACT_MATCH_WEIGHT
+        } else {
+            0.0
+        }
+    }
+}
+
+struct Field {
+    words: Vec<String>,
+    compact: String,
+}
+
+impl Field {
+    fn new(text: &str) -> Self {
+        let words = tokenize(text);
+        let compact = words.concat();
+        Self { words, compact }
+    }
+}
+
+/// A search keyword split into concepts, each with its synonyms.
+/// A text matches when every concept occurs in it.
+pub struct KeywordMatcher {
+    concepts: Vec<Vec<Variant>>,
+}
+
+impl KeywordMatcher {
+    pub fn new(keyword: &str) -> Self {
+        let words = tokenize(keyword);
+        let dictionary = ingredients::dictionary();
+        let mut concepts = Vec::new();
+
+        // 여러 단어짜리 성분명("hyaluronic acid")은 가장 긴 것부터 한 덩어리로 묶습니다
+        let mut i = 0;
+        while i < words.len() {
+            let longest = (i + 1..=(i + MAX_SYNONYM_WORDS).min(words.len()))
+                .rev()
+                .find(|&end| dictionary.get_by_name(&words[i..end].join(" ")).is_some())
+                .unwrap_or(i + 1);
+
+            let phrase = words[i..longest].join(" ");
+            let mut variants = vec![Variant::new(&phrase, 1.0)];
+            variants.extend(synonyms(&phrase).iter().map(|s| Variant::new(s, SYNONYM_WEIGHT)));
+            concepts.push(variants);
+
+            i = longest;
+        }
+
+        Self { concepts }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.concepts.is_empty()
+    }
+
+    /// Whether every concept of the keyword occurs in the title or the body
+    pub fn is_match(&self, title: &str, body: &str) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        let (title, body) = (Field::new(title), Field::new(body));
+        self.concepts
+            .iter()
+            .all(|variants| concept_score(variants, &title) > 0.0 || concept_score(variants, &body) > 0.0)
+    }
+
+    /// Relevance in 0..=1: the average concept hit, a title hit counting twice a body hit
+    pub fn score(&self, title: &str, body: &str) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        let (title, body) = (Field::new(title), Field::new(body));
+        let total: f64 = self
+            .concepts
+            .iter()
+            .map(|variants| {
+                (TITLE_WEIGHT * concept_score(variants, &title) + BODY_WEIGHT * concept_score(variants, &body))
+                    / (TITLE_WEIGHT + BODY_WEIGHT)
+            })
+            .sum();
+        total / self.concepts.len() as f64
+    }
+}
+
+fn concept_score(variants: &[Variant], field: &Field) -> f64 {
+    variants.iter().map(|v| v.find(field)).fold(0.0, f64::max)
+}
+
+/// Share of character pairs two texts have in common (Dice coefficient), ignoring case, spacing
+/// and punctuation. 1.0 for identical texts.
+pub fn text_similarity(a: &str, b: &str) -> f64 {
+    let compact = |text: &str| normalize(text).replace(' ', "");
+    bigram_similarity(&compact(a), &compact(b))
+}
+
+/// Dice coefficient of the character pairs of two strings, compared as they are
+pub fn bigram_similarity(a: &str, b: &str) -> f64 {
+    let bigrams = |text: &str| -> HashSet<(char, char)> {
+        let chars: Vec<char> = text.chars().collect();
+        chars.windows(2).map(|w| (w[0], w[1])).collect()
+    };
+    let (a, b) = (bigrams(a), bigrams(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    2.0 * a.intersection(&b).count() as f64 / (a.len() + b.len()) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_one_trailing_particle() {
+        assert_eq!(strip_particle("나이아신아마이드가"), "나이아신아마이드");
+        assert_eq!(strip_particle("레티놀으로부터"), "레티놀");
+        assert_eq!(strip_particle("피부에서"), "피부");
+        assert_eq!(strip_particle("성분들"), "성분");
+        // 조사를 떼면 한 글자만 남는 단어, 한글이 아닌 단어는 그대로 둡니다
+        assert_eq!(strip_particle("오이"), "오이");
+        assert_eq!(strip_particle("retinol이"), "retinol이");
+        assert_eq!(strip_particle(""), "");
+    }
+
+    #[test]
+    fn keeps_words_that_end_like_a_particle() {
+        assert_eq!(strip_particle("달팽이"), "달팽이");
+        assert_eq!(strip_particle("알로에"), "알로에");
+        assert_eq!(strip_particle("유기농바나나"), "유기농바나나");
+        assert_eq!(strip_particle("달팽이가"), "달팽이");
+        assert_eq!(strip_particle("알로에는"), "알로에");
+        assert_eq!(strip_particle("알로에에서"), "알로에");
+        assert_eq!(tokenize("달팽이 크림과 알로에"), ["달팽이", "크림", "알로에"]);
+    }
+
+    #[test]
+    fn composes_and_decomposes_hangul() {
+        let decomposed = "\u{1102}\u{1161}\u{110B}\u{1175}\u{110B}\u{1161}\u{1109}\u{1175}\u{11AB}";
+        assert_eq!(compose_hangul(decomposed), "나이아신");
+        assert_eq!(decompose_hangul("나이아신"), decomposed);
+        assert_eq!(compose_hangul(&decompose_hangul("히알루론산 cream")), "히알루론산 cream");
+        // 입력 중인 자음은 초성으로 바꿉니다
+        assert_eq!(decompose_hangul("나ㅅ"), "\u{1102}\u{1161}\u{1109}");
+        // 짝이 없는 모음이나 종성은 그대로 둡니다
+        assert_eq!(compose_hangul("\u{1161}\u{11AB}a"), "\u{1161}\u{11AB}a");
+    }
+
+    #[test]
+    fn normalizes_case_punctuation_and_spacing() {
+        assert_eq!(normalize("  Vitamin-C,  \u{1102}\u{1161}이아신 (5%)! "), "vitamin c 나이아신 5");
+        assert_eq!(tokenize("레티놀은 비타민A의 한 형태"), ["레티놀", "비타민a의", "한", "형태"]);
+    }
+
+    #[test]
+    fn finds_other_names_of_an_ingredient() {
+        let names = synonyms("나이아신아마이드");
+        for name in ["niacinamide", "nicotinamide", "vitamin b3", "니코틴아마이드"] {
+            assert!(names.iter().any(|s| s == name), "{name}: {names:?}");
+        }
+        assert!(!names.iter().any(|s| s == "나이아신아마이드"));
+        assert!(synonyms("나이아신아마이드가").iter().any(|s| s == "niacinamide"));
+        assert!(synonyms("수분크림").is_empty());
+    }
+
+    #[test]
+    fn matches_every_concept_under_any_of_its_names() {
+        let matcher = KeywordMatcher::new("나이아신아마이드 미백");
+        assert!(matcher.is_match("나이아신아마이드가 들어간 미백 크림", ""));
+        assert!(matcher.is_match("Niacinamide 5% serum", "미백 기능성 인증"));
+        assert!(!matcher.is_match("Niacinamide 5% serum", "보습 크림"));
+
+        // 여러 단어짜리 성분명은 한 덩어리로, 한글은 붙여 써도 찾습니다
+        let matcher = KeywordMatcher::new("hyaluronic acid");
+        assert!(matcher.is_match("히알루론산크림 출시", ""));
+        assert!(!matcher.is_match("acid and hyaluronic", ""));
+
+        assert!(KeywordMatcher::new("  ").is_empty());
+        assert!(!KeywordMatcher::new("  ").is_match("anything", "anything"));
+    }
+
+    #[test]
+    fn scores_title_hits_above_body_and_synonyms_below_the_keyword() {
+        let matcher = KeywordMatcher::new("레티놀");
+        assert_eq!(matcher.score("레티놀 크림", "레티놀 함유"), 1.0);
+        assert!((matcher.score("레티놀 크림", "") - 2.0 / 3.0).abs() < 1e-9);
+        assert!((matcher.score("", "레티놀 크림") - 1.0 / 3.0).abs() < 1e-9);
+        assert!((matcher.score("Retinol serum", "") - 2.0 / 3.0 * SYNONYM_WEIGHT).abs() < 1e-9);
+        assert!((matcher.score("레티놀크림", "") - 2.0 / 3.0 * COMPACT_MATCH_WEIGHT).abs() < 1e-9);
+        assert_eq!(matcher.score("수분 크림", "보습"), 0.0);
+    }
+
+    #[test]
+    fn compares_texts_by_character_pairs() {
+        assert_eq!(text_similarity("Retinol Cream!", "retinol cream"), 1.0);
+        assert_eq!(text_similarity("abc", "xyz"), 0.0);
+        assert_eq!(bigram_similarity("a", "a"), 0.0);
+        assert!((bigram_similarity("night", "nacht") - 0.25).abs() < 1e-9);
+    }
+}
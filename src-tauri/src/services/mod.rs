@@ -1,8 +1,23 @@
 // External API services
 
 pub mod anthropic;
+pub mod citation;
+pub mod compatibility;
 pub mod crossref;
+pub mod dataset;
+pub mod evidence;
 pub mod google;
+pub mod ingredients;
+pub mod label;
+pub mod literature;
+pub mod llm;
+pub mod matching;
 pub mod news;
 pub mod openai;
 pub mod pubmed;
+pub mod regulation;
+pub mod scheduler;
+pub mod stream;
+pub mod structured;
+pub mod suggest;
+pub mod trend;
@@ -1,9 +1,29 @@
-use reqwest::Client;
+use super::matching::{self, KeywordMatcher};
+use super::scheduler::{self, Provider, SendScheduled};
+use crate::models::{AppSettings, NewsFeed, NewsFeedRegistry, NewsSearchFilters, NewsSort};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, TimeZone, Utc};
+use futures_util::future::join_all;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
-use quick_xml::de::from_str;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+// 피드 하나에서 가져올 최대 기사 수
+const MAX_ITEMS_PER_FEED: usize = 30;
+
+// 제목이 이 정도 이상 겹치면 같은 기사로 봅니다
+const NEAR_DUPLICATE_SIMILARITY: f64 = 0.8;
+const MIN_STORY_TITLE_CHARS: usize = 8;
+
+// 시간대가 없는 날짜는 한국 시간으로 읽습니다
+const KST_OFFSET_SECONDS: i32 = 9 * 3600;
 
 pub struct NewsService {
     client: Client,
+    feeds: Vec<NewsFeed>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -11,155 +31,592 @@ pub struct NewsResult {
     pub title: String,
     pub description: String,
     pub link: String,
+    /// RFC 2822 when the feed's date could be read, else as published (or "Unknown")
     pub pub_date: String,
+    #[serde(default)]
+    pub published_at: Option<DateTime<FixedOffset>>,
     pub source: String,
+    #[serde(default)]
+    pub category: String,
+    /// How well the article matches the search keyword, 0..=1
+    #[serde(default)]
+    pub relevance: f64,
+    /// Other feeds that carried the same story
+    #[serde(default)]
+    pub other_sources: Vec<String>,
 }
 
-// RSS Feed structures
-#[derive(Debug, Deserialize)]
-struct RssFeed {
-    channel: RssChannel,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedFormat {
+    Rss,
+    Atom,
 }
 
-#[derive(Debug, Deserialize)]
-struct RssChannel {
-    #[serde(default)]
-    item: Vec<RssItem>,
+/// A downloaded feed, before keyword filtering
+#[derive(Debug, Clone)]
+pub struct ParsedFeed {
+    pub format: FeedFormat,
+    pub title: Option<String>,
+    pub entries: Vec<FeedEntry>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FeedEntry {
+    pub title: String,
+    pub summary: String,
+    pub link: String,
+    /// As published: RFC 2822 for RSS, RFC 3339 for Atom
+    pub published: Option<String>,
+    pub categories: Vec<String>,
 }
 
-#[derive(Debug, Deserialize)]
-struct RssItem {
-    title: Option<String>,
-    description: Option<String>,
-    link: Option<String>,
-    #[serde(rename = "pubDate")]
-    pub_date: Option<String>,
+/// Last response of a feed, reused when the server answers 304 Not Modified
+struct CachedFeed {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    feed: ParsedFeed,
+}
+
+fn feed_cache() -> &'static Mutex<HashMap<String, CachedFeed>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, CachedFeed>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
 impl NewsService {
+    /// The built-in feed list
     pub fn new() -> Self {
+        Self::with_feeds(NewsFeedRegistry::default().feeds)
+    }
+
+    /// Search only the enabled feeds of `feeds`
+    pub fn with_feeds(feeds: Vec<NewsFeed>) -> Self {
         Self {
-            client: Client::new(),
+            client: scheduler::http_client(),
+            feeds: feeds.into_iter().filter(|f| f.enabled).collect(),
         }
     }
 
-    /// Search Yonhap News RSS feed
-    pub async fn search_yonhap(&self, keyword: &str) -> Result<Vec<NewsResult>, String> {
-        // 연합뉴스 주요 RSS 피드들
-        let feeds = vec![
-            "https://www.yna.co.kr/rss/news.xml",           // 전체 뉴스
-            "https://www.yna.co.kr/rss/economy.xml",        // 경제
-            "https://www.yna.co.kr/rss/science.xml",        // 과학
-        ];
+    /// The user's feed registry
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        Self::with_feeds(settings.news_feeds.feeds.clone())
+    }
 
-        let mut all_results = Vec::new();
+    /// Search every enabled feed
+    pub async fn search_all(&self, keyword: &str) -> Result<Vec<NewsResult>, String> {
+        self.search(keyword, &[], &NewsSearchFilters::default()).await
+    }
+
+    /// Search the enabled feeds of the given categories (all when empty). A story carried by
+    /// several feeds is returned once. With a date window, articles without a readable date are
+    /// left out. Feeds that fail are skipped; it is an error only when none of them could be read.
+    pub async fn search(
+        &self,
+        keyword: &str,
+        categories: &[String],
+        filters: &NewsSearchFilters,
+    ) -> Result<Vec<NewsResult>, String> {
+        let feeds: Vec<&NewsFeed> = self
+            .feeds
+            .iter()
+            .filter(|f| categories.is_empty() || categories.iter().any(|c| c.eq_ignore_ascii_case(&f.category)))
+            .collect();
+        if feeds.is_empty() {
+            return Ok(vec![]);
+        }
 
-        for feed_url in feeds {
-            match self.fetch_and_parse_rss(feed_url, keyword, "연합뉴스").await {
+        let matcher = KeywordMatcher::new(keyword);
+        let fetched = join_all(feeds.iter().map(|feed| self.search_feed(feed, &matcher))).await;
+
+        let mut all_results = Vec::new();
+        let mut errors = Vec::new();
+        for (feed, result) in feeds.iter().zip(fetched) {
+            match result {
                 Ok(results) => all_results.extend(results),
-                Err(e) => eprintln!("연합뉴스 RSS 파싱 실패 ({}): {}", feed_url, e),
+                Err(e) => {
+                    eprintln!("뉴스 피드 읽기 실패 ({}, {}): {}", feed.name, feed.url, e);
+                    errors.push(format!("{}: {}", feed.name, e));
+                }
             }
         }
+        if errors.len() == feeds.len() {
+            return Err(format!("뉴스 피드를 읽지 못했습니다 ({})", errors.join(", ")));
+        }
 
-        // Remove duplicates based on title
-        all_results.sort_by(|a, b| a.title.cmp(&b.title));
-        all_results.dedup_by(|a, b| a.title == b.title);
+        all_results.retain(|r| in_date_window(r, filters, Utc::now()));
+        let mut results = collapse_duplicates(all_results);
+        sort_results(&mut results, filters.sort);
+        Ok(results)
+    }
+
+    /// Articles of one feed that match the keyword, scored
+    pub async fn search_feed(&self, feed: &NewsFeed, matcher: &KeywordMatcher) -> Result<Vec<NewsResult>, String> {
+        let parsed = self.fetch_feed(&feed.url).await?;
 
-        Ok(all_results)
+        Ok(parsed
+            .entries
+            .into_iter()
+            .filter_map(|entry| {
+                let summary = clean_html(&entry.summary);
+                if !matcher.is_match(&entry.title, &summary) {
+                    return None;
+                }
+                let relevance = matcher.score(&entry.title, &summary);
+                Some(NewsResult {
+                    relevance,
+                    ..to_news_result(entry, feed)
+                })
+            })
+            .take(MAX_ITEMS_PER_FEED)
+            .collect())
     }
 
-    /// Search CNN RSS feed
-    pub async fn search_cnn(&self, keyword: &str) -> Result<Vec<NewsResult>, String> {
-        let feeds = vec![
-            "http://rss.cnn.com/rss/edition.rss",           // Top Stories
-            "http://rss.cnn.com/rss/edition_world.rss",     // World
-            "http://rss.cnn.com/rss/edition_technology.rss", // Technology
-        ];
+    /// Download and parse a feed, sending the validators of the previous response so an
+    /// unchanged feed costs a 304 instead of a full download
+    pub async fn fetch_feed(&self, url: &str) -> Result<ParsedFeed, String> {
+        let mut request = self.client.get(url).header("User-Agent", "MomsInsta/1.0");
+        {
+            let cache = feed_cache().lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(cached) = cache.get(url) {
+                if let Some(etag) = &cached.etag {
+                    request = request.header(IF_NONE_MATCH, etag);
+                }
+                if let Some(last_modified) = &cached.last_modified {
+                    request = request.header(IF_MODIFIED_SINCE, last_modified);
+                }
+            }
+        }
 
-        let mut all_results = Vec::new();
+        let response = request
+            .send_scheduled(Provider::News)
+            .await
+            .map_err(|e| format!("RSS 요청 실패: {}", e))?;
 
-        for feed_url in feeds {
-            match self.fetch_and_parse_rss(feed_url, keyword, "CNN").await {
-                Ok(results) => all_results.extend(results),
-                Err(e) => eprintln!("CNN RSS 파싱 실패 ({}): {}", feed_url, e),
+        if response.status() == StatusCode::NOT_MODIFIED {
+            let cache = feed_cache().lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(cached) = cache.get(url) {
+                return Ok(cached.feed.clone());
             }
+            return Err("RSS 요청 실패: 캐시 없이 304 응답을 받았습니다".to_string());
+        }
+        if !response.status().is_success() {
+            return Err(format!("RSS 요청 실패: HTTP {}", response.status()));
         }
 
-        // Remove duplicates
-        all_results.sort_by(|a, b| a.title.cmp(&b.title));
-        all_results.dedup_by(|a, b| a.title == b.title);
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
 
-        Ok(all_results)
+        let xml_content = response
+            .text()
+            .await
+            .map_err(|e| format!("RSS 응답 읽기 실패: {}", e))?;
+        let feed = parse_feed(&xml_content)?;
+
+        if etag.is_some() || last_modified.is_some() {
+            feed_cache().lock().unwrap_or_else(|e| e.into_inner()).insert(
+                url.to_string(),
+                CachedFeed {
+                    etag,
+                    last_modified,
+                    feed: feed.clone(),
+                },
+            );
+        }
+
+        Ok(feed)
     }
+}
 
-    /// Search all news sources
-    pub async fn search_all(&self, keyword: &str) -> Result<Vec<NewsResult>, String> {
-        let (yonhap_results, cnn_results) = tokio::join!(
-            self.search_yonhap(keyword),
-            self.search_cnn(keyword)
-        );
+impl Default for NewsService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-        let mut all_results = Vec::new();
+fn to_news_result(entry: FeedEntry, feed: &NewsFeed) -> NewsResult {
+    let published_at = entry.published.as_deref().and_then(parse_pub_date);
+    let pub_date = match (published_at, entry.published) {
+        (Some(date), _) => date.to_rfc2822(),
+        (None, Some(raw)) => raw,
+        (None, None) => "Unknown".to_string(),
+    };
+
+    NewsResult {
+        title: if entry.title.is_empty() { "제목 없음".to_string() } else { clean_html(&entry.title) },
+        description: clean_html(&entry.summary),
+        link: entry.link,
+        pub_date,
+        published_at,
+        source: feed.name.clone(),
+        category: feed.category.clone(),
+        relevance: 0.0,
+        other_sources: vec![],
+    }
+}
 
-        if let Ok(results) = yonhap_results {
-            all_results.extend(results);
+fn in_date_window(result: &NewsResult, filters: &NewsSearchFilters, now: DateTime<Utc>) -> bool {
+    if filters.date_from.is_none() && filters.date_to.is_none() && filters.days.is_none() {
+        return true;
+    }
+    let Some(published) = result.published_at else {
+        return false;
+    };
+    let day = published.date_naive();
+
+    // 날짜 범위를 벗어날 만큼 큰 기간은 제한 없음으로 봅니다
+    let cutoff = filters
+        .days
+        .and_then(|days| Duration::try_days(days as i64))
+        .and_then(|window| now.checked_sub_signed(window));
+
+    filters.date_from.is_none_or(|from| day >= from)
+        && filters.date_to.is_none_or(|to| day <= to)
+        && cutoff.is_none_or(|cutoff| published.with_timezone(&Utc) >= cutoff)
+}
+
+/// Merge articles that are the same story: the same link, or titles that differ only in tags
+/// like "[속보]" and the outlet suffix. The more relevant (then earlier) article is kept and
+/// the other feeds are listed in `other_sources`.
+fn collapse_duplicates(results: Vec<NewsResult>) -> Vec<NewsResult> {
+    let mut kept: Vec<(NewsResult, String)> = Vec::new();
+
+    for result in results {
+        let story = story_title(&result.title);
+        // 너무 짧은 제목("제목 없음" 등)은 링크가 같을 때만 합칩니다
+        let comparable = story.chars().count() >= MIN_STORY_TITLE_CHARS;
+        let existing = kept.iter_mut().find(|(k, k_story)| {
+            (!result.link.is_empty() && k.link == result.link)
+                || (comparable && matching::text_similarity(k_story, &story) >= NEAR_DUPLICATE_SIMILARITY)
+        });
+
+        match existing {
+            Some((k, k_story)) => {
+                let prefer_new = result.relevance > k.relevance
+                    || (result.relevance == k.relevance
+                        && result.published_at.is_some()
+                        && (k.published_at.is_none() || result.published_at < k.published_at));
+                if prefer_new {
+                    let replaced = std::mem::replace(k, result);
+                    *k_story = story;
+                    absorb_sources(k, replaced);
+                } else {
+                    absorb_sources(k, result);
+                }
+            }
+            None => kept.push((result, story)),
         }
+    }
 
-        if let Ok(results) = cnn_results {
-            all_results.extend(results);
+    kept.into_iter().map(|(result, _)| result).collect()
+}
+
+/// Most relevant or newest first, the other order breaking ties; undated articles go last when sorting by date
+fn sort_results(results: &mut [NewsResult], sort: NewsSort) {
+    let newest_first = |a: &NewsResult, b: &NewsResult| b.published_at.cmp(&a.published_at);
+    match sort {
+        NewsSort::Relevance => {
+            results.sort_by(|a, b| b.relevance.total_cmp(&a.relevance).then_with(|| newest_first(a, b)))
+        }
+        NewsSort::Newest => {
+            results.sort_by(|a, b| newest_first(a, b).then_with(|| b.relevance.total_cmp(&a.relevance)))
         }
+    }
+}
 
-        Ok(all_results)
+fn absorb_sources(kept: &mut NewsResult, duplicate: NewsResult) {
+    for source in std::iter::once(duplicate.source).chain(duplicate.other_sources) {
+        if source != kept.source && !kept.other_sources.contains(&source) {
+            kept.other_sources.push(source);
+        }
     }
+}
 
-    /// Fetch and parse RSS feed, filtering by keyword
-    async fn fetch_and_parse_rss(
-        &self,
-        url: &str,
-        keyword: &str,
-        source: &str,
-    ) -> Result<Vec<NewsResult>, String> {
-        let response = self
-            .client
-            .get(url)
-            .header("User-Agent", "MomsInsta/1.0")
-            .send()
-            .await
-            .map_err(|e| format!("RSS 요청 실패: {}", e))?;
+/// A headline without leading tags ("[속보]", "(종합)") and a trailing " - 연합뉴스" / " | CNN"
+fn story_title(title: &str) -> String {
+    let mut title = title.trim();
 
-        if !response.status().is_success() {
-            return Err(format!("RSS 요청 실패: HTTP {}", response.status()));
+    while let Some(closing) = match title.chars().next() {
+        Some('[') => Some(']'),
+        Some('(') => Some(')'),
+        Some('【') => Some('】'),
+        _ => None,
+    } {
+        let Some(close) = title.find(closing) else {
+            break;
+        };
+        title = title[close + closing.len_utf8()..].trim_start();
+    }
+
+    for separator in [" - ", " | ", " :: "] {
+        if let Some(pos) = title.rfind(separator) {
+            if title[..pos].chars().count() >= 10 {
+                title = &title[..pos];
+            }
         }
+    }
 
-        let xml_content = response
-            .text()
-            .await
-            .map_err(|e| format!("RSS 응답 읽기 실패: {}", e))?;
+    title.trim().to_string()
+}
 
-        let feed: RssFeed = from_str(&xml_content)
-            .map_err(|e| format!("RSS 파싱 실패: {}", e))?;
+// ============================================
+// Dates
+// ============================================
 
-        let keyword_lower = keyword.to_lowercase();
+/// Read a feed date: RFC 822/2822 (also with a "KST" zone or "+09:00" offset), RFC 3339, or
+/// Korean forms like "2026.10.01 09:00", "2026-10-01 09:00:00", "2026년 10월 1일 오후 3:20".
+/// Dates without a zone are taken as Korean time.
+pub fn parse_pub_date(raw: &str) -> Option<DateTime<FixedOffset>> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return None;
+    }
+    if let Ok(date) = DateTime::parse_from_rfc3339(raw) {
+        return Some(date);
+    }
 
-        let results: Vec<NewsResult> = feed.channel.item
-            .into_iter()
-            .filter(|item| {
-                let title = item.title.as_deref().unwrap_or("").to_lowercase();
-                let desc = item.description.as_deref().unwrap_or("").to_lowercase();
-                title.contains(&keyword_lower) || desc.contains(&keyword_lower)
-            })
-            .map(|item| NewsResult {
-                title: item.title.unwrap_or_else(|| "제목 없음".to_string()),
-                description: clean_html(&item.description.unwrap_or_default()),
-                link: item.link.unwrap_or_default(),
-                pub_date: item.pub_date.unwrap_or_else(|| "Unknown".to_string()),
-                source: source.to_string(),
-            })
-            .take(10) // Limit results per feed
-            .collect();
+    // 요일이 날짜와 맞지 않는 피드가 있어 요일 없이도 읽어봅니다
+    let without_weekday = raw.split_once(", ").map(|(_, rest)| rest).unwrap_or(raw);
+    for candidate in [raw, without_weekday] {
+        if let Ok(date) = DateTime::parse_from_rfc2822(candidate) {
+            return Some(date);
+        }
+        // RFC 2822 파서가 모르는 시간대 이름
+        for (zone, offset) in [(" KST", " +0900"), (" JST", " +0900"), (" UTC", " +0000")] {
+            if let Some(rest) = candidate.strip_suffix(zone) {
+                if let Ok(date) = DateTime::parse_from_rfc2822(&format!("{}{}", rest, offset)) {
+                    return Some(date);
+                }
+            }
+        }
+        // 콜론이 들어간 오프셋
+        if let Ok(date) = DateTime::parse_from_str(candidate, "%d %b %Y %H:%M:%S %:z") {
+            return Some(date);
+        }
+    }
+    for format in ["%Y-%m-%d %H:%M:%S %z", "%Y-%m-%d %H:%M:%S%:z"] {
+        if let Ok(date) = DateTime::parse_from_str(raw, format) {
+            return Some(date);
+        }
+    }
 
-        Ok(results)
+    parse_local_date(raw)
+}
+
+/// Year-first dates in any punctuation, read as Korean time
+fn parse_local_date(raw: &str) -> Option<DateTime<FixedOffset>> {
+    let numbers: Vec<u32> = raw
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|part| !part.is_empty())
+        .take(6)
+        .map(|part| part.parse().ok())
+        .collect::<Option<_>>()?;
+    if numbers.len() < 3 || !(1900..=9999).contains(&numbers[0]) {
+        return None;
+    }
+
+    let date = NaiveDate::from_ymd_opt(numbers[0] as i32, numbers[1], numbers[2])?;
+    let mut hour = numbers.get(3).copied().unwrap_or(0);
+    let upper = raw.to_ascii_uppercase();
+    if (raw.contains("오후") || upper.contains("PM")) && hour < 12 {
+        hour += 12;
+    } else if (raw.contains("오전") || upper.contains("AM")) && hour == 12 {
+        // 오전 12시는 자정입니다
+        hour = 0;
     }
+    let time = date.and_hms_opt(
+        hour,
+        numbers.get(4).copied().unwrap_or(0),
+        numbers.get(5).copied().unwrap_or(0),
+    )?;
+
+    FixedOffset::east_opt(KST_OFFSET_SECONDS)?.from_local_datetime(&time).single()
+}
+
+// ============================================
+// RSS / Atom parsing
+// ============================================
+
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    FeedTitle,
+    Title,
+    Summary,
+    Content,
+    Link,
+    Published,
+    Updated,
+    Category,
+}
+
+/// Parse RSS 2.0, RSS 1.0 (RDF) or Atom
+pub fn parse_feed(xml: &str) -> Result<ParsedFeed, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut format = None;
+    let mut title = None;
+    let mut entries = Vec::new();
+
+    let mut depth = 0usize;
+    let mut entry: Option<(FeedEntry, Option<String>, Option<String>)> = None; // (entry, content, updated)
+    let mut capture: Option<(Field, usize, String)> = None;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("RSS 파싱 실패 (위치 {}): {}", reader.buffer_position(), e))?;
+
+        match event {
+            Event::Start(e) => {
+                let name = local_name(&e);
+                if format.is_none() {
+                    format = Some(match name.as_str() {
+                        "rss" | "RDF" => FeedFormat::Rss,
+                        "feed" => FeedFormat::Atom,
+                        _ => return Err("RSS 또는 Atom 피드가 아닙니다".to_string()),
+                    });
+                } else if capture.is_none() {
+                    if name == "item" || name == "entry" {
+                        entry = Some((FeedEntry::default(), None, None));
+                    } else if let Some((current, _, _)) = entry.as_mut() {
+                        if name == "link" && format == Some(FeedFormat::Atom) {
+                            take_atom_link(&e, current);
+                        }
+                        if name == "category" {
+                            if let Some(term) = attribute(&e, "term") {
+                                current.categories.push(term);
+                            }
+                        }
+                        capture = entry_field(&name, format).map(|f| (f, depth, String::new()));
+                    } else if name == "title" && title.is_none() {
+                        capture = Some((Field::FeedTitle, depth, String::new()));
+                    }
+                }
+                depth += 1;
+            }
+            Event::Empty(e) => {
+                if let Some((current, _, _)) = entry.as_mut() {
+                    match local_name(&e).as_str() {
+                        "link" => take_atom_link(&e, current),
+                        "category" => current.categories.extend(attribute(&e, "term")),
+                        _ => {}
+                    }
+                }
+            }
+            Event::Text(e) => {
+                if let Some((_, _, text)) = capture.as_mut() {
+                    match e.unescape() {
+                        Ok(raw) => text.push_str(&raw),
+                        Err(_) => text.push_str(&String::from_utf8_lossy(&e)),
+                    }
+                }
+            }
+            Event::CData(e) => {
+                if let Some((_, _, text)) = capture.as_mut() {
+                    text.push_str(&String::from_utf8_lossy(&e.into_inner()));
+                }
+            }
+            Event::End(e) => {
+                depth = depth.saturating_sub(1);
+
+                if capture.as_ref().is_some_and(|(_, d, _)| *d == depth) {
+                    let (field, _, text) = capture.take().unwrap_or((Field::Title, 0, String::new()));
+                    let text = text.trim().to_string();
+                    if field == Field::FeedTitle {
+                        title = Some(text).filter(|t| !t.is_empty());
+                    } else if let Some((current, content, updated)) = entry.as_mut() {
+                        store_field(current, content, updated, field, text);
+                    }
+                    continue;
+                }
+
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                if name == "item" || name == "entry" {
+                    if let Some((mut done, content, updated)) = entry.take() {
+                        // 요약이 없으면 본문을, 게시일이 없으면 수정일을 씁니다
+                        if done.summary.is_empty() {
+                            done.summary = content.unwrap_or_default();
+                        }
+                        if done.published.is_none() {
+                            done.published = updated;
+                        }
+                        entries.push(done);
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    let format = format.ok_or_else(|| "빈 피드입니다".to_string())?;
+    Ok(ParsedFeed { format, title, entries })
+}
+
+fn entry_field(name: &str, format: Option<FeedFormat>) -> Option<Field> {
+    let field = match name {
+        "title" => Field::Title,
+        "description" | "summary" => Field::Summary,
+        // content:encoded (RSS) 또는 content (Atom)
+        "encoded" | "content" => Field::Content,
+        "link" if format == Some(FeedFormat::Rss) => Field::Link,
+        "pubDate" | "published" | "issued" => Field::Published,
+        // dc:date (RSS 1.0) 또는 updated (Atom)
+        "date" | "updated" | "modified" => Field::Updated,
+        "category" => Field::Category,
+        _ => return None,
+    };
+    Some(field)
+}
+
+fn store_field(
+    entry: &mut FeedEntry,
+    content: &mut Option<String>,
+    updated: &mut Option<String>,
+    field: Field,
+    text: String,
+) {
+    if text.is_empty() {
+        return;
+    }
+    match field {
+        Field::Title => entry.title = text,
+        Field::Summary => entry.summary = text,
+        Field::Content => *content = Some(text),
+        Field::Link => entry.link = text,
+        Field::Published => entry.published = Some(text),
+        Field::Updated => *updated = Some(text),
+        Field::Category => entry.categories.push(text),
+        Field::FeedTitle => {}
+    }
+}
+
+/// Atom links carry the URL in `href`; the alternate (or untyped) one is the article
+fn take_atom_link(e: &BytesStart, entry: &mut FeedEntry) {
+    let rel = attribute(e, "rel");
+    if !entry.link.is_empty() || !matches!(rel.as_deref(), None | Some("alternate")) {
+        return;
+    }
+    if let Some(href) = attribute(e, "href") {
+        entry.link = href;
+    }
+}
+
+fn local_name(e: &BytesStart) -> String {
+    String::from_utf8_lossy(e.local_name().as_ref()).into_owned()
+}
+
+fn attribute(e: &BytesStart, name: &str) -> Option<String> {
+    e.try_get_attribute(name)
+        .ok()
+        .flatten()
+        .and_then(|a| a.unescape_value().ok())
+        .map(|v| v.into_owned())
 }
 
 /// Remove HTML tags from text
@@ -187,3 +644,245 @@ fn clean_html(text: &str) -> String {
         .trim()
         .to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rss2_items() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/">
+  <channel>
+    <title>뷰티 뉴스</title>
+    <item>
+      <title><![CDATA[[속보] 나이아신아마이드 <b>미백</b> 효과]]></title>
+      <link>https://news.example/1</link>
+      <description>요약 &amp; 설명</description>
+      <pubDate>Wed, 01 Oct 2025 09:00:00 +0900</pubDate>
+      <category>화장품</category>
+    </item>
+    <item>
+      <title>레티놀 주의사항</title>
+      <link>https://news.example/2</link>
+      <content:encoded><![CDATA[<p>본문만 있는 기사</p>]]></content:encoded>
+    </item>
+  </channel>
+</rss>"#;
+
+        let feed = parse_feed(xml).unwrap();
+        assert_eq!(feed.format, FeedFormat::Rss);
+        assert_eq!(feed.title.as_deref(), Some("뷰티 뉴스"));
+        assert_eq!(feed.entries.len(), 2);
+
+        let first = &feed.entries[0];
+        assert_eq!(first.title, "[속보] 나이아신아마이드 <b>미백</b> 효과");
+        assert_eq!(first.link, "https://news.example/1");
+        assert_eq!(first.summary, "요약 & 설명");
+        assert_eq!(first.published.as_deref(), Some("Wed, 01 Oct 2025 09:00:00 +0900"));
+        assert_eq!(first.categories, vec!["화장품".to_string()]);
+
+        // 요약이 없으면 본문을 씁니다
+        assert_eq!(feed.entries[1].summary, "<p>본문만 있는 기사</p>");
+        assert_eq!(feed.entries[1].published, None);
+    }
+
+    #[test]
+    fn parses_atom_entries() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Cosmetics Journal</title>
+  <entry>
+    <title type="html">Retinol &amp;amp; retinal</title>
+    <link rel="self" href="https://journal.example/self/1"/>
+    <link rel="alternate" href="https://journal.example/articles/1"/>
+    <summary>Summary text</summary>
+    <updated>2025-10-02T10:00:00Z</updated>
+    <category term="skincare"/>
+  </entry>
+  <entry>
+    <title>Peptides</title>
+    <link href="https://journal.example/articles/2"/>
+    <published>2025-10-01T08:30:00+09:00</published>
+    <updated>2025-10-03T00:00:00Z</updated>
+    <content type="html">&lt;p&gt;Body&lt;/p&gt;</content>
+  </entry>
+</feed>"#;
+
+        let feed = parse_feed(xml).unwrap();
+        assert_eq!(feed.format, FeedFormat::Atom);
+        assert_eq!(feed.title.as_deref(), Some("Cosmetics Journal"));
+
+        let first = &feed.entries[0];
+        assert_eq!(first.title, "Retinol &amp; retinal");
+        assert_eq!(first.link, "https://journal.example/articles/1");
+        // 게시일이 없으면 수정일을 씁니다
+        assert_eq!(first.published.as_deref(), Some("2025-10-02T10:00:00Z"));
+        assert_eq!(first.categories, vec!["skincare".to_string()]);
+
+        let second = &feed.entries[1];
+        assert_eq!(second.link, "https://journal.example/articles/2");
+        assert_eq!(second.published.as_deref(), Some("2025-10-01T08:30:00+09:00"));
+        assert_eq!(second.summary, "<p>Body</p>");
+    }
+
+    #[test]
+    fn parses_rss1_rdf_items() {
+        let xml = r#"<?xml version="1.0"?>
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns="http://purl.org/rss/1.0/"
+         xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <channel><title>RDF feed</title></channel>
+  <item>
+    <title>Ceramide study</title>
+    <link>https://rdf.example/1</link>
+    <dc:date>2025-09-30T12:00:00+00:00</dc:date>
+  </item>
+</rdf:RDF>"#;
+
+        let feed = parse_feed(xml).unwrap();
+        assert_eq!(feed.format, FeedFormat::Rss);
+        assert_eq!(feed.entries.len(), 1);
+        assert_eq!(feed.entries[0].link, "https://rdf.example/1");
+        assert_eq!(feed.entries[0].published.as_deref(), Some("2025-09-30T12:00:00+00:00"));
+    }
+
+    #[test]
+    fn rejects_documents_that_are_not_feeds() {
+        assert!(parse_feed("<html><body>Not a feed</body></html>").is_err());
+        assert!(parse_feed("").is_err());
+        assert!(parse_feed("<rss><channel><item><title>broken</rss>").is_err());
+    }
+
+    #[test]
+    fn reads_feed_dates() {
+        let kst = |s: &str| DateTime::parse_from_rfc3339(s).unwrap();
+        assert_eq!(parse_pub_date("Wed, 01 Oct 2025 09:00:00 +0900"), Some(kst("2025-10-01T09:00:00+09:00")));
+        assert_eq!(parse_pub_date("Mon, 01 Oct 2025 09:00:00 KST"), Some(kst("2025-10-01T09:00:00+09:00")));
+        assert_eq!(parse_pub_date("2025.10.01 09:00"), Some(kst("2025-10-01T09:00:00+09:00")));
+        assert_eq!(parse_pub_date("2025년 10월 1일 오후 3:20"), Some(kst("2025-10-01T15:20:00+09:00")));
+        assert_eq!(parse_pub_date("2025년 10월 1일 오후 12:05"), Some(kst("2025-10-01T12:05:00+09:00")));
+        assert_eq!(parse_pub_date("2025년 10월 1일 오전 12:05"), Some(kst("2025-10-01T00:05:00+09:00")));
+        assert_eq!(parse_pub_date("2025-10-01 12:30 AM"), Some(kst("2025-10-01T00:30:00+09:00")));
+        assert_eq!(parse_pub_date("2025-10-01 12:30 PM"), Some(kst("2025-10-01T12:30:00+09:00")));
+        assert_eq!(parse_pub_date("어제"), None);
+    }
+
+    fn article(published: &str) -> NewsResult {
+        NewsResult {
+            title: "기사".to_string(),
+            description: String::new(),
+            link: String::new(),
+            pub_date: published.to_string(),
+            published_at: parse_pub_date(published),
+            source: "피드".to_string(),
+            category: String::new(),
+            relevance: 0.0,
+            other_sources: vec![],
+        }
+    }
+
+    #[test]
+    fn filters_by_date_window() {
+        let now = Utc.with_ymd_and_hms(2025, 10, 10, 0, 0, 0).unwrap();
+        let recent = article("2025-10-08T00:00:00Z");
+        let old = article("2025-09-01T00:00:00Z");
+        let undated = article("Unknown");
+
+        let last_week = NewsSearchFilters {
+            days: Some(7),
+            ..NewsSearchFilters::default()
+        };
+        assert!(in_date_window(&recent, &last_week, now));
+        assert!(!in_date_window(&old, &last_week, now));
+        assert!(!in_date_window(&undated, &last_week, now));
+
+        let september = NewsSearchFilters {
+            date_from: NaiveDate::from_ymd_opt(2025, 9, 1),
+            date_to: NaiveDate::from_ymd_opt(2025, 9, 30),
+            ..NewsSearchFilters::default()
+        };
+        assert!(in_date_window(&old, &september, now));
+        assert!(!in_date_window(&recent, &september, now));
+        assert!(in_date_window(&undated, &NewsSearchFilters::default(), now));
+    }
+
+    #[test]
+    fn huge_day_windows_do_not_panic() {
+        let now = Utc.with_ymd_and_hms(2025, 10, 10, 0, 0, 0).unwrap();
+        let filters = NewsSearchFilters {
+            days: Some(u32::MAX),
+            ..NewsSearchFilters::default()
+        };
+        assert!(in_date_window(&article("1999-01-01T00:00:00Z"), &filters, now));
+    }
+
+    fn story(title: &str, link: &str, source: &str, relevance: f64, published: &str) -> NewsResult {
+        NewsResult {
+            title: title.to_string(),
+            link: link.to_string(),
+            source: source.to_string(),
+            relevance,
+            ..article(published)
+        }
+    }
+
+    fn sources(result: &NewsResult) -> (&str, Vec<&str>) {
+        (result.source.as_str(), result.other_sources.iter().map(String::as_str).collect())
+    }
+
+    #[test]
+    fn strips_tags_and_outlet_suffixes_from_headlines() {
+        assert_eq!(story_title("[속보] 식약처, 미백 고시 개정 - 연합뉴스"), "식약처, 미백 고시 개정");
+        assert_eq!(story_title("(종합)【단독】 식약처, 미백 고시 개정 | 뉴시스"), "식약처, 미백 고시 개정");
+        assert_eq!(story_title("Retinol sales jump in Korea :: Beauty Daily"), "Retinol sales jump in Korea");
+        // 구분자 앞이 너무 짧으면 제목의 일부로 봅니다
+        assert_eq!(story_title("레티놀 - 알고 쓰자"), "레티놀 - 알고 쓰자");
+        assert_eq!(story_title("[닫히지 않은 태그"), "[닫히지 않은 태그");
+    }
+
+    #[test]
+    fn collapses_syndicated_stories() {
+        let results = vec![
+            story("[속보] 식약처, 나이아신아마이드 고시 개정 - 연합뉴스", "https://a/1", "연합뉴스", 0.8, "2025-10-01T09:00:00+09:00"),
+            story("레티놀 크림 판매 급증 - 연합뉴스", "https://a/2", "연합뉴스", 0.5, "2025-10-01T10:00:00+09:00"),
+            story("식약처, 나이아신아마이드 고시 개정 | 뉴시스", "https://b/1", "뉴시스", 0.8, "2025-10-01T08:30:00+09:00"),
+            story("(종합) 식약처, 나이아신아마이드 고시 개정 :: 뷰티경제", "https://c/1", "뷰티경제", 0.6, ""),
+            // 링크가 같으면 제목이 달라도 같은 기사입니다
+            story("레티놀 판매 늘어", "https://a/2", "연합뉴스 경제", 0.4, ""),
+            // 짧은 제목은 링크가 다르면 합치지 않습니다
+            story("제목 없음", "https://d/1", "피드1", 0.3, ""),
+            story("제목 없음", "https://d/2", "피드2", 0.3, ""),
+        ];
+
+        let collapsed = collapse_duplicates(results);
+        assert_eq!(collapsed.len(), 4);
+        // 관련도가 같으면 먼저 나온 기사를 남깁니다
+        assert_eq!(collapsed[0].title, "식약처, 나이아신아마이드 고시 개정 | 뉴시스");
+        assert_eq!(sources(&collapsed[0]), ("뉴시스", vec!["연합뉴스", "뷰티경제"]));
+        assert_eq!(sources(&collapsed[1]), ("연합뉴스", vec!["연합뉴스 경제"]));
+        assert_eq!(collapsed[1].title, "레티놀 크림 판매 급증 - 연합뉴스");
+        assert_eq!(sources(&collapsed[2]), ("피드1", vec![]));
+        assert_eq!(sources(&collapsed[3]), ("피드2", vec![]));
+    }
+
+    #[test]
+    fn sorts_by_relevance_or_date() {
+        let mut results = vec![
+            story("a", "", "피드", 0.5, "2025-10-01T09:00:00+09:00"),
+            story("b", "", "피드", 0.9, "2025-09-01T09:00:00+09:00"),
+            story("c", "", "피드", 0.5, "2025-10-02T09:00:00+09:00"),
+            story("d", "", "피드", 0.7, ""),
+        ];
+        let titles = |results: &[NewsResult]| results.iter().map(|r| r.title.clone()).collect::<Vec<_>>();
+
+        sort_results(&mut results, NewsSort::Relevance);
+        assert_eq!(titles(&results), ["b", "d", "c", "a"]);
+        sort_results(&mut results, NewsSort::Newest);
+        assert_eq!(titles(&results), ["c", "a", "b", "d"]);
+    }
+
+    #[test]
+    fn strips_html_from_text() {
+        assert_eq!(clean_html("<p>레티놀 &amp; <b>비타민C</b></p>"), "레티놀 & 비타민C");
+    }
+}
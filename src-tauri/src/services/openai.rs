@@ -1,10 +1,14 @@
-use reqwest::Client;
+use super::scheduler::{self, Provider, SendScheduled};
+use super::stream::{self, OnToken};
+use crate::models::{ModelInfo, ModelSettings, ProviderModels};
+use reqwest::{Client, Response};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 pub struct OpenAIService {
     client: Client,
     api_key: String,
+    models: ProviderModels,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -17,8 +21,17 @@ struct ChatMessage {
 struct ChatCompletionRequest {
     model: String,
     messages: Vec<ChatMessage>,
-    temperature: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     max_tokens: Option<u32>,
+    /// Reasoning models take this in place of `max_tokens`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_completion_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -31,6 +44,34 @@ struct ChatChoice {
     message: ChatMessage,
 }
 
+// Streamed completion chunk (`stream: true`)
+#[derive(Debug, Deserialize)]
+struct ChatCompletionChunk {
+    #[serde(default)]
+    choices: Vec<ChunkChoice>,
+    error: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChunkChoice {
+    delta: ChunkDelta,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChunkDelta {
+    content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelListResponse {
+    data: Vec<ModelEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelEntry {
+    id: String,
+}
+
 #[derive(Debug, Deserialize)]
 struct ImageGenerationResponse {
     data: Vec<ImageData>,
@@ -44,13 +85,120 @@ struct ImageData {
 
 impl OpenAIService {
     pub fn new(api_key: &str) -> Self {
+        Self::with_models(api_key, ModelSettings::default().openai)
+    }
+
+    pub fn with_models(api_key: &str, models: ProviderModels) -> Self {
         Self {
-            client: Client::new(),
+            client: scheduler::http_client(),
             api_key: api_key.to_string(),
+            models,
         }
     }
 
     pub async fn generate_text(&self, prompt: &str, system_prompt: Option<&str>) -> Result<String, String> {
+        self.chat_completion(prompt, system_prompt, None).await
+    }
+
+    /// Chat completion constrained to `schema` via Structured Outputs; returns the JSON text
+    pub async fn generate_structured(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        schema_name: &str,
+        schema: &serde_json::Value,
+    ) -> Result<String, String> {
+        let response_format = json_schema_format(schema_name, schema);
+        self.chat_completion(prompt, system_prompt, Some(response_format)).await
+    }
+
+    /// Like `generate_text`, but streams the reply and passes each token to `on_token`
+    pub async fn stream_text(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        on_token: &mut OnToken<'_>,
+    ) -> Result<String, String> {
+        self.stream_chat_completion(prompt, system_prompt, None, on_token).await
+    }
+
+    /// Like `generate_structured`, but streams the JSON text as it is produced
+    pub async fn stream_structured(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        schema_name: &str,
+        schema: &serde_json::Value,
+        on_token: &mut OnToken<'_>,
+    ) -> Result<String, String> {
+        let response_format = json_schema_format(schema_name, schema);
+        self.stream_chat_completion(prompt, system_prompt, Some(response_format), on_token)
+            .await
+    }
+
+    async fn chat_completion(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        response_format: Option<serde_json::Value>,
+    ) -> Result<String, String> {
+        let request = self.chat_request(prompt, system_prompt, response_format, false);
+        let response = self.post_chat(&request).await?;
+
+        let result: ChatCompletionResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("응답 파싱 실패: {}", e))?;
+
+        result.choices
+            .first()
+            .map(|c| c.message.content.clone())
+            .ok_or_else(|| "응답이 비어있습니다".to_string())
+    }
+
+    async fn stream_chat_completion(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        response_format: Option<serde_json::Value>,
+        on_token: &mut OnToken<'_>,
+    ) -> Result<String, String> {
+        let request = self.chat_request(prompt, system_prompt, response_format, true);
+        let response = self.post_chat(&request).await?;
+
+        let mut text = String::new();
+        stream::read_sse(response, |_, data| {
+            if data == "[DONE]" {
+                return Ok(false);
+            }
+
+            let chunk: ChatCompletionChunk =
+                serde_json::from_str(data).map_err(|e| format!("스트림 응답 파싱 실패: {}", e))?;
+            if let Some(error) = chunk.error {
+                return Err(format!("OpenAI API 오류: {}", error));
+            }
+
+            if let Some(delta) = chunk.choices.first().and_then(|c| c.delta.content.as_deref()) {
+                text.push_str(delta);
+                on_token(delta);
+            }
+            Ok(true)
+        })
+        .await?;
+
+        if text.is_empty() {
+            return Err("응답이 비어있습니다".to_string());
+        }
+        Ok(text)
+    }
+
+    fn chat_request(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        response_format: Option<serde_json::Value>,
+        stream: bool,
+    ) -> ChatCompletionRequest {
         let mut messages = vec![];
 
         if let Some(sys) = system_prompt {
@@ -65,19 +213,25 @@ impl OpenAIService {
             content: prompt.to_string(),
         });
 
-        let request = ChatCompletionRequest {
-            model: "gpt-4o-mini".to_string(),
+        let reasoning = is_reasoning_model(&self.models.text);
+        ChatCompletionRequest {
+            model: self.models.text.clone(),
             messages,
-            temperature: 0.7,
-            max_tokens: Some(4096),
-        };
+            temperature: (!reasoning).then_some(self.models.temperature),
+            max_tokens: (!reasoning).then_some(self.models.max_tokens),
+            max_completion_tokens: reasoning.then_some(self.models.max_tokens),
+            response_format,
+            stream: stream.then_some(true),
+        }
+    }
 
+    async fn post_chat(&self, request: &ChatCompletionRequest) -> Result<Response, String> {
         let response = self.client
             .post("https://api.openai.com/v1/chat/completions")
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
+            .json(request)
+            .send_scheduled(Provider::OpenAI)
             .await
             .map_err(|e| format!("OpenAI API 요청 실패: {}", e))?;
 
@@ -85,21 +239,12 @@ impl OpenAIService {
             let error_text = response.text().await.unwrap_or_default();
             return Err(format!("OpenAI API 오류: {}", error_text));
         }
-
-        let result: ChatCompletionResponse = response
-            .json()
-            .await
-            .map_err(|e| format!("응답 파싱 실패: {}", e))?;
-
-        result.choices
-            .first()
-            .map(|c| c.message.content.clone())
-            .ok_or_else(|| "응답이 비어있습니다".to_string())
+        Ok(response)
     }
 
     pub async fn generate_image(&self, prompt: &str, size: &str) -> Result<String, String> {
         let request_body = json!({
-            "model": "dall-e-3",
+            "model": self.models.image.as_deref().unwrap_or("dall-e-3"),
             "prompt": prompt,
             "n": 1,
             "size": size,
@@ -112,7 +257,7 @@ impl OpenAIService {
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
             .json(&request_body)
-            .send()
+            .send_scheduled(Provider::OpenAI)
             .await
             .map_err(|e| format!("DALL-E API 요청 실패: {}", e))?;
 
@@ -139,8 +284,8 @@ impl OpenAIService {
         system_prompt: &str,
         user_prompt: &str,
     ) -> Result<String, String> {
-        let request_body = json!({
-            "model": "gpt-4o",
+        let mut request_body = json!({
+            "model": self.models.vision,
             "messages": [
                 {
                     "role": "system",
@@ -161,16 +306,21 @@ impl OpenAIService {
                         }
                     ]
                 }
-            ],
-            "max_tokens": 2048
+            ]
         });
+        let token_limit = if is_reasoning_model(&self.models.vision) {
+            "max_completion_tokens"
+        } else {
+            "max_tokens"
+        };
+        request_body[token_limit] = json!(self.models.max_tokens);
 
         let response = self.client
             .post("https://api.openai.com/v1/chat/completions")
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
             .json(&request_body)
-            .send()
+            .send_scheduled(Provider::OpenAI)
             .await
             .map_err(|e| format!("OpenAI Vision API 요청 실패: {}", e))?;
 
@@ -189,4 +339,125 @@ impl OpenAIService {
             .map(|c| c.message.content.clone())
             .ok_or_else(|| "응답이 비어있습니다".to_string())
     }
+
+    /// Models available to this API key
+    pub async fn list_models(&self) -> Result<Vec<ModelInfo>, String> {
+        let response = self.client
+            .get("https://api.openai.com/v1/models")
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .send_scheduled(Provider::OpenAI)
+            .await
+            .map_err(|e| format!("OpenAI 모델 목록 요청 실패: {}", e))?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(format!("OpenAI 모델 목록 오류: {}", error_text));
+        }
+
+        let result: ModelListResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("응답 파싱 실패: {}", e))?;
+
+        let mut models: Vec<ModelInfo> = result
+            .data
+            .into_iter()
+            .filter_map(|m| {
+                let tasks = openai_model_tasks(&m.id);
+                (!tasks.is_empty()).then(|| ModelInfo {
+                    reasoning: is_reasoning_model(&m.id),
+                    id: m.id,
+                    display_name: None,
+                    tasks,
+                })
+            })
+            .collect();
+        models.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(models)
+    }
+}
+
+fn json_schema_format(schema_name: &str, schema: &serde_json::Value) -> serde_json::Value {
+    json!({
+        "type": "json_schema",
+        "json_schema": {
+            "name": schema_name,
+            "schema": schema,
+            "strict": false
+        }
+    })
+}
+
+/// o-series and gpt-5 models reject `temperature` and `max_tokens` (they take `max_completion_tokens`);
+/// the gpt-5 chat variants are regular chat models
+fn is_reasoning_model(id: &str) -> bool {
+    let id = id.rsplit('/').next().unwrap_or(id);
+    let o_series = id.len() > 1 && id.starts_with('o') && id[1..].starts_with(|c: char| c.is_ascii_digit());
+    o_series || (id.starts_with("gpt-5") && !id.contains("-chat"))
+}
+
+/// The models endpoint has no capability info, so infer it from the id (embeddings, audio etc. are skipped)
+fn openai_model_tasks(id: &str) -> Vec<String> {
+    let skip = ["embedding", "whisper", "tts", "audio", "realtime", "transcribe", "moderation", "search"];
+    if skip.iter().any(|s| id.contains(s)) {
+        return vec![];
+    }
+
+    if id.starts_with("dall-e") || id.starts_with("gpt-image") {
+        return vec!["image".to_string()];
+    }
+
+    let vision = id.starts_with("gpt-4o")
+        || id.starts_with("gpt-4.1")
+        || id.starts_with("gpt-4-turbo")
+        || id.starts_with("gpt-5")
+        || id.starts_with("o1")
+        || id.starts_with("o3")
+        || id.starts_with("o4");
+
+    if vision {
+        vec!["text".to_string(), "vision".to_string()]
+    } else if id.starts_with("gpt-") || id.starts_with("chatgpt") {
+        vec!["text".to_string()]
+    } else {
+        vec![]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_reasoning_models() {
+        for id in ["o1", "o1-mini", "o3-2025-04-16", "o4-mini", "gpt-5", "gpt-5-mini", "gpt-5.1"] {
+            assert!(is_reasoning_model(id), "{id}");
+        }
+        for id in ["gpt-4o", "gpt-4o-mini", "gpt-4.1", "gpt-5-chat-latest", "omni-moderation-latest"] {
+            assert!(!is_reasoning_model(id), "{id}");
+        }
+    }
+
+    fn service(text_model: &str) -> OpenAIService {
+        let mut models = ModelSettings::default().openai;
+        models.text = text_model.to_string();
+        models.max_tokens = 2000;
+        OpenAIService::with_models("key", models)
+    }
+
+    #[test]
+    fn reasoning_requests_use_max_completion_tokens_without_temperature() {
+        let body = serde_json::to_value(service("o3-mini").chat_request("hi", None, None, false)).unwrap();
+        assert_eq!(body["max_completion_tokens"], 2000);
+        assert!(body.get("max_tokens").is_none());
+        assert!(body.get("temperature").is_none());
+    }
+
+    #[test]
+    fn chat_requests_keep_temperature_and_max_tokens() {
+        let body = serde_json::to_value(service("gpt-4o-mini").chat_request("hi", None, None, false)).unwrap();
+        assert_eq!(body["max_tokens"], 2000);
+        assert!(body.get("temperature").is_some());
+        assert!(body.get("max_completion_tokens").is_none());
+    }
 }
@@ -1,9 +1,14 @@
+use super::scheduler::{self, Provider, SendScheduled};
+use crate::models::{AbstractSection, AppSettings, ArticleType, NcbiSettings, PaperSearchFilters, PaperSort, Population};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
 use reqwest::Client;
 use serde::Deserialize;
 
 pub struct PubMedService {
     client: Client,
     base_url: String,
+    ncbi: NcbiSettings,
 }
 
 #[derive(Debug, Deserialize)]
@@ -18,115 +23,104 @@ struct ESearchData {
     count: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
-struct EFetchResult {
-    #[serde(rename = "PubmedArticle", default)]
-    articles: Vec<PubmedArticle>,
-}
-
-#[derive(Debug, Deserialize)]
-struct PubmedArticle {
-    #[serde(rename = "MedlineCitation")]
-    medline_citation: MedlineCitation,
-}
-
-#[derive(Debug, Deserialize)]
-struct MedlineCitation {
-    #[serde(rename = "PMID")]
-    pmid: PmidElement,
-    #[serde(rename = "Article")]
-    article: Article,
-}
-
-#[derive(Debug, Deserialize)]
-struct PmidElement {
-    #[serde(rename = "$value", default)]
-    value: String,
-}
-
-#[derive(Debug, Deserialize)]
-struct Article {
-    #[serde(rename = "ArticleTitle")]
-    title: Option<String>,
-    #[serde(rename = "Abstract")]
-    article_abstract: Option<AbstractText>,
-    #[serde(rename = "AuthorList")]
-    author_list: Option<AuthorList>,
-    #[serde(rename = "Journal")]
-    journal: Option<Journal>,
-}
-
-#[derive(Debug, Deserialize)]
-struct AbstractText {
-    #[serde(rename = "AbstractText")]
-    text: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-struct AuthorList {
-    #[serde(rename = "Author", default)]
-    authors: Vec<Author>,
-}
-
-#[derive(Debug, Deserialize)]
-struct Author {
-    #[serde(rename = "LastName")]
-    last_name: Option<String>,
-    #[serde(rename = "ForeName")]
-    fore_name: Option<String>,
-    #[serde(rename = "Initials")]
-    initials: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-struct Journal {
-    #[serde(rename = "JournalIssue")]
-    journal_issue: Option<JournalIssue>,
-}
-
-#[derive(Debug, Deserialize)]
-struct JournalIssue {
-    #[serde(rename = "PubDate")]
-    pub_date: Option<PubDate>,
-}
-
-#[derive(Debug, Deserialize)]
-struct PubDate {
-    #[serde(rename = "Year")]
-    year: Option<String>,
-}
-
 #[derive(Debug, Clone)]
 pub struct PaperInfo {
     pub pmid: String,
     pub title: String,
     pub authors: Vec<String>,
+    /// Every abstract section, prefixed with its label ("BACKGROUND: ...") when it has one
     pub abstract_text: String,
+    pub abstract_sections: Vec<AbstractSection>,
     pub year: String,
+    pub journal: Option<String>,
+    pub doi: Option<String>,
+    /// PubMed Central id ("PMC1234567") when the full text is in PMC
+    pub pmcid: Option<String>,
+    pub mesh_terms: Vec<String>,
+    pub keywords: Vec<String>,
+    /// PubMed publication types, e.g. "Randomized Controlled Trial", "Review"
+    pub publication_types: Vec<String>,
+    /// Country of the first author's affiliation, when it can be read off the address
+    pub country: Option<String>,
 }
 
 impl PubMedService {
     pub fn new() -> Self {
         Self {
-            client: Client::new(),
+            client: scheduler::http_client(),
             base_url: "https://eutils.ncbi.nlm.nih.gov/entrez/eutils".to_string(),
+            ncbi: NcbiSettings::default(),
+        }
+    }
+
+    /// Point the service at another E-utilities host (e.g. a local fixture server)
+    pub fn with_base_url(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            ..Self::new()
+        }
+    }
+
+    /// Endpoint override and NCBI identification from the app settings
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        let service = match &settings.source_endpoints.pubmed {
+            Some(url) => Self::with_base_url(url),
+            None => Self::new(),
+        };
+        service.with_ncbi(&settings.ncbi)
+    }
+
+    /// Send `api_key`/`tool`/`email` with every request
+    pub fn with_ncbi(mut self, ncbi: &NcbiSettings) -> Self {
+        self.ncbi = ncbi.clone();
+        self
+    }
+
+    /// Keyed requests get NCBI's higher rate limit
+    fn provider(&self) -> Provider {
+        if self.ncbi.api_key.is_some() {
+            Provider::NcbiWithKey
+        } else {
+            Provider::Ncbi
         }
     }
 
+    /// E-utilities URL with the given parameters plus the configured identification
+    fn eutils_url(&self, endpoint: &str, params: &[(&str, String)]) -> String {
+        let identification = [
+            ("api_key", &self.ncbi.api_key),
+            ("tool", &self.ncbi.tool),
+            ("email", &self.ncbi.email),
+        ];
+        let query: Vec<String> = params
+            .iter()
+            .map(|(key, value)| (*key, value.as_str()))
+            .chain(
+                identification
+                    .iter()
+                    .filter_map(|(key, value)| value.as_deref().map(|v| (*key, v))),
+            )
+            .map(|(key, value)| format!("{}={}", key, urlencoding::encode(value)))
+            .collect();
+        format!("{}/{}?{}", self.base_url, endpoint, query.join("&"))
+    }
+
     /// Search PubMed for articles matching the query
     pub async fn search(&self, query: &str, limit: u32) -> Result<Vec<PaperInfo>, String> {
+        self.search_query(&PubMedQuery::new(query).page(0, limit)).await
+    }
+
+    /// Run a built query: esearch for the page of ids, then efetch their records
+    pub async fn search_query(&self, query: &PubMedQuery) -> Result<Vec<PaperInfo>, String> {
         // Step 1: Search for IDs
-        let search_url = format!(
-            "{}/esearch.fcgi?db=pubmed&term={}&retmax={}&retmode=json&sort=relevance",
-            self.base_url,
-            urlencoding::encode(query),
-            limit
-        );
+        let mut params = query.search_params();
+        params.push(("retmode", "json".to_string()));
+        let search_url = self.eutils_url("esearch.fcgi", &params);
 
         let search_response = self
             .client
             .get(&search_url)
-            .send()
+            .send_scheduled(self.provider())
             .await
             .map_err(|e| format!("PubMed 검색 요청 실패: {}", e))?;
 
@@ -145,16 +139,19 @@ impl PubMedService {
         }
 
         // Step 2: Fetch article details
-        let fetch_url = format!(
-            "{}/efetch.fcgi?db=pubmed&id={}&retmode=xml",
-            self.base_url,
-            ids.join(",")
+        let fetch_url = self.eutils_url(
+            "efetch.fcgi",
+            &[
+                ("db", "pubmed".to_string()),
+                ("id", ids.join(",")),
+                ("retmode", "xml".to_string()),
+            ],
         );
 
         let fetch_response = self
             .client
             .get(&fetch_url)
-            .send()
+            .send_scheduled(self.provider())
             .await
             .map_err(|e| format!("PubMed fetch 요청 실패: {}", e))?;
 
@@ -167,107 +164,236 @@ impl PubMedService {
             .await
             .map_err(|e| format!("응답 텍스트 읽기 실패: {}", e))?;
 
-        // Parse XML and extract paper info
-        let papers = self.parse_pubmed_xml(&xml_text)?;
+        // efetch는 id 순서를 보장하지 않으므로 검색 순서(관련도/최신순)로 되돌립니다
+        let mut papers = parse_pubmed_xml(&xml_text)?;
+        papers.sort_by_key(|p| ids.iter().position(|id| *id == p.pmid).unwrap_or(usize::MAX));
         Ok(papers)
     }
 
-    /// Parse PubMed XML response
-    fn parse_pubmed_xml(&self, xml: &str) -> Result<Vec<PaperInfo>, String> {
-        let mut papers = Vec::new();
-
-        // Simple XML parsing using string manipulation for reliability
-        let articles: Vec<&str> = xml.split("<PubmedArticle>").skip(1).collect();
-
-        for article_xml in articles {
-            let pmid = self.extract_tag_content(article_xml, "PMID").unwrap_or_default();
-            let title = self
-                .extract_tag_content(article_xml, "ArticleTitle")
-                .unwrap_or_else(|| "제목 없음".to_string());
-            let abstract_text = self
-                .extract_tag_content(article_xml, "AbstractText")
-                .unwrap_or_else(|| "초록 없음".to_string());
-            let year = self
-                .extract_tag_content(article_xml, "Year")
-                .unwrap_or_else(|| "연도 미상".to_string());
-
-            // Extract authors
-            let authors = self.extract_authors(article_xml);
-
-            papers.push(PaperInfo {
-                pmid,
-                title,
-                authors,
-                abstract_text,
-                year,
-            });
+    /// Number of PubMed records matching the query (no records are fetched)
+    pub async fn count(&self, query: &str) -> Result<u64, String> {
+        let url = self.eutils_url(
+            "esearch.fcgi",
+            &[
+                ("db", "pubmed".to_string()),
+                ("term", query.to_string()),
+                ("retmax", "0".to_string()),
+                ("retmode", "json".to_string()),
+            ],
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .send_scheduled(self.provider())
+            .await
+            .map_err(|e| format!("PubMed 검색 요청 실패: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err("PubMed 검색 API 오류".to_string());
         }
 
-        Ok(papers)
+        let result: ESearchResult = response
+            .json()
+            .await
+            .map_err(|e| format!("검색 응답 파싱 실패: {}", e))?;
+
+        result
+            .esearchresult
+            .count
+            .and_then(|c| c.parse().ok())
+            .ok_or_else(|| "PubMed 검색 결과 수를 읽을 수 없습니다".to_string())
+    }
+
+    /// Search for cosmetic/skincare ingredient related papers under any of the ingredient's names
+    pub async fn search_ingredient(&self, names: &[String], limit: u32) -> Result<Vec<PaperInfo>, String> {
+        self.search_query(&PubMedQuery::ingredient_names(names).page(0, limit))
+            .await
+    }
+}
+
+// ============================================
+// Query builder
+// ============================================
+
+/// An esearch request: free-text term, PubMed field filters, sort order and page
+#[derive(Debug, Clone)]
+pub struct PubMedQuery {
+    term: String,
+    year_from: Option<u16>,
+    year_to: Option<u16>,
+    article_types: Vec<ArticleType>,
+    humans_only: bool,
+    languages: Vec<String>,
+    populations: Vec<Population>,
+    sort: PaperSort,
+    retstart: u32,
+    retmax: u32,
+}
+
+impl PubMedQuery {
+    pub fn new(term: &str) -> Self {
+        Self {
+            term: term.trim().to_string(),
+            year_from: None,
+            year_to: None,
+            article_types: vec![],
+            humans_only: false,
+            languages: vec![],
+            populations: vec![],
+            sort: PaperSort::Relevance,
+            retstart: 0,
+            retmax: 10,
+        }
     }
 
-    fn extract_tag_content(&self, xml: &str, tag: &str) -> Option<String> {
-        let start_tag = format!("<{}", tag);
-        let end_tag = format!("</{}>", tag);
+    /// The ingredient under any of its names, alone or in a cosmetic/skin context, in papers about
+    /// safety or efficacy
+    pub fn ingredient_names(names: &[String]) -> Self {
+        // 따옴표가 남으면 구문 검색이 깨지므로 지웁니다
+        let names: Vec<String> = names
+            .iter()
+            .map(|n| collapse_whitespace(&n.replace('"', " ")))
+            .filter(|n| !n.is_empty())
+            .collect();
+        let name = match names.as_slice() {
+            [] => return Self::new(""),
+            [single] => single.to_string(),
+            _ => format!(
+                "({})",
+                names.iter().map(|n| format!("\"{}\"", n)).collect::<Vec<_>>().join(" OR ")
+            ),
+        };
+        Self::new(&format!(
+            "({} OR {} cosmetic OR {} skin OR {} skincare) AND (safety OR efficacy OR benefit)",
+            name, name, name, name
+        ))
+    }
 
-        let start = xml.find(&start_tag)?;
-        let after_start = &xml[start..];
-        let content_start = after_start.find('>')? + 1;
-        let content = &after_start[content_start..];
-        let end = content.find(&end_tag)?;
+    /// Publication years, inclusive; either end may be open
+    pub fn years(mut self, from: Option<u16>, to: Option<u16>) -> Self {
+        self.year_from = from;
+        self.year_to = to;
+        self
+    }
 
-        let text = &content[..end];
-        // Remove any nested XML tags
-        let clean_text = self.strip_tags(text);
-        Some(clean_text.trim().to_string())
+    pub fn article_types(mut self, types: &[ArticleType]) -> Self {
+        self.article_types = types.to_vec();
+        self
     }
 
-    fn strip_tags(&self, text: &str) -> String {
-        let mut result = String::new();
-        let mut in_tag = false;
+    pub fn humans_only(mut self, humans_only: bool) -> Self {
+        self.humans_only = humans_only;
+        self
+    }
 
-        for c in text.chars() {
-            match c {
-                '<' => in_tag = true,
-                '>' => in_tag = false,
-                _ if !in_tag => result.push(c),
-                _ => {}
-            }
-        }
+    pub fn languages(mut self, languages: &[String]) -> Self {
+        self.languages = languages.to_vec();
+        self
+    }
 
-        result
+    pub fn populations(mut self, populations: &[Population]) -> Self {
+        self.populations = populations.to_vec();
+        self
+    }
+
+    pub fn sort(mut self, sort: PaperSort) -> Self {
+        self.sort = sort;
+        self
     }
 
-    fn extract_authors(&self, xml: &str) -> Vec<String> {
-        let mut authors = Vec::new();
+    pub fn page(mut self, retstart: u32, retmax: u32) -> Self {
+        self.retstart = retstart;
+        self.retmax = retmax;
+        self
+    }
 
-        // Find all Author elements
-        for author_part in xml.split("<Author").skip(1) {
-            if let Some(end_idx) = author_part.find("</Author>") {
-                let author_xml = &author_part[..end_idx];
-                let last_name = self
-                    .extract_tag_content(author_xml, "LastName")
-                    .unwrap_or_default();
-                let initials = self
-                    .extract_tag_content(author_xml, "Initials")
-                    .unwrap_or_default();
+    /// Apply everything in `filters` except the page size
+    pub fn filters(self, filters: &PaperSearchFilters) -> Self {
+        let retmax = self.retmax;
+        self.years(filters.year_from, filters.year_to)
+            .article_types(&filters.article_types)
+            .humans_only(filters.humans_only)
+            .languages(&filters.languages)
+            .populations(&filters.populations)
+            .sort(filters.sort)
+            .page(filters.offset, retmax)
+    }
 
-                if !last_name.is_empty() {
-                    authors.push(format!("{} {}", last_name, initials));
-                }
-            }
+    /// Full PubMed search term, e.g. `(retinol) AND (2015:3000[dp]) AND (humans[mh])`
+    pub fn term(&self) -> String {
+        let mut clauses = vec![format!("({})", self.term)];
+
+        if self.year_from.is_some() || self.year_to.is_some() {
+            // PubMed은 열린 범위를 지원하지 않으므로 충분히 넓은 연도로 채웁니다
+            clauses.push(format!(
+                "({}:{}[dp])",
+                self.year_from.unwrap_or(1800),
+                self.year_to.unwrap_or(3000)
+            ));
+        }
+
+        if !self.article_types.is_empty() {
+            let types: Vec<&str> = self
+                .article_types
+                .iter()
+                .map(|t| match t {
+                    ArticleType::MetaAnalysis => "meta-analysis[pt]",
+                    ArticleType::SystematicReview => "systematic review[pt]",
+                    ArticleType::RandomizedControlledTrial => "randomized controlled trial[pt]",
+                    ArticleType::ClinicalTrial => "clinical trial[pt]",
+                    ArticleType::Review => "review[pt]",
+                    ArticleType::CaseReport => "case reports[pt]",
+                })
+                .collect();
+            clauses.push(format!("({})", types.join(" OR ")));
+        }
+
+        if self.humans_only {
+            clauses.push("(humans[mh])".to_string());
+        }
+
+        // 검색식이 깨지지 않도록 언어 이름은 글자만 남깁니다
+        let languages: Vec<String> = self
+            .languages
+            .iter()
+            .map(|l| l.chars().filter(|c| c.is_ascii_alphabetic()).collect::<String>().to_lowercase())
+            .filter(|l| !l.is_empty())
+            .map(|l| format!("{}[la]", l))
+            .collect();
+        if !languages.is_empty() {
+            clauses.push(format!("({})", languages.join(" OR ")));
+        }
+
+        if !self.populations.is_empty() {
+            let populations: Vec<&str> = self
+                .populations
+                .iter()
+                .map(|p| match p {
+                    Population::Pregnancy => "pregnancy[mh]",
+                    // Infant MeSH는 신생아(Infant, Newborn)까지 포함합니다
+                    Population::Infant => "infant[mh]",
+                    Population::Pediatric => "child[mh] OR adolescent[mh]",
+                })
+                .collect();
+            clauses.push(format!("({})", populations.join(" OR ")));
         }
 
-        authors
+        clauses.join(" AND ")
     }
 
-    /// Search for cosmetic/skincare ingredient related papers
-    pub async fn search_ingredient(&self, ingredient: &str, limit: u32) -> Result<Vec<PaperInfo>, String> {
-        let query = format!(
-            "({} OR {} cosmetic OR {} skin OR {} skincare) AND (safety OR efficacy OR benefit)",
-            ingredient, ingredient, ingredient, ingredient
-        );
-        self.search(&query, limit).await
+    fn search_params(&self) -> Vec<(&'static str, String)> {
+        let sort = match self.sort {
+            PaperSort::Relevance => "relevance",
+            PaperSort::Newest => "pub_date",
+        };
+        vec![
+            ("db", "pubmed".to_string()),
+            ("term", self.term()),
+            ("retstart", self.retstart.to_string()),
+            ("retmax", self.retmax.to_string()),
+            ("sort", sort.to_string()),
+        ]
     }
 }
 
@@ -276,3 +402,459 @@ impl Default for PubMedService {
         Self::new()
     }
 }
+
+// ============================================
+// EFetch XML parsing
+// ============================================
+
+/// Element whose text content is being collected (inline markup like <i>/<sup> inside it is flattened)
+enum Capture {
+    Pmid,
+    Title,
+    AbstractText { label: Option<String> },
+    JournalTitle,
+    Year,
+    MedlineDate,
+    Doi,
+    Pmcid,
+    LastName,
+    Initials,
+    CollectiveName,
+    Affiliation,
+    MeshTerm,
+    Keyword,
+    PublicationType,
+}
+
+#[derive(Default)]
+struct ArticleBuilder {
+    pmid: String,
+    title: String,
+    sections: Vec<AbstractSection>,
+    year: Option<String>,
+    journal: Option<String>,
+    doi: Option<String>,
+    pmcid: Option<String>,
+    authors: Vec<String>,
+    affiliations: Vec<String>,
+    mesh_terms: Vec<String>,
+    keywords: Vec<String>,
+    publication_types: Vec<String>,
+}
+
+#[derive(Default)]
+struct AuthorBuilder {
+    last_name: String,
+    initials: String,
+    collective_name: String,
+}
+
+impl AuthorBuilder {
+    /// "LastName Initials", or the group name for collective authors
+    fn display_name(self) -> String {
+        if self.last_name.is_empty() {
+            self.collective_name
+        } else {
+            format!("{} {}", self.last_name, self.initials).trim().to_string()
+        }
+    }
+}
+
+impl ArticleBuilder {
+    fn build(self) -> PaperInfo {
+        let abstract_text = if self.sections.is_empty() {
+            "초록 없음".to_string()
+        } else {
+            self.sections
+                .iter()
+                .map(|s| match &s.label {
+                    Some(label) => format!("{}: {}", label, s.text),
+                    None => s.text.clone(),
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        };
+
+        PaperInfo {
+            pmid: self.pmid,
+            title: if self.title.is_empty() { "제목 없음".to_string() } else { self.title },
+            authors: self.authors,
+            abstract_text,
+            abstract_sections: self.sections,
+            year: self.year.unwrap_or_else(|| "연도 미상".to_string()),
+            journal: self.journal,
+            doi: self.doi,
+            pmcid: self.pmcid,
+            mesh_terms: self.mesh_terms,
+            keywords: self.keywords,
+            publication_types: self.publication_types,
+            country: self.affiliations.first().and_then(|a| affiliation_country(a)),
+        }
+    }
+}
+
+/// Parse an EFetch `PubmedArticleSet` document
+fn parse_pubmed_xml(xml: &str) -> Result<Vec<PaperInfo>, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut papers = Vec::new();
+
+    let mut path: Vec<String> = Vec::new();
+    let mut article: Option<ArticleBuilder> = None;
+    let mut author: Option<AuthorBuilder> = None;
+    // (what, depth of the captured element, collected text)
+    let mut capture: Option<(Capture, usize, String)> = None;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("PubMed XML 파싱 실패 (위치 {}): {}", reader.buffer_position(), e))?;
+
+        match event {
+            Event::Start(e) => {
+                let name = element_name(&e);
+                if capture.is_none() {
+                    match name.as_str() {
+                        "PubmedArticle" => article = Some(ArticleBuilder::default()),
+                        "Author" if parent_is(&path, "AuthorList") => author = Some(AuthorBuilder::default()),
+                        _ => {}
+                    }
+                    capture = capture_for(&name, &e, &path).map(|c| (c, path.len(), String::new()));
+                }
+                path.push(name);
+            }
+            Event::Text(e) => {
+                if let Some((_, _, text)) = capture.as_mut() {
+                    match e.unescape() {
+                        Ok(raw) => text.push_str(&raw),
+                        // 정의되지 않은 엔티티가 있어도 글자는 살립니다
+                        Err(_) => text.push_str(&String::from_utf8_lossy(&e)),
+                    }
+                }
+            }
+            Event::CData(e) => {
+                if let Some((_, _, text)) = capture.as_mut() {
+                    text.push_str(&String::from_utf8_lossy(&e.into_inner()));
+                }
+            }
+            Event::End(_) => {
+                let name = path.pop().unwrap_or_default();
+
+                if capture.as_ref().is_some_and(|(_, depth, _)| *depth == path.len()) {
+                    if let (Some((what, _, text)), Some(article)) = (capture.take(), article.as_mut()) {
+                        store_capture(article, author.as_mut(), what, collapse_whitespace(&text));
+                    }
+                    continue;
+                }
+
+                match name.as_str() {
+                    "Author" => {
+                        if let (Some(a), Some(article)) = (author.take(), article.as_mut()) {
+                            let name = a.display_name();
+                            if !name.is_empty() {
+                                article.authors.push(name);
+                            }
+                        }
+                    }
+                    "PubmedArticle" => {
+                        if let Some(article) = article.take() {
+                            papers.push(article.build());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            // 빈 요소(<AbstractText/> 등)는 담을 내용이 없습니다
+            _ => {}
+        }
+    }
+
+    Ok(papers)
+}
+
+fn element_name(e: &BytesStart) -> String {
+    String::from_utf8_lossy(e.local_name().as_ref()).into_owned()
+}
+
+fn parent_is(path: &[String], name: &str) -> bool {
+    path.last().is_some_and(|p| p == name)
+}
+
+fn attribute(e: &BytesStart, name: &str) -> Option<String> {
+    e.try_get_attribute(name)
+        .ok()
+        .flatten()
+        .and_then(|a| a.unescape_value().ok())
+        .map(|v| v.into_owned())
+}
+
+/// Which capture applies to an element, judged by its parent. Reference lists and
+/// comment/correction entries repeat many of the same tags and are skipped.
+fn capture_for(name: &str, e: &BytesStart, path: &[String]) -> Option<Capture> {
+    if path.iter().any(|p| p == "ReferenceList" || p == "CommentsCorrectionsList") {
+        return None;
+    }
+
+    let capture = match name {
+        "PMID" if parent_is(path, "MedlineCitation") => Capture::Pmid,
+        "ArticleTitle" => Capture::Title,
+        "AbstractText" if parent_is(path, "Abstract") => Capture::AbstractText {
+            label: attribute(e, "Label").or_else(|| attribute(e, "NlmCategory")),
+        },
+        "Title" if parent_is(path, "Journal") => Capture::JournalTitle,
+        "Year" if parent_is(path, "PubDate") => Capture::Year,
+        "MedlineDate" if parent_is(path, "PubDate") => Capture::MedlineDate,
+        "ArticleId" if parent_is(path, "ArticleIdList") && attribute(e, "IdType").as_deref() == Some("doi") => {
+            Capture::Doi
+        }
+        "ArticleId" if parent_is(path, "ArticleIdList") && attribute(e, "IdType").as_deref() == Some("pmc") => {
+            Capture::Pmcid
+        }
+        "ELocationID" if attribute(e, "EIdType").as_deref() == Some("doi") => Capture::Doi,
+        "LastName" if parent_is(path, "Author") => Capture::LastName,
+        "Initials" if parent_is(path, "Author") => Capture::Initials,
+        "CollectiveName" if parent_is(path, "Author") => Capture::CollectiveName,
+        "Affiliation" if parent_is(path, "AffiliationInfo") => Capture::Affiliation,
+        "DescriptorName" if parent_is(path, "MeshHeading") => Capture::MeshTerm,
+        "Keyword" if parent_is(path, "KeywordList") => Capture::Keyword,
+        "PublicationType" if parent_is(path, "PublicationTypeList") => Capture::PublicationType,
+        _ => return None,
+    };
+    Some(capture)
+}
+
+fn store_capture(article: &mut ArticleBuilder, author: Option<&mut AuthorBuilder>, what: Capture, text: String) {
+    if text.is_empty() {
+        return;
+    }
+
+    match what {
+        Capture::Pmid => article.pmid = text,
+        Capture::Title => article.title = text,
+        Capture::AbstractText { label } => article.sections.push(AbstractSection { label, text }),
+        Capture::JournalTitle => article.journal = Some(text),
+        Capture::Year => article.year = Some(text),
+        // "2019 Jan-Feb" 처럼 자유 형식인 날짜는 앞의 연도만 씁니다
+        Capture::MedlineDate => {
+            if article.year.is_none() {
+                article.year = text
+                    .get(..4)
+                    .filter(|y| y.chars().all(|c| c.is_ascii_digit()))
+                    .map(|y| y.to_string());
+            }
+        }
+        // ELocationID와 ArticleIdList 양쪽에 있을 수 있으므로 처음 것만 씁니다
+        Capture::Doi => {
+            if article.doi.is_none() {
+                article.doi = Some(text);
+            }
+        }
+        Capture::Pmcid => article.pmcid = Some(text),
+        Capture::LastName => {
+            if let Some(author) = author {
+                author.last_name = text;
+            }
+        }
+        Capture::Initials => {
+            if let Some(author) = author {
+                author.initials = text;
+            }
+        }
+        Capture::CollectiveName => {
+            if let Some(author) = author {
+                author.collective_name = text;
+            }
+        }
+        Capture::Affiliation => article.affiliations.push(text),
+        Capture::MeshTerm => article.mesh_terms.push(text),
+        Capture::Keyword => article.keywords.push(text),
+        Capture::PublicationType => article.publication_types.push(text),
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Country from the end of an affiliation address ("..., Seoul, Korea. a@b.kr" -> "South Korea")
+fn affiliation_country(affiliation: &str) -> Option<String> {
+    let address = affiliation.split("Electronic address").next().unwrap_or(affiliation);
+    let address: String = address
+        .split_whitespace()
+        .filter(|word| !word.contains('@'))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let last = address
+        .trim()
+        .trim_end_matches(['.', ';', ','])
+        .rsplit([',', ';'])
+        .next()?;
+    // 우편번호가 붙은 경우 ("Seoul 03722") 숫자를 뗍니다
+    let last: String = last
+        .split_whitespace()
+        .filter(|w| !w.chars().any(|c| c.is_ascii_digit()))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let last = last.trim_end_matches('.');
+    let last = last.strip_prefix("the ").or_else(|| last.strip_prefix("The ")).unwrap_or(last);
+
+    let country = match last {
+        "Korea" | "Republic of Korea" | "Korea (South)" | "South Korea" => "South Korea",
+        "USA" | "U.S.A" | "US" | "United States of America" | "United States" => "United States",
+        "UK" | "U.K" | "England" | "Scotland" | "Wales" | "United Kingdom" => "United Kingdom",
+        "P.R. China" | "PR China" | "People's Republic of China" | "China" => "China",
+        "Türkiye" | "Turkey" => "Turkey",
+        "Brasil" | "Brazil" => "Brazil",
+        // 도시나 기관명, 미국 주 약자("MA")로 끝나는 주소는 국가를 알 수 없습니다
+        other => COUNTRIES.iter().copied().find(|c| c.eq_ignore_ascii_case(other))?,
+    };
+    Some(country.to_string())
+}
+
+/// Countries recognized at the end of an affiliation, besides the spellings mapped above
+const COUNTRIES: &[&str] = &[
+    "Argentina", "Australia", "Austria", "Belgium", "Canada", "Chile", "Colombia", "Czech Republic", "Denmark",
+    "Egypt", "Finland", "France", "Germany", "Greece", "Hong Kong", "Hungary", "India", "Indonesia", "Iran",
+    "Ireland", "Israel", "Italy", "Japan", "Malaysia", "Mexico", "Netherlands", "New Zealand", "Nigeria",
+    "Norway", "Pakistan", "Philippines", "Poland", "Portugal", "Romania", "Russia", "Saudi Arabia", "Serbia",
+    "Singapore", "Slovenia", "South Africa", "Spain", "Sweden", "Switzerland", "Taiwan", "Thailand", "Tunisia",
+    "Vietnam",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn ingredient_terms_quote_several_names() {
+        assert_eq!(
+            PubMedQuery::ingredient_names(&names(&["retinol"])).term(),
+            "((retinol OR retinol cosmetic OR retinol skin OR retinol skincare) AND (safety OR efficacy OR benefit))"
+        );
+        assert_eq!(
+            PubMedQuery::ingredient_names(&names(&["Niacinamide", " ", "Nicotinamide"])).term(),
+            "(((\"Niacinamide\" OR \"Nicotinamide\") OR (\"Niacinamide\" OR \"Nicotinamide\") cosmetic \
+             OR (\"Niacinamide\" OR \"Nicotinamide\") skin OR (\"Niacinamide\" OR \"Nicotinamide\") skincare) \
+             AND (safety OR efficacy OR benefit))"
+        );
+    }
+
+    #[test]
+    fn ingredient_terms_drop_quotes_and_blank_names() {
+        let query = PubMedQuery::ingredient_names(&names(&["\"Vitamin C\"", "\"", "Ascorbic \"acid"]));
+        let term = query.term();
+        assert!(term.starts_with("(((\"Vitamin C\" OR \"Ascorbic acid\") OR"), "{term}");
+        assert_eq!(term.matches('"').count() % 2, 0);
+
+        let single = PubMedQuery::ingredient_names(&names(&["", "\"Retinol\""])).term();
+        assert!(single.starts_with("((Retinol OR Retinol cosmetic"), "{single}");
+    }
+
+    #[test]
+    fn term_adds_filters() {
+        let query = PubMedQuery::new(" retinol ")
+            .years(Some(2015), None)
+            .article_types(&[ArticleType::MetaAnalysis, ArticleType::Review])
+            .humans_only(true)
+            .languages(&["English".to_string(), "\"]".to_string()])
+            .populations(&[Population::Pregnancy, Population::Pediatric]);
+        assert_eq!(
+            query.term(),
+            "(retinol) AND (2015:3000[dp]) AND (meta-analysis[pt] OR review[pt]) AND (humans[mh]) \
+             AND (english[la]) AND (pregnancy[mh] OR child[mh] OR adolescent[mh])"
+        );
+        assert_eq!(PubMedQuery::new("retinol").years(None, Some(2020)).term(), "(retinol) AND (1800:2020[dp])");
+    }
+
+    const EFETCH: &str = include_str!("../../tests/fixtures/pubmed_efetch.xml");
+
+    fn papers() -> Vec<PaperInfo> {
+        parse_pubmed_xml(EFETCH).unwrap()
+    }
+
+    #[test]
+    fn parses_every_article() {
+        let papers = papers();
+        assert_eq!(papers.iter().map(|p| p.pmid.as_str()).collect::<Vec<_>>(), ["38000001", "38000002"]);
+
+        let paper = &papers[0];
+        // 제목 안의 <i> 같은 표시는 글자만 남기고 엔티티는 풉니다
+        assert_eq!(paper.title, "Topical niacinamide reduces in vivo hyperpigmentation & sebum: a randomized trial.");
+        assert_eq!(paper.journal.as_deref(), Some("Journal of cosmetic dermatology"));
+        assert_eq!(paper.mesh_terms, ["Niacinamide", "Hyperpigmentation"]);
+        assert_eq!(paper.keywords, ["melasma", "skin barrier"]);
+        assert_eq!(paper.publication_types, ["Randomized Controlled Trial", "Journal Article"]);
+
+        assert_eq!(papers[1].title, "Stability of retinol in O/W emulsions");
+        assert_eq!(papers[1].journal.as_deref(), Some("Cosmetics"));
+    }
+
+    #[test]
+    fn parses_authors_including_groups() {
+        let papers = papers();
+        assert_eq!(papers[0].authors, ["Kim J", "Smith AB", "Niacinamide Study Group"]);
+        assert_eq!(papers[1].authors, ["Müller H"]);
+    }
+
+    #[test]
+    fn keeps_labelled_abstract_sections() {
+        let paper = &papers()[0];
+        let sections: Vec<(Option<&str>, &str)> =
+            paper.abstract_sections.iter().map(|s| (s.label.as_deref(), s.text.as_str())).collect();
+        assert_eq!(
+            sections,
+            [
+                (Some("BACKGROUND"), "Niacinamide is widely used in cosmetic formulations."),
+                (Some("METHODS"), "Forty subjects applied 5% niacinamide for 8 weeks."),
+                (Some("RESULTS"), "Melanin index fell by 12 m2 units (p < 0.05)."),
+            ]
+        );
+        assert!(paper.abstract_text.starts_with("BACKGROUND: Niacinamide is widely used"));
+        assert!(paper.abstract_text.contains("\n\nMETHODS: Forty subjects"));
+
+        assert!(papers()[1].abstract_sections.is_empty());
+        assert_eq!(papers()[1].abstract_text, "초록 없음");
+    }
+
+    #[test]
+    fn reads_dates_and_ids_but_not_those_of_references() {
+        let papers = papers();
+        assert_eq!(papers[0].year, "2023");
+        assert_eq!(papers[0].doi.as_deref(), Some("10.5555/jocd.2023.0417"));
+        assert_eq!(papers[0].pmcid.as_deref(), Some("PMC10000001"));
+
+        assert_eq!(papers[1].year, "2019");
+        assert_eq!(papers[1].doi, None);
+        assert_eq!(papers[1].pmcid, None);
+    }
+
+    #[test]
+    fn takes_the_country_from_the_first_affiliation() {
+        let papers = papers();
+        assert_eq!(papers[0].country.as_deref(), Some("South Korea"));
+        assert_eq!(papers[1].country.as_deref(), Some("Germany"));
+    }
+
+    #[test]
+    fn recognizes_only_known_countries() {
+        let cases = [
+            ("Dept. of Chemistry, Tokyo, Japan.", Some("Japan")),
+            ("Skin Research Institute, Boston, MA, USA. Electronic address: a@b.org.", Some("United States")),
+            ("School of Pharmacy, Shanghai 201203, P.R. China", Some("China")),
+            ("Unilever R&D, Port Sunlight, Wirral, UK; b@c.com", Some("United Kingdom")),
+            ("Leiden University, Leiden, the Netherlands.", Some("Netherlands")),
+            ("Department of Dermatology, Boston, MA.", None),
+            ("Institute of Cosmetic Science", None),
+            ("", None),
+        ];
+        for (affiliation, country) in cases {
+            assert_eq!(affiliation_country(affiliation).as_deref(), country, "{affiliation}");
+        }
+    }
+}
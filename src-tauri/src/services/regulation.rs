@@ -0,0 +1,173 @@
+// Regulatory status of ingredients: MFDS prohibited/restricted lists, EU Cosmetics Regulation
+// annexes and pregnancy/infant caution lists. Every fact carries the source it is cited from.
+
+use super::dataset::{self, ActiveDataset, CitesSource, Dataset, Source, Sources};
+use super::ingredients::IngredientDictionary;
+use crate::models::{RegulatoryDataset, RegulatoryFact, RegulatoryRule, RegulatorySource};
+use std::sync::Arc;
+
+pub struct RegulatoryDatabase {
+    sources: Sources<RegulatorySource>,
+    rules: Vec<RegulatoryRule>,
+}
+
+impl RegulatoryDatabase {
+    pub fn new(dataset: RegulatoryDataset) -> Self {
+        Self {
+            sources: Sources::new(dataset.sources),
+            rules: dataset.rules,
+        }
+    }
+
+    /// Everything the lists say about an ingredient, prohibitions first
+    pub fn facts(&self, ingredient_id: &str) -> Vec<RegulatoryFact> {
+        let mut facts: Vec<RegulatoryFact> = self
+            .rules
+            .iter()
+            .filter(|rule| rule.ingredient_id == ingredient_id)
+            .filter_map(|rule| {
+                let source = self.sources.cited_by(rule)?;
+                Some(RegulatoryFact {
+                    list: rule.list,
+                    status: rule.status,
+                    max_concentration: rule.max_concentration.clone(),
+                    conditions: rule.conditions.clone(),
+                    reference: rule.reference.clone(),
+                    source: source.clone(),
+                })
+            })
+            .collect();
+        facts.sort_by_key(|f| f.status);
+        facts
+    }
+}
+
+/// Sources are replaced by id. Incoming rules replace every existing rule of the same
+/// ingredient and list, so re-importing an updated list does not leave stale limits behind.
+impl Dataset for RegulatoryDataset {
+    type Index = RegulatoryDatabase;
+
+    const SEED: &'static str = include_str!("../../data/regulations.json");
+    const LABEL: &'static str = "규제 데이터";
+
+    fn parse(json: &str) -> Result<Self, String> {
+        parse_dataset(json)
+    }
+
+    fn merge(&mut self, incoming: Self) {
+        dataset::merge_sources(&mut self.sources, incoming.sources);
+        self.rules.retain(|rule| {
+            !incoming
+                .rules
+                .iter()
+                .any(|r| r.ingredient_id == rule.ingredient_id && r.list == rule.list)
+        });
+        self.rules.extend(incoming.rules);
+    }
+
+    fn build(self) -> RegulatoryDatabase {
+        RegulatoryDatabase::new(self)
+    }
+}
+
+impl Source for RegulatorySource {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl CitesSource for RegulatoryRule {
+    fn source_id(&self) -> &str {
+        &self.source_id
+    }
+
+    fn subject(&self) -> String {
+        self.ingredient_id.clone()
+    }
+}
+
+pub fn parse_dataset(json: &str) -> Result<RegulatoryDataset, String> {
+    serde_json::from_str(json).map_err(|e| format!("규제 데이터 형식이 올바르지 않습니다: {}", e))
+}
+
+/// Point every rule at a dictionary id. Rules naming an ingredient the dictionary does not know
+/// exactly are dropped; their names are returned.
+pub fn link_ingredients(dataset: &mut RegulatoryDataset, dictionary: &IngredientDictionary) -> Vec<String> {
+    let mut unmatched: Vec<String> = Vec::new();
+    dataset.rules.retain_mut(|rule| {
+        let known = dictionary.entries().iter().any(|i| i.id == rule.ingredient_id);
+        if known {
+            return true;
+        }
+        match dictionary.get_by_name(&rule.ingredient_id) {
+            Some(ingredient) => {
+                rule.ingredient_id = ingredient.id.clone();
+                true
+            }
+            None => {
+                if !unmatched.contains(&rule.ingredient_id) {
+                    unmatched.push(rule.ingredient_id.clone());
+                }
+                false
+            }
+        }
+    });
+    unmatched
+}
+
+// ============================================
+// Active database
+// ============================================
+
+static ACTIVE: ActiveDataset<RegulatoryDataset> = ActiveDataset::new();
+
+/// The bundled data plus the user's imports once they are loaded or installed
+pub fn active() -> &'static ActiveDataset<RegulatoryDataset> {
+    &ACTIVE
+}
+
+pub fn database() -> Arc<RegulatoryDatabase> {
+    ACTIVE.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::RegulatoryList;
+    use crate::services::ingredients;
+
+    #[test]
+    fn seed_rules_cite_known_sources_and_dictionary_ids() {
+        let mut dataset = parse_dataset(RegulatoryDataset::SEED).unwrap();
+        assert!(dataset::check_sources(&dataset.rules, &dataset.sources, &[]).is_ok());
+
+        let rules = dataset.rules.len();
+        let unmatched = link_ingredients(&mut dataset, &ingredients::dictionary());
+        assert!(unmatched.is_empty(), "{unmatched:?}");
+        assert_eq!(dataset.rules.len(), rules);
+    }
+
+    #[test]
+    fn reports_vitamin_a_and_arbutin_limits_from_the_2024_amendment() {
+        let database = database();
+        for id in ["retinol", "retinyl-acetate", "retinyl-palmitate", "alpha-arbutin", "arbutin"] {
+            let facts = database.facts(id);
+            assert!(
+                facts.iter().any(|f| f.list == RegulatoryList::EuAnnexIii && f.source.id == "eu-2024-996"),
+                "{id}"
+            );
+        }
+    }
+
+    #[test]
+    fn drops_facts_without_a_source() {
+        let dataset = parse_dataset(
+            r#"{"sources": [], "rules": [
+                {"ingredient_id": "retinol", "list": "eu_annex_iii", "status": "restricted", "source_id": "missing"}
+            ]}"#,
+        )
+        .unwrap();
+        assert!(dataset::check_sources(&dataset.rules, &dataset.sources, &[]).is_err());
+        assert!(RegulatoryDatabase::new(dataset).facts("retinol").is_empty());
+    }
+}
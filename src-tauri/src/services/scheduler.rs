@@ -0,0 +1,285 @@
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::time::Instant;
+
+/// Upstream APIs that get their own concurrency and rate limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Provider {
+    OpenAI,
+    Anthropic,
+    Google,
+    /// Search-box autocomplete; an unofficial endpoint, so kept apart from the Gemini API and gentle
+    Autocomplete,
+    /// NCBI E-utilities without an API key (3 req/s)
+    Ncbi,
+    /// NCBI E-utilities with an API key (10 req/s)
+    NcbiWithKey,
+    CrossRef,
+    OpenCitations,
+    Unpaywall,
+    News,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Limits {
+    max_concurrent: usize,
+    requests_per_second: f64,
+    burst: f64,
+}
+
+impl Provider {
+    fn limits(self) -> Limits {
+        let (max_concurrent, requests_per_second, burst) = match self {
+            Provider::OpenAI => (4, 5.0, 5.0),
+            Provider::Anthropic => (4, 4.0, 4.0),
+            Provider::Google => (4, 5.0, 5.0),
+            Provider::Autocomplete => (2, 2.0, 2.0),
+            // NCBI counts requests per rolling second, so never burst
+            Provider::Ncbi => (3, 3.0, 1.0),
+            Provider::NcbiWithKey => (10, 10.0, 1.0),
+            Provider::CrossRef => (5, 10.0, 5.0),
+            Provider::OpenCitations => (3, 3.0, 3.0),
+            Provider::Unpaywall => (5, 10.0, 5.0),
+            Provider::News => (2, 2.0, 2.0),
+        };
+        Limits {
+            max_concurrent,
+            requests_per_second,
+            burst,
+        }
+    }
+}
+
+const MAX_RETRIES: u32 = 4;
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(120);
+
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+    /// Set from Retry-After so every queued request of the provider waits, not only the one that got 429
+    blocked_until: Option<Instant>,
+}
+
+/// Per-provider concurrency limit plus token bucket
+struct ProviderGate {
+    limits: Limits,
+    permits: Semaphore,
+    bucket: Mutex<BucketState>,
+}
+
+impl ProviderGate {
+    fn new(limits: Limits) -> Self {
+        Self {
+            limits,
+            permits: Semaphore::new(limits.max_concurrent),
+            bucket: Mutex::new(BucketState {
+                tokens: limits.burst,
+                last_refill: Instant::now(),
+                blocked_until: None,
+            }),
+        }
+    }
+
+    /// Wait until the bucket has a token (and any Retry-After cooldown is over), then take it
+    async fn take_token(&self) {
+        loop {
+            let wait = {
+                let mut state = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+                let now = Instant::now();
+
+                match state.blocked_until {
+                    Some(until) if until > now => until - now,
+                    _ => {
+                        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+                        state.tokens = (state.tokens + elapsed * self.limits.requests_per_second).min(self.limits.burst);
+                        state.last_refill = now;
+
+                        if state.tokens >= 1.0 {
+                            state.tokens -= 1.0;
+                            return;
+                        }
+                        Duration::from_secs_f64((1.0 - state.tokens) / self.limits.requests_per_second)
+                    }
+                }
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    fn block_for(&self, delay: Duration) {
+        let mut state = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+        let until = Instant::now() + delay;
+        if state.blocked_until.is_none_or(|current| current < until) {
+            state.blocked_until = Some(until);
+        }
+    }
+}
+
+fn gate(provider: Provider) -> Arc<ProviderGate> {
+    static GATES: OnceLock<Mutex<HashMap<Provider, Arc<ProviderGate>>>> = OnceLock::new();
+    let gates = GATES.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut gates = gates.lock().unwrap_or_else(|e| e.into_inner());
+    gates
+        .entry(provider)
+        .or_insert_with(|| Arc::new(ProviderGate::new(provider.limits())))
+        .clone()
+}
+
+/// Shared HTTP client so every service reuses one connection pool
+pub fn http_client() -> Client {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    CLIENT
+        .get_or_init(|| {
+            // 스트리밍 응답도 같은 클라이언트를 쓰므로 전체 시간 대신 읽기 사이의 대기 시간만 제한합니다.
+            // 이미지 생성처럼 첫 바이트까지 오래 걸리는 요청도 통과할 만큼 넉넉하게 둡니다.
+            Client::builder()
+                .connect_timeout(Duration::from_secs(15))
+                .read_timeout(Duration::from_secs(180))
+                .build()
+                .unwrap_or_default()
+        })
+        .clone()
+}
+
+/// Send a request through the provider's limits, retrying rate-limit/overload responses and transient network errors.
+/// After the last retry the final response is returned as-is so callers report the upstream error.
+pub async fn send(provider: Provider, request: RequestBuilder) -> Result<Response, reqwest::Error> {
+    let gate = gate(provider);
+    let mut attempt = 0;
+
+    loop {
+        // Bodies that cannot be cloned (streams) only get a single attempt
+        let Some(current) = request.try_clone() else {
+            let _permit = gate.permits.acquire().await;
+            gate.take_token().await;
+            return request.send().await;
+        };
+
+        let result = {
+            let _permit = gate.permits.acquire().await;
+            gate.take_token().await;
+            current.send().await
+        };
+
+        if attempt >= MAX_RETRIES {
+            return result;
+        }
+
+        let delay = match &result {
+            Ok(response) if is_retryable_status(response.status()) => {
+                let delay = retry_after(response).unwrap_or_else(|| backoff(attempt));
+                gate.block_for(delay);
+                delay
+            }
+            Err(e) if e.is_timeout() || e.is_connect() => backoff(attempt),
+            _ => return result,
+        };
+
+        eprintln!(
+            "{:?} 요청 재시도 {}/{} ({}ms 후)",
+            provider,
+            attempt + 1,
+            MAX_RETRIES,
+            delay.as_millis()
+        );
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// 429/503, plus Anthropic's non-standard 529 "overloaded"
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE || status.as_u16() == 529
+}
+
+/// Retry-After as delta-seconds or an HTTP date
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    parse_retry_after(value, chrono::Utc::now())
+}
+
+/// A Retry-After header value, capped at `MAX_RETRY_AFTER`; unusable values fall back to backoff
+fn parse_retry_after(value: &str, now: chrono::DateTime<chrono::Utc>) -> Option<Duration> {
+    let value = value.trim();
+
+    let delay = if let Ok(seconds) = value.parse::<f64>() {
+        // "inf", "NaN", 1e400 같은 값은 Duration으로 바꿀 수 없습니다
+        if !seconds.is_finite() {
+            return None;
+        }
+        Duration::from_secs_f64(seconds.clamp(0.0, MAX_RETRY_AFTER.as_secs_f64()))
+    } else {
+        let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+        (date.with_timezone(&chrono::Utc) - now).to_std().unwrap_or(Duration::ZERO)
+    };
+
+    Some(delay.min(MAX_RETRY_AFTER))
+}
+
+/// Exponential backoff with jitter: half the window fixed, half random
+fn backoff(attempt: u32) -> Duration {
+    let window = BASE_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_BACKOFF);
+    let half = window / 2;
+    half + half.mul_f64(fastrand::f64())
+}
+
+/// `.send_scheduled(provider)` in place of `.send()` on a request builder
+pub trait SendScheduled {
+    fn send_scheduled(self, provider: Provider) -> impl Future<Output = Result<Response, reqwest::Error>> + Send;
+}
+
+impl SendScheduled for RequestBuilder {
+    fn send_scheduled(self, provider: Provider) -> impl Future<Output = Result<Response, reqwest::Error>> + Send {
+        send(provider, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn now() -> chrono::DateTime<chrono::Utc> {
+        chrono::Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_delta_seconds() {
+        assert_eq!(parse_retry_after("5", now()), Some(Duration::from_secs(5)));
+        assert_eq!(parse_retry_after(" 1.5 ", now()), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_retry_after("-3", now()), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn caps_large_values() {
+        assert_eq!(parse_retry_after("100000", now()), Some(MAX_RETRY_AFTER));
+        assert_eq!(parse_retry_after("1e300", now()), Some(MAX_RETRY_AFTER));
+    }
+
+    #[test]
+    fn rejects_non_finite_values() {
+        for value in ["1e400", "inf", "-inf", "infinity", "NaN"] {
+            assert_eq!(parse_retry_after(value, now()), None, "{value}");
+        }
+    }
+
+    #[test]
+    fn parses_http_dates() {
+        assert_eq!(
+            parse_retry_after("Sat, 01 Mar 2025 12:00:30 GMT", now()),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(parse_retry_after("Sat, 01 Mar 2025 11:00:00 GMT", now()), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("Sun, 02 Mar 2025 12:00:00 GMT", now()), Some(MAX_RETRY_AFTER));
+        assert_eq!(parse_retry_after("tomorrow", now()), None);
+    }
+}
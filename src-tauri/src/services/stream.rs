@@ -0,0 +1,280 @@
+use reqwest::Response;
+
+/// Callback that receives each partial token of a streamed reply
+pub type OnToken<'a> = dyn FnMut(&str) + Send + 'a;
+
+/// Read a `text/event-stream` body and hand each event's name and data to `handle`.
+/// Stops early when `handle` returns `Ok(false)` (e.g. OpenAI's `[DONE]`).
+pub async fn read_sse<F>(mut response: Response, mut handle: F) -> Result<(), String>
+where
+    F: FnMut(&str, &str) -> Result<bool, String>,
+{
+    let mut parser = SseParser::default();
+
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("스트림 읽기 실패: {}", e))?
+    {
+        if !parser.push(&chunk, &mut handle)? {
+            return Ok(());
+        }
+    }
+
+    parser.finish(&mut handle)
+}
+
+/// Splits an event stream into events however its bytes are chunked
+#[derive(Default)]
+struct SseParser {
+    // 멀티바이트 문자가 청크 경계에서 잘릴 수 있어 바이트 단위로 모읍니다
+    pending: Vec<u8>,
+    event: String,
+    data: String,
+}
+
+impl SseParser {
+    /// Feed the next chunk; returns false once `handle` asked to stop
+    fn push<F>(&mut self, chunk: &[u8], handle: &mut F) -> Result<bool, String>
+    where
+        F: FnMut(&str, &str) -> Result<bool, String>,
+    {
+        self.pending.extend_from_slice(chunk);
+
+        while let Some(newline) = self.pending.iter().position(|b| *b == b'\n') {
+            let raw: Vec<u8> = self.pending.drain(..=newline).collect();
+            if !self.line(&String::from_utf8_lossy(&raw), handle)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// End of the body: the last line and event may not be terminated
+    fn finish<F>(mut self, handle: &mut F) -> Result<(), String>
+    where
+        F: FnMut(&str, &str) -> Result<bool, String>,
+    {
+        let rest = std::mem::take(&mut self.pending);
+        if !rest.is_empty() && !self.line(&String::from_utf8_lossy(&rest), handle)? {
+            return Ok(());
+        }
+        // 마지막 이벤트 뒤에 빈 줄이 없는 경우
+        if !self.data.is_empty() {
+            handle(&self.event, &self.data)?;
+        }
+        Ok(())
+    }
+
+    fn line<F>(&mut self, line: &str, handle: &mut F) -> Result<bool, String>
+    where
+        F: FnMut(&str, &str) -> Result<bool, String>,
+    {
+        let line = line.trim_end_matches(['\n', '\r']);
+
+        if line.is_empty() {
+            let proceed = self.data.is_empty() || handle(&self.event, &self.data)?;
+            self.event.clear();
+            self.data.clear();
+            return Ok(proceed);
+        }
+
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => self.event = value.to_string(),
+            "data" => {
+                if !self.data.is_empty() {
+                    self.data.push('\n');
+                }
+                self.data.push_str(value);
+            }
+            // 주석(":" 로 시작)과 id/retry 필드는 무시
+            _ => {}
+        }
+        Ok(true)
+    }
+}
+
+/// Incrementally picks complete objects out of the first JSON array in a streamed reply,
+/// so `[{..}, {..}]` and `{"items": [{..}, {..}]}` both yield each item as soon as its `}` arrives.
+#[derive(Default)]
+pub struct ArrayItemScanner {
+    buffer: String,
+    scanned: usize,
+    stack: Vec<char>,
+    in_string: bool,
+    escaped: bool,
+    item_start: Option<usize>,
+}
+
+impl ArrayItemScanner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed the next chunk; returns the JSON text of every item completed by it
+    pub fn push(&mut self, chunk: &str) -> Vec<String> {
+        self.buffer.push_str(chunk);
+        let mut items = Vec::new();
+
+        for (i, c) in self.buffer[self.scanned..].char_indices() {
+            let i = i + self.scanned;
+
+            if self.in_string {
+                match c {
+                    _ if self.escaped => self.escaped = false,
+                    '\\' => self.escaped = true,
+                    '"' => self.in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+
+            match c {
+                '"' => self.in_string = true,
+                '{' | '[' => {
+                    if c == '{' && self.item_start.is_none() && self.in_first_array() {
+                        self.item_start = Some(i);
+                    }
+                    self.stack.push(c);
+                }
+                '}' | ']' => {
+                    self.stack.pop();
+                    if c == '}' && self.in_first_array() {
+                        if let Some(start) = self.item_start.take() {
+                            items.push(self.buffer[start..=i].to_string());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // 완성된 항목 앞부분은 더 필요 없으므로 버립니다
+        let keep_from = self.item_start.unwrap_or(self.buffer.len());
+        self.buffer.drain(..keep_from);
+        self.item_start = self.item_start.map(|_| 0);
+        self.scanned = self.buffer.len();
+
+        items
+    }
+
+    /// True while the innermost open container is the outermost array
+    fn in_first_array(&self) -> bool {
+        self.stack.last() == Some(&'[') && self.stack.iter().filter(|c| **c == '[').count() == 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// Feed `chunks` to a parser and collect the (event, data) pairs it hands out
+    fn events(chunks: &[&[u8]]) -> Vec<(String, String)> {
+        let mut events = Vec::new();
+        let mut handle = |event: &str, data: &str| {
+            events.push((event.to_string(), data.to_string()));
+            Ok(data != "[DONE]")
+        };
+        let mut parser = SseParser::default();
+        for chunk in chunks {
+            if !parser.push(chunk, &mut handle).unwrap() {
+                return events;
+            }
+        }
+        parser.finish(&mut handle).unwrap();
+        events
+    }
+
+    fn pair(event: &str, data: &str) -> (String, String) {
+        (event.to_string(), data.to_string())
+    }
+
+    #[test]
+    fn joins_a_character_split_across_chunks() {
+        let body = "data: 연구일지\n\n".as_bytes();
+        // "연" is three bytes starting at 6; split inside it
+        assert_eq!(events(&[&body[..7], &body[7..]]), [pair("", "연구일지")]);
+    }
+
+    #[test]
+    fn reads_event_names_multi_line_data_and_crlf() {
+        let body = b": keep-alive\r\nevent: delta\r\ndata: a\r\ndata: b\r\nid: 1\r\n\r\ndata:c\n\n";
+        assert_eq!(events(&[body]), [pair("delta", "a\nb"), pair("", "c")]);
+    }
+
+    #[test]
+    fn stops_when_the_handler_says_so() {
+        let body = b"data: a\n\ndata: [DONE]\n\ndata: never\n\n";
+        assert_eq!(events(&[body]), [pair("", "a"), pair("", "[DONE]")]);
+    }
+
+    #[test]
+    fn delivers_a_final_event_without_a_trailing_blank_line() {
+        assert_eq!(events(&[b"data: a\n\ndata: b\n"]), [pair("", "a"), pair("", "b")]);
+        assert_eq!(events(&[b"event: end\ndata: b"]), [pair("end", "b")]);
+        assert_eq!(events(&[b"data: a\n\n", b""]), [pair("", "a")]);
+    }
+
+    #[tokio::test]
+    async fn reads_a_response_body() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("event: x\ndata: 하나\n\ndata: 둘"))
+            .mount(&server)
+            .await;
+        let response = reqwest::get(server.uri()).await.unwrap();
+
+        let mut seen = Vec::new();
+        read_sse(response, |event, data| {
+            seen.push(pair(event, data));
+            Ok(true)
+        })
+        .await
+        .unwrap();
+        assert_eq!(seen, [pair("x", "하나"), pair("", "둘")]);
+    }
+
+    fn scan(chunks: &[&str]) -> Vec<String> {
+        let mut scanner = ArrayItemScanner::new();
+        chunks.iter().flat_map(|chunk| scanner.push(chunk)).collect()
+    }
+
+    #[test]
+    fn yields_items_split_across_chunks_once_complete() {
+        let mut scanner = ArrayItemScanner::new();
+        assert!(scanner.push(r#"[{"title": "가"#).is_empty());
+        assert_eq!(scanner.push(r#"나"}, {"title""#), [r#"{"title": "가나"}"#]);
+        assert_eq!(scanner.push(r#": "다"}]"#), [r#"{"title": "다"}"#]);
+    }
+
+    #[test]
+    fn yields_the_same_items_however_the_reply_is_chunked() {
+        let reply = r#"{"items": [{"a": 1, "b": {"c": [1, 2]}}, {"a": 2}]}"#;
+        let expected = [r#"{"a": 1, "b": {"c": [1, 2]}}"#, r#"{"a": 2}"#];
+        assert_eq!(scan(&[reply]), expected);
+
+        let chars: Vec<String> = reply.chars().map(String::from).collect();
+        let chars: Vec<&str> = chars.iter().map(String::as_str).collect();
+        assert_eq!(scan(&chars), expected);
+    }
+
+    #[test]
+    fn ignores_brackets_and_quotes_inside_strings() {
+        let reply = r#"[{"t": "} ] { [", "q": "말했다 \"}\" 끝"}, {"t": "\\"}]"#;
+        assert_eq!(
+            scan(&[reply]),
+            [r#"{"t": "} ] { [", "q": "말했다 \"}\" 끝"}"#, r#"{"t": "\\"}"#]
+        );
+    }
+
+    #[test]
+    fn reads_the_items_wrapper_and_a_bare_array() {
+        assert_eq!(scan(&[r#"```json\n{"items":[{"a":1}]}\n```"#]), [r#"{"a":1}"#]);
+        assert_eq!(scan(&[r#"[{"a":1},{"a":2}]"#]), [r#"{"a":1}"#, r#"{"a":2}"#]);
+        assert!(scan(&[r#"{"a": {"b": 1}}"#]).is_empty());
+    }
+}
@@ -0,0 +1,351 @@
+use crate::error::AppError;
+use crate::services::llm::{extract_json_value, LlmProvider};
+use crate::services::stream::OnToken;
+use schemars::generate::SchemaSettings;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+// 복구 요청에 다시 보낼 이전 응답의 최대 길이
+const MAX_ECHOED_RESPONSE: usize = 6000;
+
+/// JSON schema of the expected reply, handed to the provider's native structured-output mode
+pub struct OutputSchema {
+    /// Identifier used as the OpenAI schema name / Anthropic tool name
+    pub name: String,
+    pub schema: Value,
+}
+
+impl OutputSchema {
+    /// Schema generated from the Rust type, with sub-schemas inlined (Gemini and tool schemas reject `$ref`)
+    pub fn for_type<T: JsonSchema>() -> Self {
+        let mut schema = SchemaSettings::draft2020_12()
+            .with(|s| s.inline_subschemas = true)
+            .into_generator()
+            .into_root_schema_for::<T>()
+            .to_value();
+
+        if let Some(object) = schema.as_object_mut() {
+            object.remove("$schema");
+        }
+
+        let name: String = T::schema_name()
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+            .collect();
+
+        Self {
+            name: if name.is_empty() { "response".to_string() } else { name },
+            schema,
+        }
+    }
+}
+
+/// Generate a `T` from the model: native structured output, schema validation, then at most one
+/// repair round-trip. Never substitutes placeholder content when the model's output is unusable.
+pub async fn generate<T>(llm: &dyn LlmProvider, prompt: &str, system_prompt: Option<&str>) -> Result<T, AppError>
+where
+    T: DeserializeOwned + JsonSchema,
+{
+    run(llm, prompt, system_prompt, None).await
+}
+
+/// `generate` with the first attempt streamed to `on_token`; a repair round-trip, if needed, is not streamed
+pub async fn generate_streaming<T>(
+    llm: &dyn LlmProvider,
+    prompt: &str,
+    system_prompt: Option<&str>,
+    on_token: &mut OnToken<'_>,
+) -> Result<T, AppError>
+where
+    T: DeserializeOwned + JsonSchema,
+{
+    run(llm, prompt, system_prompt, Some(on_token)).await
+}
+
+async fn run<T>(
+    llm: &dyn LlmProvider,
+    prompt: &str,
+    system_prompt: Option<&str>,
+    on_token: Option<&mut OnToken<'_>>,
+) -> Result<T, AppError>
+where
+    T: DeserializeOwned + JsonSchema,
+{
+    let schema = OutputSchema::for_type::<T>();
+
+    let raw = match on_token {
+        Some(on_token) => llm.stream_structured(prompt, system_prompt, &schema, on_token).await,
+        None => llm.generate_structured(prompt, system_prompt, &schema).await,
+    }
+    .map_err(AppError::ApiError)?;
+
+    let problems = match parse_and_validate::<T>(&schema, &raw) {
+        Ok(value) => return Ok(value),
+        Err(problems) => problems,
+    };
+    eprintln!("구조화 응답 검증 실패, 복구 요청: {}", problems.join("; "));
+
+    let repair_prompt = repair_prompt(prompt, &problems, &raw);
+
+    let repaired = llm
+        .generate_structured(&repair_prompt, system_prompt, &schema)
+        .await
+        .map_err(AppError::ApiError)?;
+
+    parse_and_validate::<T>(&schema, &repaired).map_err(|problems| AppError::InvalidLlmOutput(problems.join("; ")))
+}
+
+/// `generate` for a reply about an image. Vision requests have no native structured-output mode, so the
+/// schema is described in the system prompt, and the repair round-trip sends the image again.
+pub async fn analyze_image<T>(
+    llm: &dyn LlmProvider,
+    base64_image: &str,
+    mime_type: &str,
+    system_prompt: &str,
+    user_prompt: &str,
+) -> Result<T, AppError>
+where
+    T: DeserializeOwned + JsonSchema,
+{
+    let schema = OutputSchema::for_type::<T>();
+    let system_prompt = format!(
+        "{}\n\n다음 JSON 스키마에 맞는 JSON만 출력하세요:\n{}",
+        system_prompt, schema.schema
+    );
+
+    let raw = llm
+        .analyze_image(base64_image, mime_type, &system_prompt, user_prompt)
+        .await
+        .map_err(AppError::ApiError)?;
+
+    let problems = match parse_and_validate::<T>(&schema, &raw) {
+        Ok(value) => return Ok(value),
+        Err(problems) => problems,
+    };
+    eprintln!("이미지 분석 응답 검증 실패, 복구 요청: {}", problems.join("; "));
+
+    let repaired = llm
+        .analyze_image(base64_image, mime_type, &system_prompt, &repair_prompt(user_prompt, &problems, &raw))
+        .await
+        .map_err(AppError::ApiError)?;
+
+    parse_and_validate::<T>(&schema, &repaired).map_err(|problems| AppError::InvalidLlmOutput(problems.join("; ")))
+}
+
+fn repair_prompt(prompt: &str, problems: &[String], raw: &str) -> String {
+    format!(
+        "{}\n\n---\n이전 응답이 요구된 JSON 스키마와 맞지 않았습니다.\n문제:\n- {}\n\n이전 응답:\n{}\n\n\
+         문제를 고쳐 스키마에 맞는 JSON만 다시 출력하세요. 근거가 없는 내용은 지어내지 말고 비워 두세요.",
+        prompt,
+        problems.join("\n- "),
+        truncate(raw, MAX_ECHOED_RESPONSE)
+    )
+}
+
+fn parse_and_validate<T: DeserializeOwned>(schema: &OutputSchema, raw: &str) -> Result<T, Vec<String>> {
+    let value = extract_json_value(raw).map_err(|e| vec![e])?;
+
+    let mut problems = Vec::new();
+    validate(&schema.schema, &value, "$", &mut problems);
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+
+    serde_json::from_value(value).map_err(|e| vec![e.to_string()])
+}
+
+/// Check `value` against the JSON Schema subset that schemars emits for plain structs
+fn validate(schema: &Value, value: &Value, path: &str, problems: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        // `true` accepts anything, `false` nothing
+        if schema == &Value::Bool(false) {
+            problems.push(format!("{}: 허용되지 않는 값입니다", path));
+        }
+        return;
+    };
+
+    if let Some(types) = schema.get("type") {
+        let allowed: Vec<&str> = match types {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(list) => list.iter().filter_map(|t| t.as_str()).collect(),
+            _ => vec![],
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|t| matches_type(t, value)) {
+            problems.push(format!("{}: {} 타입이어야 합니다", path, allowed.join(" 또는 ")));
+            return;
+        }
+    }
+
+    if let Some(options) = schema.get("enum").and_then(|e| e.as_array()) {
+        if !options.contains(value) {
+            problems.push(format!("{}: 허용된 값이 아닙니다 ({})", path, value));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            problems.push(format!("{}: {} 이어야 합니다", path, expected));
+        }
+    }
+
+    for key in ["anyOf", "oneOf"] {
+        if let Some(variants) = schema.get(key).and_then(|v| v.as_array()) {
+            let matched = variants.iter().any(|variant| {
+                let mut sub = Vec::new();
+                validate(variant, value, path, &mut sub);
+                sub.is_empty()
+            });
+            if !matched {
+                problems.push(format!("{}: 허용된 형식 중 어느 것과도 맞지 않습니다", path));
+            }
+        }
+    }
+
+    match value {
+        Value::Number(n) => {
+            let n = n.as_f64().unwrap_or_default();
+            if let Some(min) = schema.get("minimum").and_then(|m| m.as_f64()) {
+                if n < min {
+                    problems.push(format!("{}: {} 이상이어야 합니다", path, min));
+                }
+            }
+            if let Some(max) = schema.get("maximum").and_then(|m| m.as_f64()) {
+                if n > max {
+                    problems.push(format!("{}: {} 이하여야 합니다", path, max));
+                }
+            }
+        }
+        Value::String(s) => {
+            let len = s.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(|m| m.as_u64()) {
+                if len < min {
+                    problems.push(format!("{}: 최소 {}자 이상이어야 합니다", path, min));
+                }
+            }
+            if let Some(max) = schema.get("maxLength").and_then(|m| m.as_u64()) {
+                if len > max {
+                    problems.push(format!("{}: 최대 {}자까지 가능합니다", path, max));
+                }
+            }
+        }
+        Value::Array(items) => {
+            let len = items.len() as u64;
+            if let Some(min) = schema.get("minItems").and_then(|m| m.as_u64()) {
+                if len < min {
+                    problems.push(format!("{}: 항목이 {}개 이상이어야 합니다", path, min));
+                }
+            }
+            if let Some(max) = schema.get("maxItems").and_then(|m| m.as_u64()) {
+                if len > max {
+                    problems.push(format!("{}: 항목은 최대 {}개입니다", path, max));
+                }
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    validate(item_schema, item, &format!("{}[{}]", path, i), problems);
+                }
+            }
+        }
+        Value::Object(fields) => {
+            if let Some(required) = schema.get("required").and_then(|r| r.as_array()) {
+                for name in required.iter().filter_map(|r| r.as_str()) {
+                    if !fields.contains_key(name) {
+                        problems.push(format!("{}.{}: 필수 항목이 없습니다", path, name));
+                    }
+                }
+            }
+
+            let properties = schema.get("properties").and_then(|p| p.as_object());
+            for (name, field) in fields {
+                match properties.and_then(|p| p.get(name)) {
+                    Some(field_schema) => validate(field_schema, field, &format!("{}.{}", path, name), problems),
+                    None => {
+                        if schema.get("additionalProperties") == Some(&Value::Bool(false)) {
+                            problems.push(format!("{}.{}: 정의되지 않은 항목입니다", path, name));
+                        }
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn matches_type(expected: &str, value: &Value) -> bool {
+    match expected {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "string" => value.is_string(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        _ => true,
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(max_chars).collect();
+    format!("{}…", cut)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::label::LabelOcrOutput;
+    use crate::services::llm::ScriptedLlm;
+
+    const VALID: &str = include_str!("../../tests/fixtures/label_ocr/valid.json");
+    const MALFORMED: &str = include_str!("../../tests/fixtures/label_ocr/malformed.txt");
+    const NO_JSON: &str = include_str!("../../tests/fixtures/label_ocr/no_json.txt");
+
+    async fn read(llm: &ScriptedLlm) -> Result<LabelOcrOutput, AppError> {
+        analyze_image(llm, "aW1hZ2U=", "image/png", "전성분을 읽으세요.", "읽어주세요.").await
+    }
+
+    #[tokio::test]
+    async fn accepts_a_valid_reply_without_repair() {
+        let llm = ScriptedLlm::new([Ok(VALID)]);
+        assert!(!read(&llm).await.unwrap().is_empty());
+        assert_eq!(*llm.prompts.lock().unwrap(), ["읽어주세요."]);
+    }
+
+    #[tokio::test]
+    async fn repairs_a_reply_that_breaks_the_schema() {
+        let llm = ScriptedLlm::new([Ok(MALFORMED), Ok(VALID)]);
+        assert!(!read(&llm).await.unwrap().is_empty());
+
+        let prompts = llm.prompts.lock().unwrap();
+        let repair = &prompts[1];
+        assert!(repair.starts_with("읽어주세요."));
+        assert!(repair.contains("$.entries[0].confidence: number 타입이어야 합니다"), "{repair}");
+        assert!(repair.contains("$.entries[1].confidence: 1 이하여야 합니다"), "{repair}");
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_one_failed_repair() {
+        let llm = ScriptedLlm::new([Ok(NO_JSON), Ok(MALFORMED)]);
+        match read(&llm).await {
+            Err(AppError::InvalidLlmOutput(problems)) => assert!(problems.contains("confidence"), "{problems}"),
+            other => panic!("expected InvalidLlmOutput, got {:?}", other.map(|o| o.is_empty())),
+        }
+        assert!(llm.prompts.lock().unwrap()[1].contains("응답에서 JSON을 찾을 수 없습니다."));
+    }
+
+    #[tokio::test]
+    async fn passes_provider_errors_through() {
+        let llm = ScriptedLlm::new([Ok(MALFORMED), Err("429 Too Many Requests")]);
+        assert!(matches!(read(&llm).await, Err(AppError::ApiError(e)) if e.contains("429")));
+    }
+
+    #[test]
+    fn validates_nested_fields_against_the_schema() {
+        let schema = OutputSchema::for_type::<LabelOcrOutput>();
+        let problems = parse_and_validate::<LabelOcrOutput>(&schema, MALFORMED).err().unwrap();
+        assert_eq!(problems.len(), 2, "{problems:?}");
+        assert!(parse_and_validate::<LabelOcrOutput>(&schema, VALID).is_ok());
+    }
+}
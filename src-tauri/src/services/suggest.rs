@@ -0,0 +1,612 @@
+use super::crossref::CrossRefService;
+use super::ingredients;
+use super::matching::{self, KeywordMatcher};
+use super::news::NewsService;
+use super::pubmed::PubMedService;
+use super::scheduler::{self, Provider, SendScheduled};
+use super::trend;
+use crate::models::{AppSettings, KeywordHistory, KeywordSuggestion, TrendGranularity, TrendPoint, TrendSeries};
+use async_trait::async_trait;
+use chrono::{Datelike, NaiveDate, Utc};
+use futures_util::future::join_all;
+use reqwest::Client;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use uuid::Uuid;
+
+/// Dated text a source found for the keyword; used for co-occurring terms and trend counts
+#[derive(Debug, Clone)]
+pub struct SourceDocument {
+    pub text: String,
+    pub date: Option<NaiveDate>,
+}
+
+/// What one source contributes: ready-made expansions and/or documents to mine
+#[derive(Debug, Default)]
+pub struct SourceHits {
+    pub expansions: Vec<String>,
+    pub documents: Vec<SourceDocument>,
+}
+
+/// A pluggable origin of keyword suggestions
+#[async_trait]
+pub trait SuggestionSource: Send + Sync {
+    /// Label reported as `KeywordSuggestion.source`
+    fn name(&self) -> &'static str;
+
+    async fn collect(&self, keyword: &str) -> Result<SourceHits, String>;
+}
+
+// ============================================
+// Sources
+// ============================================
+
+/// Search-box autocomplete (Google suggest JSON: `["query", ["suggestion", ...]]`)
+pub struct AutocompleteSource {
+    client: Client,
+    base_url: String,
+}
+
+impl AutocompleteSource {
+    pub fn new() -> Self {
+        Self::with_base_url("https://suggestqueries.google.com/complete/search")
+    }
+
+    pub fn with_base_url(base_url: &str) -> Self {
+        Self {
+            client: scheduler::http_client(),
+            base_url: base_url.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl SuggestionSource for AutocompleteSource {
+    fn name(&self) -> &'static str {
+        "autocomplete"
+    }
+
+    async fn collect(&self, keyword: &str) -> Result<SourceHits, String> {
+        let url = format!(
+            "{}?client=firefox&hl=ko&q={}",
+            self.base_url,
+            urlencoding::encode(keyword)
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .send_scheduled(Provider::Autocomplete)
+            .await
+            .map_err(|e| format!("자동완성 요청 실패: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("자동완성 요청 실패: HTTP {}", response.status()));
+        }
+
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| format!("자동완성 응답 파싱 실패: {}", e))?;
+
+        let expansions = body
+            .get(1)
+            .and_then(|list| list.as_array())
+            .map(|list| list.iter().filter_map(|s| s.as_str()).map(|s| s.to_string()).collect())
+            .unwrap_or_default();
+
+        Ok(SourceHits {
+            expansions,
+            documents: vec![],
+        })
+    }
+}
+
+pub struct PubMedSource {
+    service: PubMedService,
+    limit: u32,
+}
+
+impl PubMedSource {
+    pub fn new(service: PubMedService) -> Self {
+        Self { service, limit: 20 }
+    }
+}
+
+#[async_trait]
+impl SuggestionSource for PubMedSource {
+    fn name(&self) -> &'static str {
+        "pubmed"
+    }
+
+    async fn collect(&self, keyword: &str) -> Result<SourceHits, String> {
+        let papers = self.service.search(keyword, self.limit).await?;
+        Ok(SourceHits {
+            expansions: vec![],
+            documents: papers
+                .into_iter()
+                .map(|p| SourceDocument {
+                    date: parse_partial_date(&p.year),
+                    text: format!("{} {}", p.title, p.abstract_text),
+                })
+                .collect(),
+        })
+    }
+}
+
+pub struct CrossRefSource {
+    service: CrossRefService,
+    limit: usize,
+}
+
+impl CrossRefSource {
+    pub fn new(service: CrossRefService) -> Self {
+        Self { service, limit: 20 }
+    }
+}
+
+#[async_trait]
+impl SuggestionSource for CrossRefSource {
+    fn name(&self) -> &'static str {
+        "crossref"
+    }
+
+    async fn collect(&self, keyword: &str) -> Result<SourceHits, String> {
+        let works = self.service.search(keyword, self.limit).await?;
+        Ok(SourceHits {
+            expansions: vec![],
+            documents: works
+                .into_iter()
+                .map(|w| SourceDocument {
+                    date: parse_partial_date(&w.published_date),
+                    text: w.title,
+                })
+                .collect(),
+        })
+    }
+}
+
+/// Articles from the user's news feed registry
+pub struct NewsSource {
+    service: NewsService,
+}
+
+impl NewsSource {
+    pub fn new(service: NewsService) -> Self {
+        Self { service }
+    }
+}
+
+#[async_trait]
+impl SuggestionSource for NewsSource {
+    fn name(&self) -> &'static str {
+        "news"
+    }
+
+    async fn collect(&self, keyword: &str) -> Result<SourceHits, String> {
+        let documents = self
+            .service
+            .search_all(keyword)
+            .await?
+            .into_iter()
+            .map(|item| SourceDocument {
+                date: item.published_at.map(|d| d.date_naive()),
+                text: format!("{} {}", item.title, item.description),
+            })
+            .collect();
+
+        Ok(SourceHits {
+            expansions: vec![],
+            documents,
+        })
+    }
+}
+
+/// Research prompts the user already ran in their projects
+pub struct HistorySource {
+    prompts: Vec<SourceDocument>,
+}
+
+impl HistorySource {
+    pub fn new(prompts: Vec<SourceDocument>) -> Self {
+        Self { prompts }
+    }
+}
+
+#[async_trait]
+impl SuggestionSource for HistorySource {
+    fn name(&self) -> &'static str {
+        "history"
+    }
+
+    async fn collect(&self, keyword: &str) -> Result<SourceHits, String> {
+        let keyword = keyword.to_lowercase();
+        let matching: Vec<&SourceDocument> = self
+            .prompts
+            .iter()
+            .filter(|p| p.text.to_lowercase().contains(&keyword))
+            .collect();
+
+        Ok(SourceHits {
+            // 짧은 프롬프트는 그대로 키워드 후보가 됩니다
+            expansions: matching
+                .iter()
+                .map(|p| p.text.trim())
+                .filter(|t| t.chars().count() <= MAX_EXPANSION_CHARS)
+                .map(|t| t.to_string())
+                .collect(),
+            documents: matching.into_iter().cloned().collect(),
+        })
+    }
+}
+
+// ============================================
+// Engine
+// ============================================
+
+const MAX_EXPANSION_CHARS: usize = 40;
+// 공동 출현 용어로 인정하려면 최소 이 수의 문서에 나와야 합니다
+const MIN_DOCUMENT_FREQUENCY: usize = 2;
+const MAX_COOCCURRING_TERMS: usize = 10;
+
+const STOPWORDS: &[&str] = &[
+    "the", "and", "for", "with", "from", "that", "this", "was", "were", "are", "been", "has", "have", "had",
+    "not", "but", "its", "their", "our", "into", "than", "also", "may", "can", "using", "used", "use",
+    "study", "studies", "effect", "effects", "results", "result", "based", "between", "after", "during",
+    "which", "these", "those", "there", "such", "both", "each", "other", "more", "most", "all", "any",
+    "new", "via", "per", "about", "over", "under", "showed", "shows", "found", "however", "compared",
+    "및", "등", "위한", "대한", "있는", "통해", "따른", "관련", "위해", "에서", "으로", "하는", "있다", "했다",
+];
+
+struct Candidate {
+    keyword: String,
+    source: &'static str,
+    score: f64,
+}
+
+/// Merges every source's expansions and the terms that co-occur with the keyword in their documents
+pub struct SuggestionEngine {
+    sources: Vec<Box<dyn SuggestionSource>>,
+}
+
+impl SuggestionEngine {
+    pub fn new(sources: Vec<Box<dyn SuggestionSource>>) -> Self {
+        Self { sources }
+    }
+
+    /// Autocomplete, PubMed, CrossRef and news (honouring endpoint overrides), plus the given past research prompts
+    pub fn with_default_sources(settings: &AppSettings, history: Vec<SourceDocument>) -> Self {
+        let endpoints = &settings.source_endpoints;
+        let autocomplete = match &endpoints.autocomplete {
+            Some(url) => AutocompleteSource::with_base_url(url),
+            None => AutocompleteSource::new(),
+        };
+        let pubmed = PubMedService::from_settings(settings);
+        let crossref = match &endpoints.crossref {
+            Some(url) => CrossRefService::with_base_url(url),
+            None => CrossRefService::new(),
+        };
+
+        Self::new(vec![
+            Box::new(autocomplete),
+            Box::new(PubMedSource::new(pubmed)),
+            Box::new(CrossRefSource::new(crossref)),
+            Box::new(NewsSource::new(NewsService::from_settings(settings))),
+            Box::new(HistorySource::new(history)),
+        ])
+    }
+
+    pub async fn suggest(&self, keyword: &str, limit: usize) -> Vec<KeywordSuggestion> {
+        let keyword = keyword.trim();
+        let results = join_all(self.sources.iter().map(|s| s.collect(keyword))).await;
+
+        let mut hits: Vec<(&'static str, SourceHits)> = Vec::new();
+        for (source, result) in self.sources.iter().zip(results) {
+            match result {
+                Ok(h) => hits.push((source.name(), h)),
+                // 한 소스가 실패해도 나머지 결과로 추천합니다
+                Err(e) => eprintln!("키워드 추천 소스 실패 ({}): {}", source.name(), e),
+            }
+        }
+
+        // 성분명이면 다른 이름으로 쓴 문서도 찾습니다 (영어 논문에는 "나이아신아마이드"가 나오지 않습니다)
+        let ingredient = ingredients::dictionary().resolve(keyword).cloned();
+        let subject = KeywordMatcher::new(ingredient.as_ref().map_or(keyword, |i| i.inci_name.as_str()));
+        let mut seed_tokens: HashSet<String> = terms(keyword).into_iter().collect();
+        if let Some(ingredient) = &ingredient {
+            seed_tokens.extend(ingredients::names(ingredient).iter().flat_map(|name| terms(name)));
+        }
+        let mut candidates: HashMap<String, Candidate> = HashMap::new();
+
+        for (source, h) in &hits {
+            for (i, expansion) in h.expansions.iter().enumerate() {
+                // 앞쪽 자동완성일수록 가중치가 큽니다
+                let score = 3.0 - (i as f64 * 0.1).min(2.0);
+                add_candidate(&mut candidates, keyword, expansion, source, score);
+            }
+
+            for (term, frequency) in cooccurring_terms(&h.documents, &subject, &seed_tokens) {
+                let expansion = format!("{} {}", keyword, term);
+                add_candidate(&mut candidates, keyword, &expansion, source, frequency as f64);
+            }
+        }
+
+        let today = Utc::now().date_naive();
+
+        let mut ranked: Vec<Candidate> = candidates.into_values().collect();
+        ranked.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.keyword.cmp(&b.keyword)));
+
+        ranked
+            .into_iter()
+            .take(limit)
+            .map(|c| {
+                let (trend, _) = trend::classify(&mention_history(&c.keyword, &seed_tokens, &hits), today);
+                KeywordSuggestion {
+                    id: Uuid::new_v4().to_string(),
+                    keyword: c.keyword,
+                    trend: trend.to_string(),
+                    source: c.source.to_string(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Same suggestion from several sources accumulates score; the strongest source is reported
+fn add_candidate(
+    candidates: &mut HashMap<String, Candidate>,
+    keyword: &str,
+    expansion: &str,
+    source: &'static str,
+    score: f64,
+) {
+    let normalized = expansion.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    if normalized.is_empty() || normalized == keyword.to_lowercase() {
+        return;
+    }
+
+    let candidate = candidates.entry(normalized).or_insert_with(|| Candidate {
+        keyword: expansion.split_whitespace().collect::<Vec<_>>().join(" "),
+        source,
+        score: 0.0,
+    });
+    if score > candidate.score {
+        candidate.source = source;
+    }
+    candidate.score += score;
+}
+
+/// Terms appearing in at least `MIN_DOCUMENT_FREQUENCY` documents that mention the subject
+fn cooccurring_terms(
+    documents: &[SourceDocument],
+    subject: &KeywordMatcher,
+    seed_tokens: &HashSet<String>,
+) -> Vec<(String, usize)> {
+    let mut frequency: HashMap<String, usize> = HashMap::new();
+
+    for document in documents.iter().filter(|d| subject.is_match(&d.text, "")) {
+        let terms: HashSet<String> = terms(&document.text)
+            .into_iter()
+            .filter(|t| !seed_tokens.contains(t) && !STOPWORDS.contains(&t.as_str()))
+            .collect();
+        for term in terms {
+            *frequency.entry(term).or_default() += 1;
+        }
+    }
+
+    let mut terms: Vec<(String, usize)> = frequency
+        .into_iter()
+        .filter(|(_, count)| *count >= MIN_DOCUMENT_FREQUENCY)
+        .collect();
+    terms.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    terms.truncate(MAX_COOCCURRING_TERMS);
+    terms
+}
+
+/// Words worth suggesting: tokens of two or more characters that are not plain numbers
+fn terms(text: &str) -> Vec<String> {
+    matching::tokenize(text)
+        .into_iter()
+        .filter(|t| t.chars().count() >= 2 && !t.chars().all(|c| c.is_ascii_digit()))
+        .collect()
+}
+
+/// Dated documents that mention the suggestion's own terms, as the series `trend::classify` reads:
+/// news per day, papers and past prompts per publication year, one series per source
+fn mention_history(
+    suggestion: &str,
+    seed_tokens: &HashSet<String>,
+    hits: &[(&'static str, SourceHits)],
+) -> KeywordHistory {
+    let own_terms: Vec<String> = terms(suggestion).into_iter().filter(|t| !seed_tokens.contains(t)).collect();
+    // 검색어 자체로만 이루어진 추천이면 추천 전체를 셉니다
+    let matcher = KeywordMatcher::new(&if own_terms.is_empty() { suggestion.to_string() } else { own_terms.join(" ") });
+
+    let mut history = KeywordHistory {
+        keyword: suggestion.to_string(),
+        updated_at: String::new(),
+        series: vec![],
+        seen_news: vec![],
+    };
+    for (source, h) in hits {
+        let granularity = if *source == trend::SOURCE_NEWS { TrendGranularity::Day } else { TrendGranularity::Year };
+        let mut counts: BTreeMap<String, u64> = BTreeMap::new();
+        for document in &h.documents {
+            let Some(date) = document.date.filter(|_| matcher.is_match(&document.text, "")) else {
+                continue;
+            };
+            let period = match granularity {
+                TrendGranularity::Day => date.format("%Y-%m-%d").to_string(),
+                TrendGranularity::Year => date.year().to_string(),
+            };
+            *counts.entry(period).or_default() += 1;
+        }
+        if !counts.is_empty() {
+            history.series.push(TrendSeries {
+                source: source.to_string(),
+                granularity,
+                points: counts.into_iter().map(|(period, count)| TrendPoint { period, count }).collect(),
+            });
+        }
+    }
+    history
+}
+
+/// "2023", "2023-5" or "2023-05-14" → first day of the period
+fn parse_partial_date(text: &str) -> Option<NaiveDate> {
+    let mut parts = text.trim().split('-').map(|p| p.trim().parse::<u32>());
+    let year = parts.next()?.ok()? as i32;
+    let month = parts.next().and_then(|m| m.ok()).unwrap_or(1);
+    let day = parts.next().and_then(|d| d.ok()).unwrap_or(1);
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeSource {
+        name: &'static str,
+        expansions: Vec<&'static str>,
+        documents: Vec<&'static str>,
+        fail: bool,
+    }
+
+    impl FakeSource {
+        fn boxed(
+            name: &'static str,
+            expansions: &[&'static str],
+            documents: &[&'static str],
+        ) -> Box<dyn SuggestionSource> {
+            Box::new(Self {
+                name,
+                expansions: expansions.to_vec(),
+                documents: documents.to_vec(),
+                fail: false,
+            })
+        }
+    }
+
+    #[async_trait]
+    impl SuggestionSource for FakeSource {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        async fn collect(&self, _keyword: &str) -> Result<SourceHits, String> {
+            if self.fail {
+                return Err("연결 실패".to_string());
+            }
+            Ok(SourceHits {
+                expansions: self.expansions.iter().map(|s| s.to_string()).collect(),
+                documents: self
+                    .documents
+                    .iter()
+                    .map(|text| SourceDocument {
+                        text: text.to_string(),
+                        date: None,
+                    })
+                    .collect(),
+            })
+        }
+    }
+
+    fn keywords(suggestions: &[KeywordSuggestion]) -> Vec<&str> {
+        suggestions.iter().map(|s| s.keyword.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn merges_expansions_from_every_source() {
+        let failing = Box::new(FakeSource {
+            name: "broken",
+            expansions: vec!["레티놀 세럼"],
+            documents: vec![],
+            fail: true,
+        });
+        let engine = SuggestionEngine::new(vec![
+            FakeSource::boxed("autocomplete", &["레티놀 크림", "레티놀", "레티놀  세럼"], &[]),
+            FakeSource::boxed("history", &["레티놀 세럼"], &[]),
+            failing,
+        ]);
+
+        let suggestions = engine.suggest(" 레티놀 ", 10).await;
+        // 두 소스가 함께 낸 후보가 앞서고, 검색어 자체와 실패한 소스는 빠집니다
+        assert_eq!(keywords(&suggestions), ["레티놀 세럼", "레티놀 크림"]);
+        assert_eq!(suggestions[0].source, "history");
+        assert_eq!(suggestions[1].source, "autocomplete");
+
+        assert_eq!(engine.suggest("레티놀", 1).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn mines_terms_from_documents_that_use_another_name() {
+        let engine = SuggestionEngine::new(vec![FakeSource::boxed(
+            "pubmed",
+            &[],
+            &[
+                "Topical niacinamide reduces hyperpigmentation in melasma",
+                "Niacinamide and hyperpigmentation: a randomized trial",
+                "Niacinamide improves barrier function",
+                // 검색어를 언급하지 않는 문서의 단어는 세지 않습니다
+                "Retinol hyperpigmentation outcomes",
+            ],
+        )]);
+
+        let suggestions = engine.suggest("나이아신아마이드", 10).await;
+        assert_eq!(keywords(&suggestions), ["나이아신아마이드 hyperpigmentation"]);
+        assert_eq!(suggestions[0].source, "pubmed");
+    }
+
+    #[test]
+    fn counts_mentions_per_day_for_news_and_per_year_for_papers() {
+        let document = |text: &str, date: &str| SourceDocument {
+            text: text.to_string(),
+            date: NaiveDate::parse_from_str(date, "%Y-%m-%d").ok(),
+        };
+        let hits = vec![
+            (
+                "news",
+                SourceHits {
+                    expansions: vec![],
+                    documents: vec![
+                        document("레티놀 크림 출시", "2025-03-02"),
+                        document("레티놀 크림 리뷰", "2025-03-02"),
+                        document("레티놀 세럼", "2025-03-03"),
+                    ],
+                },
+            ),
+            (
+                "pubmed",
+                SourceHits {
+                    expansions: vec![],
+                    documents: vec![document("레티놀 크림 임상", "2023-05-01"), document("크림 연구", "")],
+                },
+            ),
+        ];
+        let seed_tokens: HashSet<String> = terms("레티놀").into_iter().collect();
+
+        let history = mention_history("레티놀 크림", &seed_tokens, &hits);
+        let counts = |s: &TrendSeries| -> Vec<(String, u64)> {
+            s.points.iter().map(|p| (p.period.clone(), p.count)).collect()
+        };
+        assert_eq!(history.series.len(), 2);
+        let (news, pubmed) = (&history.series[0], &history.series[1]);
+        assert_eq!((news.source.as_str(), news.granularity), ("news", TrendGranularity::Day));
+        assert_eq!(counts(news), [("2025-03-02".to_string(), 2)]);
+        assert_eq!((pubmed.source.as_str(), pubmed.granularity), ("pubmed", TrendGranularity::Year));
+        assert_eq!(counts(pubmed), [("2023".to_string(), 1)]);
+    }
+
+    #[test]
+    fn terms_skip_short_words_numbers_and_particles() {
+        assert_eq!(terms("레티놀은 2024년 A 기준 1% 크림"), ["레티놀", "2024년", "기준", "크림"]);
+    }
+
+    #[test]
+    fn parses_partial_dates() {
+        assert_eq!(parse_partial_date("2023"), NaiveDate::from_ymd_opt(2023, 1, 1));
+        assert_eq!(parse_partial_date("2023-5"), NaiveDate::from_ymd_opt(2023, 5, 1));
+        assert_eq!(parse_partial_date(" 2023-05-14 "), NaiveDate::from_ymd_opt(2023, 5, 14));
+        assert_eq!(parse_partial_date(""), None);
+        assert_eq!(parse_partial_date("2023-13"), None);
+    }
+}
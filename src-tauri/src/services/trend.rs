@@ -0,0 +1,463 @@
+use super::crossref::CrossRefService;
+use super::ingredients;
+use super::news::{NewsResult, NewsService};
+use super::pubmed::PubMedService;
+use crate::models::{
+    AppSettings, KeywordHistory, KeywordTrend, TrendGranularity, TrendPoint, TrendSeries, TrendStore,
+};
+use chrono::{Datelike, NaiveDate, Utc};
+
+pub const SOURCE_NEWS: &str = "news";
+pub const SOURCE_PUBMED: &str = "pubmed";
+pub const SOURCE_CROSSREF: &str = "crossref";
+
+// 연도별 문헌 수를 조회할 기간 (올해 포함)
+const YEARS_OF_HISTORY: i32 = 6;
+const MAX_SEEN_NEWS: usize = 2000;
+const MAX_NEWS_DAYS: usize = 400;
+
+// 뉴스 비교 구간: 최근 7일 vs 그 이전 28일
+const NEWS_RECENT_DAYS: i64 = 7;
+const NEWS_BASELINE_DAYS: i64 = 28;
+const HOT_NEWS_COUNT: u64 = 5;
+
+/// Normalized store key for a keyword
+pub fn trend_key(keyword: &str) -> String {
+    keyword.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// Fetches per-period counts for a keyword from PubMed, CrossRef and the news feeds
+pub struct TrendCollector {
+    pubmed: PubMedService,
+    crossref: CrossRefService,
+    news: NewsService,
+}
+
+impl TrendCollector {
+    pub fn new(settings: &AppSettings) -> Self {
+        Self {
+            pubmed: PubMedService::from_settings(settings),
+            crossref: settings
+                .source_endpoints
+                .crossref
+                .as_deref()
+                .map(CrossRefService::with_base_url)
+                .unwrap_or_else(CrossRefService::new),
+            news: NewsService::from_settings(settings),
+        }
+    }
+
+    /// Fetch fresh counts; a failing source is left out so its stored series stays as it was
+    pub async fn collect(&self, keyword: &str) -> TrendUpdate {
+        let this_year = Utc::now().year();
+        let years: Vec<i32> = (this_year - YEARS_OF_HISTORY + 1..=this_year).collect();
+
+        let (pubmed, crossref, news) = tokio::join!(
+            self.pubmed_counts(keyword, &years),
+            self.crossref_counts(keyword, &years),
+            self.news.search_all(keyword)
+        );
+
+        TrendUpdate {
+            pubmed: pubmed
+                .map_err(|e| eprintln!("PubMed 연도별 집계 실패 ({}): {}", keyword, e))
+                .ok(),
+            crossref: crossref
+                .map_err(|e| eprintln!("CrossRef 연도별 집계 실패 ({}): {}", keyword, e))
+                .ok(),
+            news: news.map_err(|e| eprintln!("뉴스 집계 실패 ({}): {}", keyword, e)).ok(),
+        }
+    }
+
+    async fn pubmed_counts(&self, keyword: &str, years: &[i32]) -> Result<Vec<TrendPoint>, String> {
+        let term = pubmed_term(keyword);
+        let mut points = Vec::new();
+        for year in years {
+            let count = self.pubmed.count(&format!("({}) AND {}[pdat]", term, year)).await?;
+            points.push(TrendPoint {
+                period: year.to_string(),
+                count,
+            });
+        }
+        Ok(points)
+    }
+
+    async fn crossref_counts(&self, keyword: &str, years: &[i32]) -> Result<Vec<TrendPoint>, String> {
+        let mut points = Vec::new();
+        for year in years {
+            let count = self
+                .crossref
+                .count(keyword, &format!("{}-01-01", year), &format!("{}-12-31", year))
+                .await?;
+            points.push(TrendPoint {
+                period: year.to_string(),
+                count,
+            });
+        }
+        Ok(points)
+    }
+}
+
+/// PubMed term for the keyword: any of the ingredient's English names, else the keyword as typed
+/// (PubMed has next to nothing under a Korean name)
+fn pubmed_term(keyword: &str) -> String {
+    let names = ingredients::dictionary()
+        .resolve(keyword)
+        .map(ingredients::search_names)
+        .filter(|names| !names.is_empty());
+    match names {
+        Some(names) => names
+            .iter()
+            .map(|name| format!("\"{}\"", name.replace('"', "")))
+            .collect::<Vec<_>>()
+            .join(" OR "),
+        None => keyword.trim().to_string(),
+    }
+}
+
+/// Counts gathered by `TrendCollector::collect`, applied to the store afterwards
+pub struct TrendUpdate {
+    pub pubmed: Option<Vec<TrendPoint>>,
+    pub crossref: Option<Vec<TrendPoint>>,
+    pub news: Option<Vec<NewsResult>>,
+}
+
+impl TrendUpdate {
+    pub fn apply(self, history: &mut KeywordHistory) {
+        if let Some(points) = self.pubmed {
+            set_series(history, SOURCE_PUBMED, TrendGranularity::Year, points);
+        }
+        if let Some(points) = self.crossref {
+            set_series(history, SOURCE_CROSSREF, TrendGranularity::Year, points);
+        }
+        if let Some(items) = self.news {
+            record_news(history, &items);
+        }
+        history.updated_at = Utc::now().to_rfc3339();
+    }
+}
+
+/// History entry for `keyword`, created empty on first use
+pub fn history_entry<'a>(store: &'a mut TrendStore, keyword: &str) -> &'a mut KeywordHistory {
+    store
+        .keywords
+        .entry(trend_key(keyword))
+        .or_insert_with(|| KeywordHistory {
+            keyword: keyword.trim().to_string(),
+            updated_at: Utc::now().to_rfc3339(),
+            series: vec![],
+            seen_news: vec![],
+        })
+}
+
+/// Count news items per publication day, skipping items counted by an earlier fetch
+pub fn record_news(history: &mut KeywordHistory, items: &[NewsResult]) {
+    let mut new_days: Vec<String> = Vec::new();
+
+    for item in items {
+        let id = if item.link.is_empty() { &item.title } else { &item.link };
+        if history.seen_news.contains(id) {
+            continue;
+        }
+        // 날짜를 알 수 없는 기사는 시계열에 넣지 않습니다
+        let Some(date) = item.published_at else {
+            continue;
+        };
+        history.seen_news.push(id.clone());
+        new_days.push(date.date_naive().format("%Y-%m-%d").to_string());
+    }
+
+    let overflow = history.seen_news.len().saturating_sub(MAX_SEEN_NEWS);
+    history.seen_news.drain(..overflow);
+
+    let series = series_mut(history, SOURCE_NEWS, TrendGranularity::Day);
+    for day in new_days {
+        match series.points.iter_mut().find(|p| p.period == day) {
+            Some(point) => point.count += 1,
+            None => series.points.push(TrendPoint { period: day, count: 1 }),
+        }
+    }
+    series.points.sort_by(|a, b| a.period.cmp(&b.period));
+    let overflow = series.points.len().saturating_sub(MAX_NEWS_DAYS);
+    series.points.drain(..overflow);
+}
+
+/// Replace the given periods of a series (literature counts are totals, so newer numbers win)
+fn set_series(history: &mut KeywordHistory, source: &str, granularity: TrendGranularity, points: Vec<TrendPoint>) {
+    let series = series_mut(history, source, granularity);
+    for point in points {
+        match series.points.iter_mut().find(|p| p.period == point.period) {
+            Some(existing) => existing.count = point.count,
+            None => series.points.push(point),
+        }
+    }
+    series.points.sort_by(|a, b| a.period.cmp(&b.period));
+}
+
+fn series_mut<'a>(history: &'a mut KeywordHistory, source: &str, granularity: TrendGranularity) -> &'a mut TrendSeries {
+    let index = match history.series.iter().position(|s| s.source == source) {
+        Some(index) => index,
+        None => {
+            history.series.push(TrendSeries {
+                source: source.to_string(),
+                granularity,
+                points: vec![],
+            });
+            history.series.len() - 1
+        }
+    };
+    &mut history.series[index]
+}
+
+/// Label and series for charting; keywords without history are "stable" with no growth figure
+pub fn keyword_trend(keyword: &str, history: Option<&KeywordHistory>, today: NaiveDate) -> KeywordTrend {
+    let Some(history) = history else {
+        return KeywordTrend {
+            keyword: keyword.trim().to_string(),
+            trend: "stable".to_string(),
+            growth: None,
+            updated_at: None,
+            series: vec![],
+        };
+    };
+
+    let (trend, growth) = classify(history, today);
+    KeywordTrend {
+        keyword: history.keyword.clone(),
+        trend: trend.to_string(),
+        growth,
+        updated_at: Some(history.updated_at.clone()),
+        series: history.series.clone(),
+    }
+}
+
+/// Average growth over the sources that have enough history:
+/// hot = at least doubling (or +50% with a busy news week), rising = +20%, otherwise stable
+pub fn classify(history: &KeywordHistory, today: NaiveDate) -> (&'static str, Option<f64>) {
+    let mut growths = Vec::new();
+    let mut recent_news = 0;
+
+    for series in &history.series {
+        match series.granularity {
+            TrendGranularity::Year => growths.extend(yearly_growth(series, today)),
+            TrendGranularity::Day => {
+                if let Some((growth, recent)) = daily_growth(series, today) {
+                    growths.push(growth);
+                    recent_news = recent;
+                }
+            }
+        }
+    }
+
+    if growths.is_empty() {
+        return ("stable", None);
+    }
+    let growth = growths.iter().sum::<f64>() / growths.len() as f64;
+
+    let label = if growth >= 2.0 || (growth >= 1.5 && recent_news >= HOT_NEWS_COUNT) {
+        "hot"
+    } else if growth >= 1.2 {
+        "rising"
+    } else {
+        "stable"
+    };
+    (label, Some(growth))
+}
+
+/// Publications per year over the last year and a bit (this year's partial count included)
+/// versus the average of the three years before
+fn yearly_growth(series: &TrendSeries, today: NaiveDate) -> Option<f64> {
+    let count = |year: i32| {
+        series
+            .points
+            .iter()
+            .find(|p| p.period == year.to_string())
+            .map(|p| p.count as f64)
+    };
+
+    let this_year = today.year();
+    let baseline: Vec<f64> = (this_year - 4..=this_year - 2).filter_map(count).collect();
+    let last_year = count(this_year - 1)?;
+    if baseline.is_empty() {
+        return None;
+    }
+
+    let elapsed = today.ordinal() as f64 / 365.0;
+    let recent_rate = (last_year + count(this_year).unwrap_or(0.0)) / (1.0 + elapsed);
+    let baseline_rate = baseline.iter().sum::<f64>() / baseline.len() as f64;
+
+    Some((recent_rate + 1.0) / (baseline_rate + 1.0))
+}
+
+/// News items in the last week versus the weekly average of the four weeks before.
+/// Needs the store to have been recording for longer than the recent window.
+fn daily_growth(series: &TrendSeries, today: NaiveDate) -> Option<(f64, u64)> {
+    let dated: Vec<(i64, u64)> = series
+        .points
+        .iter()
+        .filter_map(|p| {
+            let date = NaiveDate::parse_from_str(&p.period, "%Y-%m-%d").ok()?;
+            Some(((today - date).num_days(), p.count))
+        })
+        .collect();
+
+    let oldest = dated.iter().map(|(age, _)| *age).max()?;
+    if oldest < NEWS_RECENT_DAYS {
+        return None;
+    }
+
+    let recent: u64 = dated
+        .iter()
+        .filter(|(age, _)| (0..NEWS_RECENT_DAYS).contains(age))
+        .map(|(_, c)| c)
+        .sum();
+    let baseline_days = (oldest + 1 - NEWS_RECENT_DAYS).min(NEWS_BASELINE_DAYS);
+    let baseline: u64 = dated
+        .iter()
+        .filter(|(age, _)| (NEWS_RECENT_DAYS..NEWS_RECENT_DAYS + baseline_days).contains(age))
+        .map(|(_, c)| c)
+        .sum();
+    let baseline_weekly = baseline as f64 * NEWS_RECENT_DAYS as f64 / baseline_days as f64;
+
+    Some(((recent as f64 + 1.0) / (baseline_weekly + 1.0), recent))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    fn day(date: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+    }
+
+    fn series(granularity: TrendGranularity, points: &[(&str, u64)]) -> TrendSeries {
+        TrendSeries {
+            source: "test".to_string(),
+            granularity,
+            points: points
+                .iter()
+                .map(|(period, count)| TrendPoint {
+                    period: period.to_string(),
+                    count: *count,
+                })
+                .collect(),
+        }
+    }
+
+    fn yearly(last_year: u64) -> TrendSeries {
+        series(
+            TrendGranularity::Year,
+            &[("2021", 10), ("2022", 10), ("2023", 10), ("2024", last_year), ("2025", 0)],
+        )
+    }
+
+    /// `recent` items over the last week and `baseline` spread over the four weeks before
+    fn daily(today: NaiveDate, recent: u64, baseline: u64) -> TrendSeries {
+        let period = |age: i64| (today - chrono::Duration::days(age)).format("%Y-%m-%d").to_string();
+        let oldest = period(NEWS_RECENT_DAYS + NEWS_BASELINE_DAYS - 1);
+        let (baseline_day, recent_day) = (period(10), period(1));
+        series(
+            TrendGranularity::Day,
+            &[(oldest.as_str(), 0), (baseline_day.as_str(), baseline), (recent_day.as_str(), recent)],
+        )
+    }
+
+    fn history(series: Vec<TrendSeries>) -> KeywordHistory {
+        KeywordHistory {
+            keyword: "레티놀".to_string(),
+            updated_at: String::new(),
+            series,
+            seen_news: vec![],
+        }
+    }
+
+    fn news(link: &str, title: &str, published_at: Option<&str>) -> NewsResult {
+        NewsResult {
+            title: title.to_string(),
+            description: String::new(),
+            link: link.to_string(),
+            pub_date: String::new(),
+            published_at: published_at.map(|d| DateTime::parse_from_rfc3339(d).unwrap()),
+            source: "피드".to_string(),
+            category: String::new(),
+            relevance: 1.0,
+            other_sources: vec![],
+        }
+    }
+
+    #[test]
+    fn yearly_growth_compares_the_last_year_with_the_three_before() {
+        let today = day("2025-07-02");
+        // 작년 30건과 올해 절반 동안의 0건 → 연 20건 꼴, 기준은 연 10건
+        let growth = yearly_growth(&yearly(30), today).unwrap();
+        assert!((growth - 21.0 / 11.0).abs() < 0.05, "{growth}");
+
+        let with_this_year = series(
+            TrendGranularity::Year,
+            &[("2021", 10), ("2022", 10), ("2023", 10), ("2024", 30), ("2025", 15)],
+        );
+        let growth = yearly_growth(&with_this_year, today).unwrap();
+        assert!((growth - 31.0 / 11.0).abs() < 0.05, "{growth}");
+
+        let no_last_year = series(TrendGranularity::Year, &[("2021", 10), ("2022", 10), ("2025", 5)]);
+        assert_eq!(yearly_growth(&no_last_year, today), None);
+        let no_baseline = series(TrendGranularity::Year, &[("2024", 10), ("2025", 5)]);
+        assert_eq!(yearly_growth(&no_baseline, today), None);
+    }
+
+    #[test]
+    fn daily_growth_compares_the_last_week_with_the_weekly_baseline() {
+        let today = day("2025-07-02");
+        let (growth, recent) = daily_growth(&daily(today, 10, 4), today).unwrap();
+        assert_eq!(recent, 10);
+        assert!((growth - 11.0 / 2.0).abs() < 1e-9, "{growth}");
+
+        // 기록한 지 일주일이 안 됐으면 비교하지 않습니다
+        let fresh = series(TrendGranularity::Day, &[("2025-06-30", 3), ("2025-07-01", 4)]);
+        assert_eq!(daily_growth(&fresh, today), None);
+        assert_eq!(daily_growth(&series(TrendGranularity::Day, &[]), today), None);
+    }
+
+    #[test]
+    fn classifies_by_average_growth() {
+        let today = day("2025-01-01");
+        let label = |series: Vec<TrendSeries>| classify(&history(series), today).0;
+
+        assert_eq!(classify(&history(vec![]), today), ("stable", None));
+        assert_eq!(label(vec![yearly(10)]), "stable");
+        assert_eq!(label(vec![yearly(13)]), "rising");
+        assert_eq!(label(vec![yearly(17)]), "rising");
+        assert_eq!(label(vec![yearly(25)]), "hot");
+        // +50% 이상에 이번 주 기사가 많으면 hot
+        assert_eq!(label(vec![yearly(17), daily(today, 5, 11)]), "hot");
+        assert_eq!(label(vec![yearly(17), daily(today, 4, 8)]), "rising");
+    }
+
+    #[test]
+    fn records_each_news_item_once_per_day() {
+        let mut history = history(vec![]);
+        record_news(
+            &mut history,
+            &[
+                news("https://a/1", "첫 기사", Some("2025-03-02T09:00:00+09:00")),
+                news("https://a/2", "둘째 기사", Some("2025-03-02T18:00:00+09:00")),
+                news("", "링크 없는 기사", Some("2025-03-01T12:00:00+00:00")),
+                news("https://a/3", "날짜 없는 기사", None),
+            ],
+        );
+        record_news(
+            &mut history,
+            &[
+                news("https://a/1", "첫 기사", Some("2025-03-02T09:00:00+09:00")),
+                news("", "링크 없는 기사", Some("2025-03-01T12:00:00+00:00")),
+                news("https://a/4", "새 기사", Some("2025-03-03T08:00:00+09:00")),
+            ],
+        );
+
+        let points: Vec<(&str, u64)> = history.series[0].points.iter().map(|p| (p.period.as_str(), p.count)).collect();
+        assert_eq!(points, [("2025-03-01", 1), ("2025-03-02", 2), ("2025-03-03", 1)]);
+        assert_eq!(history.series[0].granularity, TrendGranularity::Day);
+        assert_eq!(history.seen_news, ["https://a/1", "https://a/2", "링크 없는 기사", "https://a/4"]);
+    }
+}
@@ -1,6 +1,6 @@
 import { useState } from "react";
-import { Search, Sparkles, BookOpen, ImageIcon, Star, CheckCircle, AlertTriangle, FileText, Eye, Wand2 } from "lucide-react";
-import { useKeywordStore } from "@/stores/keywordStore";
+import { Search, Sparkles, BookOpen, ImageIcon, Star, CheckCircle, AlertTriangle, FileText, Eye, Wand2, Scale } from "lucide-react";
+import { useKeywordStore, regulatoryNote } from "@/stores/keywordStore";
 import { useSettingsStore } from "@/stores/settingsStore";
 import { useContentStore } from "@/stores/contentStore";
 import { convertFileSrc } from "@tauri-apps/api/core";
@@ -223,6 +223,21 @@ export default function LeftPanel({ className }: LeftPanelProps) {
               </div>
             )}
 
+            {/* Regulatory Facts */}
+            {(ingredientAnalysis.regulatoryFacts ?? []).length > 0 && (
+              <div className="space-y-1">
+                <div className="flex items-center gap-1 text-xs font-medium text-blue-600">
+                  <Scale className="w-3 h-3" />
+                  <span>규제 정보</span>
+                </div>
+                <ul className="text-xs text-gray-600 space-y-0.5 pl-4">
+                  {ingredientAnalysis.regulatoryFacts!.map((fact, idx) => (
+                    <li key={idx} className="list-disc">{regulatoryNote(fact)}</li>
+                  ))}
+                </ul>
+              </div>
+            )}
+
             {/* Papers Count */}
             {researchResults.length > 0 && (
               <div className="text-xs text-gray-500 pt-2 border-t border-gray-200">
@@ -1,5 +1,5 @@
-import { X, FileText, CheckCircle, AlertTriangle, BookOpen, ExternalLink } from "lucide-react";
-import { useKeywordStore } from "@/stores/keywordStore";
+import { X, FileText, CheckCircle, AlertTriangle, BookOpen, ExternalLink, Scale } from "lucide-react";
+import { useKeywordStore, regulatoryNote } from "@/stores/keywordStore";
 
 interface ResearchDetailModalProps {
   isOpen: boolean;
@@ -93,6 +93,30 @@ export default function ResearchDetailModal({ isOpen, onClose }: ResearchDetailM
             </div>
           </div>
 
+          {/* Regulatory Facts */}
+          {(ingredientAnalysis.regulatoryFacts ?? []).length > 0 && (
+            <div className="bg-blue-50 rounded-lg p-4">
+              <div className="flex items-center gap-2 mb-3">
+                <Scale className="w-5 h-5 text-blue-600" />
+                <h4 className="font-medium text-blue-800">규제 정보</h4>
+              </div>
+              <ul className="space-y-2">
+                {ingredientAnalysis.regulatoryFacts!.map((fact, idx) => (
+                  <li key={idx} className="flex items-start gap-2 text-sm text-blue-700">
+                    <span className="w-1.5 h-1.5 rounded-full bg-blue-500 mt-1.5 flex-shrink-0" />
+                    {fact.source.url ? (
+                      <a href={fact.source.url} target="_blank" rel="noopener noreferrer" className="hover:underline">
+                        {regulatoryNote(fact)}
+                      </a>
+                    ) : (
+                      regulatoryNote(fact)
+                    )}
+                  </li>
+                ))}
+              </ul>
+            </div>
+          )}
+
           {/* Related Papers */}
           {researchResults.length > 0 && (
             <div>
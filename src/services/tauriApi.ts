@@ -79,6 +79,24 @@ export interface ResearchReport {
     type: "paper" | "journal" | "website";
     citedIn: string;
   }>;
+  labelAnalysis?: {
+    productName: string | null;
+    entries: Array<{
+      position: number;
+      name: string;
+      inciName: string | null;
+      koreanName: string | null;
+      flags: LabelFlag[];
+      notes: string[];
+    }>;
+    combinations: Array<{
+      ingredients: string[];
+      reason: string;
+      advice?: string | null;
+      source?: string | null;
+    }>;
+    unresolved: string[];
+  } | null;
 }
 
 export interface ContentGroup {
@@ -87,6 +105,8 @@ export interface ContentGroup {
   researchItemIds: string[];
   contents: ContentItem[];
   createdAt: string;
+  /** Content item id -> layout template id; unassigned items use the slide-role template */
+  itemLayoutTemplates?: Record<string, string>;
 }
 
 export interface ContentItem {
@@ -117,6 +137,25 @@ export interface KeywordSuggestion {
   source: string;
 }
 
+export interface TrendPoint {
+  period: string; // "2024-05-13" (day) or "2024" (year)
+  count: number;
+}
+
+export interface TrendSeries {
+  source: "news" | "pubmed" | "crossref";
+  granularity: "day" | "year";
+  points: TrendPoint[];
+}
+
+export interface KeywordTrend {
+  keyword: string;
+  trend: "rising" | "stable" | "hot";
+  growth: number | null;
+  updatedAt: string | null;
+  series: TrendSeries[];
+}
+
 export interface PaperResult {
   id: string;
   title: string;
@@ -126,6 +165,68 @@ export interface PaperResult {
   source: string;
   citationCount: number | null;
   doi: string | null;
+  pmid?: string | null;
+  pmcid?: string | null;
+  url?: string | null;
+  openAccessUrl?: string | null;
+  journal?: string | null;
+  abstractSections?: AbstractSection[];
+  meshTerms?: string[];
+  keywords?: string[];
+  publicationTypes?: string[];
+  country?: string | null;
+  evidence?: EvidenceAssessment | null;
+}
+
+export interface EvidenceAssessment {
+  grade: string;
+  study_design: string;
+  subject: string;
+  sample_size: number | null;
+  year: number | null;
+  weak: boolean;
+}
+
+export interface BenefitEvidence {
+  benefit: string;
+  paper_ids: string[];
+  grade: string | null;
+  weak_evidence: boolean;
+}
+
+export interface AbstractSection {
+  label: string | null;
+  text: string;
+}
+
+export interface ScholarlyRecord extends PaperResult {
+  found_in: string[]; // "pubmed" | "crossref" | "web"
+  score: number;
+}
+
+export interface LiteratureSearchResult {
+  records: ScholarlyRecord[];
+  searched: string[];
+  failures: { source: string; error: string }[];
+}
+
+export type ArticleType =
+  | "meta_analysis"
+  | "systematic_review"
+  | "randomized_controlled_trial"
+  | "clinical_trial"
+  | "review"
+  | "case_report";
+
+export interface PaperSearchFilters {
+  year_from?: number;
+  year_to?: number;
+  article_types?: ArticleType[];
+  humans_only?: boolean;
+  languages?: string[];
+  populations?: ("pregnancy" | "infant" | "pediatric")[];
+  sort?: "relevance" | "newest";
+  offset?: number;
 }
 
 export interface IngredientAnalysis {
@@ -136,6 +237,11 @@ export interface IngredientAnalysis {
   cautions: string[];
   recommendedConcentration: string | null;
   relatedPapers: PaperResult[];
+  benefitEvidence?: BenefitEvidence[];
+  /** Dictionary entry the input resolved to */
+  ingredient?: Ingredient | null;
+  /** Sourced MFDS/EU/pregnancy list entries; not written by the LLM, unlike cautions */
+  regulatory_facts?: RegulatoryFact[];
 }
 
 // Response types - use snake_case to match Rust backend
@@ -236,22 +342,73 @@ export interface AppSettings {
   savePath: string;
   layoutSettings: LayoutSettings;
   imageSizePresets: ImageSizePreset[];
+  ncbi?: NcbiSettings;
+  news_feeds?: NewsFeedRegistry;
+}
+
+export interface NcbiSettings {
+  api_key: string | null;
+  tool: string | null;
+  email: string | null;
+}
+
+export interface NewsFeed {
+  id: string;
+  name: string;
+  url: string;
+  category: string;
+  enabled: boolean;
+}
+
+export interface NewsFeedRegistry {
+  feeds: NewsFeed[];
+}
+
+export interface NewsFeedTest {
+  format: "rss" | "atom";
+  title: string | null;
+  item_count: number;
+  sample_titles: string[];
 }
 
 // API Functions
 
 /**
- * Get keyword suggestions based on input
+ * Get keyword suggestions based on input.
+ * Mixes autocomplete, terms co-occurring in PubMed/CrossRef/news results and past research
+ * prompts (of projectId, or of all projects).
  */
-export async function suggestKeywords(keyword: string): Promise<KeywordSuggestion[]> {
-  return invoke<KeywordSuggestion[]>("suggest_keywords", { keyword });
+export async function suggestKeywords(
+  keyword: string,
+  projectId?: string,
+  limit?: number
+): Promise<KeywordSuggestion[]> {
+  return invoke<KeywordSuggestion[]>("suggest_keywords", { keyword, projectId, limit });
+}
+
+/**
+ * Recorded trend series for a keyword (no network access)
+ */
+export async function getKeywordTrend(keyword: string): Promise<KeywordTrend> {
+  return invoke<KeywordTrend>("get_keyword_trend", { keyword });
+}
+
+/**
+ * Fetch yearly PubMed/CrossRef counts and current news for a keyword, record them and reclassify
+ */
+export async function refreshKeywordTrend(keyword: string): Promise<KeywordTrend> {
+  return invoke<KeywordTrend>("refresh_keyword_trend", { keyword });
 }
 
 /**
  * Search for academic papers
  */
-export async function searchPapers(keyword: string, limit?: number): Promise<PaperResult[]> {
-  return invoke<PaperResult[]>("search_papers", { keyword, limit });
+export async function searchPapers(
+  keyword: string,
+  limit?: number,
+  filters?: PaperSearchFilters
+): Promise<PaperResult[]> {
+  return invoke<PaperResult[]>("search_papers", { keyword, limit, filters });
 }
 
 /**
@@ -287,6 +444,10 @@ export interface ConferenceSearchResult {
   source: string;
   doi: string | null;
   url: string | null;
+  pmid?: string | null;
+  pmcid?: string | null;
+  citationCount?: number | null;
+  openAccessUrl?: string | null;
 }
 
 export interface NewsSearchResult {
@@ -294,7 +455,23 @@ export interface NewsSearchResult {
   description: string;
   link: string;
   pubDate: string;
+  /** RFC 3339, null when the feed's date could not be read */
+  published_at: string | null;
   source: string;
+  category: string;
+  /** How well the article matches the keyword (0-1) */
+  relevance: number;
+  /** Other feeds that carried the same story */
+  other_sources: string[];
+}
+
+export interface NewsSearchFilters {
+  /** "YYYY-MM-DD" */
+  date_from?: string | null;
+  date_to?: string | null;
+  /** Published within the last N days */
+  days?: number | null;
+  sort?: "relevance" | "newest";
 }
 
 /**
@@ -319,19 +496,61 @@ export async function searchConferences(
 }
 
 /**
- * Search news using RSS feeds (Yonhap, CNN)
+ * Search PubMed, CrossRef and the web (when googleCx and a Google key are set) at once.
+ * Duplicates are merged and records are ranked by relevance, evidence strength and recency;
+ * sources that failed are listed in `failures`.
  */
-export async function searchNews(keyword: string): Promise<NewsSearchResult[]> {
-  return invoke<NewsSearchResult[]>("search_news", { keyword });
+export async function searchLiterature(
+  keyword: string,
+  options?: {
+    limit?: number;
+    sources?: ("pubmed" | "crossref" | "web")[];
+    filters?: PaperSearchFilters;
+    googleCx?: string;
+  }
+): Promise<LiteratureSearchResult> {
+  return invoke<LiteratureSearchResult>("search_literature", { keyword, ...options });
+}
+
+/**
+ * Merge paper and conference results into one list; records sharing a DOI, PMID or PMCID
+ * become one record.
+ */
+export async function mergePaperResults(
+  papers: PaperResult[],
+  conferences: ConferenceSearchResult[]
+): Promise<PaperResult[]> {
+  return invoke<PaperResult[]>("merge_paper_results", { papers, conferences });
 }
 
 /**
- * Generate content plan
+ * Search news in the enabled feeds of the registry, optionally only feeds of the given categories.
+ * With a date window in filters, articles without a readable date are left out.
+ */
+export async function searchNews(
+  keyword: string,
+  categories?: string[],
+  filters?: NewsSearchFilters
+): Promise<NewsSearchResult[]> {
+  return invoke<NewsSearchResult[]>("search_news", {
+    keyword,
+    categories: categories ?? null,
+    filters: filters ?? null,
+  });
+}
+
+/**
+ * Generate content plan.
+ * While it runs, the calling window receives "llm:token" ({ streamId, delta }) and
+ * "content-plan:item" ({ streamId, index, item }) events for the given streamId.
+ * If the final plan differs from the streamed items (the reply had to be repaired),
+ * "content-plan:replace" ({ streamId, items }) replaces all of them.
  */
 export async function generateContentPlan(
-  request: ContentGenerationRequest
+  request: ContentGenerationRequest,
+  streamId?: string
 ): Promise<ContentPlanItem[]> {
-  return invoke<ContentPlanItem[]>("generate_content_plan", { request });
+  return invoke<ContentPlanItem[]>("generate_content_plan", { request, streamId });
 }
 
 /**
@@ -363,7 +582,8 @@ export async function generateImage(
 }
 
 /**
- * Generate multiple images in batch
+ * Queue a batch of images as a background job; returns the job id.
+ * Progress arrives through the "job:progress", "job:completed", "job:failed" and "job:finished" events.
  */
 export async function generateBatchImages(
   requests: ImageGenerationRequest[],
@@ -372,8 +592,8 @@ export async function generateBatchImages(
   model?: string,
   aspectRatio?: string,
   negativePrompt?: string
-): Promise<GeneratedImage[]> {
-  return invoke<GeneratedImage[]>("generate_batch_images", {
+): Promise<string> {
+  return invoke<string>("generate_batch_images", {
     requests,
     apiKey,
     provider,
@@ -389,20 +609,23 @@ export async function generateBatchImages(
 export async function downloadImage(
   imageUrl: string,
   savePath: string,
-  withText?: boolean
+  withText?: boolean,
+  content?: ContentItem,
+  layoutTemplateId?: string
 ): Promise<string> {
-  return invoke<string>("download_image", { imageUrl, savePath, withText });
+  return invoke<string>("download_image", { imageUrl, savePath, withText, content, layoutTemplateId });
 }
 
 /**
- * Download all images to local storage
+ * Download all images to local storage; with text, `contentGroup` supplies the slide text and layouts
  */
 export async function downloadAllImages(
   images: GeneratedImage[],
   basePath: string,
-  withText?: boolean
+  withText?: boolean,
+  contentGroup?: ContentGroup
 ): Promise<string[]> {
-  return invoke<string[]>("download_all_images", { images, basePath, withText });
+  return invoke<string[]>("download_all_images", { images, basePath, withText, contentGroup });
 }
 
 /**
@@ -426,6 +649,42 @@ export async function saveApiKeys(keys: AppSettings["apiKeys"]): Promise<void> {
   return invoke<void>("save_api_keys", { keys });
 }
 
+/**
+ * Save the NCBI API key and tool/email identification sent with PubMed requests.
+ */
+export async function saveNcbiSettings(ncbi: NcbiSettings): Promise<void> {
+  return invoke<void>("save_ncbi_settings", { ncbi });
+}
+
+/**
+ * List the news feeds (RSS or Atom) searched by searchNews
+ */
+export async function listNewsFeeds(): Promise<NewsFeed[]> {
+  return invoke<NewsFeed[]>("list_news_feeds");
+}
+
+/**
+ * Add a news feed; a blank category becomes "general"
+ */
+export async function addNewsFeed(name: string, url: string, category: string): Promise<NewsFeed> {
+  return invoke<NewsFeed>("add_news_feed", { name, url, category });
+}
+
+export async function removeNewsFeed(feedId: string): Promise<void> {
+  return invoke<void>("remove_news_feed", { feedId });
+}
+
+export async function setNewsFeedEnabled(feedId: string, enabled: boolean): Promise<void> {
+  return invoke<void>("set_news_feed_enabled", { feedId, enabled });
+}
+
+/**
+ * Download a feed URL and report its format, title and first article titles
+ */
+export async function testNewsFeed(url: string): Promise<NewsFeedTest> {
+  return invoke<NewsFeedTest>("test_news_feed", { url });
+}
+
 /**
  * Get save path
  */
@@ -529,6 +788,254 @@ export async function generatePromptFromImage(
   });
 }
 
+// ============================================
+// Ingredient Dictionary
+// ============================================
+
+export interface Ingredient {
+  id: string;
+  inci_name: string;
+  /** 화장품 표준 성분명 */
+  korean_name: string;
+  aliases: string[];
+  cas_numbers: string[];
+  ec_numbers: string[];
+  functions: string[];
+}
+
+export interface IngredientMatch {
+  ingredient: Ingredient;
+  matched_name: string;
+  score: number;
+}
+
+export interface IngredientImportSummary {
+  added: number;
+  updated: number;
+  total: number;
+}
+
+/**
+ * Fuzzy search by INCI name, Korean name, alias, CAS or EC number
+ */
+export async function lookupIngredient(query: string, limit?: number): Promise<IngredientMatch[]> {
+  return invoke<IngredientMatch[]>("lookup_ingredient", { query, limit });
+}
+
+/**
+ * Ingredients whose name starts with the typed text (a Hangul syllable still being typed also matches)
+ */
+export async function autocompleteIngredient(prefix: string, limit?: number): Promise<IngredientMatch[]> {
+  return invoke<IngredientMatch[]>("autocomplete_ingredient", { prefix, limit });
+}
+
+/**
+ * The dictionary entry a free-text name means, or null when it is unknown or ambiguous
+ */
+export async function resolveIngredient(name: string): Promise<Ingredient | null> {
+  return invoke<Ingredient | null>("resolve_ingredient", { name });
+}
+
+/**
+ * Merge a JSON dataset ({"ingredients": [...]}, same format as the bundled seed data) into the dictionary
+ */
+export async function importIngredientDataset(path: string): Promise<IngredientImportSummary> {
+  return invoke<IngredientImportSummary>("import_ingredient_dataset", { path });
+}
+
+// ============================================
+// Regulatory Data
+// ============================================
+
+export type RegulatoryList =
+  | "mfds_prohibited"
+  | "mfds_restricted"
+  | "mfds_allergen"
+  | "eu_annex_ii"
+  | "eu_annex_iii"
+  | "eu_annex_v"
+  | "eu_annex_vi"
+  | "pregnancy_caution"
+  | "infant_caution";
+
+export interface RegulatorySource {
+  id: string;
+  title: string;
+  issuer: string;
+  url: string | null;
+  note: string | null;
+}
+
+export interface RegulatoryFact {
+  list: RegulatoryList;
+  status: "prohibited" | "restricted" | "caution";
+  max_concentration: string | null;
+  conditions: string | null;
+  /** Entry in the source, e.g. "Annex V/29", "별표 2" */
+  reference: string | null;
+  source: RegulatorySource;
+}
+
+export interface RegulatoryReport {
+  ingredient: Ingredient | null;
+  facts: RegulatoryFact[];
+}
+
+export interface RegulatoryImportSummary {
+  sources: number;
+  rules: number;
+  unmatched_ingredients: string[];
+}
+
+/**
+ * MFDS, EU annex and pregnancy/infant list entries for an ingredient, each with its source
+ */
+export async function getRegulatoryStatus(ingredientName: string): Promise<RegulatoryReport> {
+  return invoke<RegulatoryReport>("get_regulatory_status", { ingredientName });
+}
+
+/**
+ * Merge a JSON dataset ({"sources": [...], "rules": [...]}) into the regulatory data.
+ * Its rules replace existing rules of the same ingredient and list.
+ */
+export async function importRegulatoryDataset(path: string): Promise<RegulatoryImportSummary> {
+  return invoke<RegulatoryImportSummary>("import_regulatory_dataset", { path });
+}
+
+// ============================================
+// Ingredient Compatibility
+// ============================================
+
+export interface InteractionSource {
+  id: string;
+  title: string;
+  url: string | null;
+  note: string | null;
+}
+
+export type Compatibility = "synergistic" | "neutral" | "cautionary";
+
+export interface IngredientPairing {
+  first: Ingredient;
+  second: Ingredient;
+  compatibility: Compatibility;
+  rationale: string;
+  advice: string | null;
+  /** Part of the source, e.g. "Annex V" */
+  reference: string | null;
+  source: InteractionSource;
+}
+
+export interface CompatibilityReport {
+  ingredients: Ingredient[];
+  unresolved: string[];
+  /** Cautionary pairs first, then synergistic, then neutral */
+  pairings: IngredientPairing[];
+}
+
+/**
+ * Which of the given ingredients are synergistic, fine or cautionary together, each with a rationale and source
+ */
+export async function checkIngredientCompatibility(ingredients: string[]): Promise<CompatibilityReport> {
+  return invoke<CompatibilityReport>("check_ingredient_compatibility", { ingredients });
+}
+
+/**
+ * Everything the pairing rules say about one ingredient with others
+ */
+export async function getIngredientPairings(ingredientName: string): Promise<IngredientPairing[]> {
+  return invoke<IngredientPairing[]>("get_ingredient_pairings", { ingredientName });
+}
+
+// ============================================
+// Ingredient Label Analysis
+// ============================================
+
+export type LabelFlag =
+  | "fragrance"
+  | "allergen"
+  | "prohibited"
+  | "restricted"
+  | "pregnancy_caution"
+  | "infant_caution";
+
+export interface LabelEntry {
+  /** 1-based; labels list ingredients by decreasing content */
+  position: number;
+  name: string;
+  ingredient: Ingredient | null;
+  /** For unknown names, a similar dictionary entry to suggest; nothing is checked against it */
+  suggestion?: string | null;
+  flags: LabelFlag[];
+  regulatory_facts: RegulatoryFact[];
+}
+
+export interface LabelCombination {
+  ingredients: string[];
+  reason: string;
+  advice: string | null;
+  source: InteractionSource;
+}
+
+export interface LabelReport {
+  product_name: string | null;
+  entries: LabelEntry[];
+  unresolved: string[];
+  combinations: LabelCombination[];
+  summary: string;
+}
+
+/**
+ * Check a whole product ingredient list (전성분) pasted from packaging, in Korean or English
+ */
+export async function analyzeIngredientLabel(labelText: string, productName?: string): Promise<LabelReport> {
+  return invoke<LabelReport>("analyze_ingredient_label", { labelText, productName });
+}
+
+/** Position in a photo as fractions (0-1) of the image width and height from the top left */
+export interface BoundingBox {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+export interface LabelOcrEntry {
+  position: number;
+  /** What the model read, before cleaning */
+  text: string;
+  name: string;
+  ingredient: Ingredient | null;
+  confidence: number;
+  bounding_box: BoundingBox | null;
+  /** Low confidence or unknown to the dictionary */
+  needs_review: boolean;
+}
+
+export interface LabelOcrResult {
+  entries: LabelOcrEntry[];
+  /** Comma-separated list, ready for analyzeIngredientLabel */
+  label_text: string;
+}
+
+/**
+ * Read the ingredient list from a photo of product packaging with the provider's vision model
+ */
+export async function extractIngredientLabel(
+  imagePath: string,
+  apiKey?: string,
+  provider?: string
+): Promise<LabelOcrResult> {
+  return invoke<LabelOcrResult>("extract_ingredient_label", { imagePath, apiKey, provider });
+}
+
+/**
+ * Save a label report to a project as a research item; returns the saved item
+ */
+export async function saveLabelResearch(projectId: string, report: LabelReport): Promise<ResearchItem> {
+  return invoke<ResearchItem>("save_label_research", { projectId, report });
+}
+
 // ============================================
 // Project Management API
 // ============================================
@@ -677,6 +1184,18 @@ export async function saveContentGroup(projectId: string, group: ContentGroup):
   }
 }
 
+/**
+ * Assign a layout template to a content item (null clears it back to the slide-role default)
+ */
+export async function assignContentLayout(
+  projectId: string,
+  groupId: string,
+  contentId: string,
+  templateId: string | null
+): Promise<ContentGroup> {
+  return invoke<ContentGroup>("assign_content_layout", { projectId, groupId, contentId, templateId });
+}
+
 // Local storage helpers
 const PROJECTS_STORAGE_KEY = "moms_insta_projects";
 
@@ -742,12 +1261,14 @@ export async function openExternalUrl(url: string): Promise<void> {
 }
 
 /**
- * Translate text to Korean using LLM
+ * Translate text to Korean using LLM.
+ * With a streamId, partial output arrives as "llm:token" events.
  */
 export async function translateToKorean(
   text: string,
   apiKey: string,
-  provider: string
+  provider: string,
+  streamId?: string
 ): Promise<string> {
-  return invoke<string>("translate_to_korean", { text, apiKey, provider });
+  return invoke<string>("translate_to_korean", { text, apiKey, provider, streamId });
 }
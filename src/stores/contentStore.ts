@@ -62,6 +62,7 @@ interface ContentState {
   loadContentGroup: (groupId: string) => void;
   deleteContentGroup: (groupId: string) => void;
   setSelectedGroupId: (id: string | null) => void;
+  assignContentLayout: (groupId: string, contentId: string, templateId: string | null) => Promise<void>;
 }
 
 // Helper function to create character name from keyword
@@ -373,6 +374,7 @@ ${researchData ? `\n자료조사 데이터:\n${researchData}` : ""}
           researchItemIds,
           contents: contentItems,
           createdAt: now,
+          itemLayoutTemplates: {},
         };
 
         set((state) => ({
@@ -424,6 +426,18 @@ ${researchData ? `\n자료조사 데이터:\n${researchData}` : ""}
       setSelectedGroupId: (id: string | null) => {
         set({ selectedGroupId: id });
       },
+
+      assignContentLayout: async (groupId, contentId, templateId) => {
+        const { currentProject } = useProjectStore.getState();
+        if (!currentProject) return;
+
+        const updated = await tauriApi.assignContentLayout(currentProject.id, groupId, contentId, templateId);
+        set((state) => ({
+          contentGroups: state.contentGroups.map((g) =>
+            g.id === groupId ? { ...g, itemLayoutTemplates: updated.itemLayoutTemplates ?? {} } : g
+          ),
+        }));
+      },
     }),
     { name: "content-store" }
   )
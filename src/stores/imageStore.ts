@@ -5,8 +5,7 @@ import { useSettingsStore, googleImageModelOptions, aspectRatioOptions } from ".
 import { useContentStore } from "./contentStore";
 import { useProjectStore } from "./projectStore";
 import { useApiPreviewStore } from "./apiPreviewStore";
-import { renderLayeredImage } from "@/utils/canvasRenderer";
-import { convertFileSrc } from "@tauri-apps/api/core";
+import type { ContentGroup } from "./projectStore";
 
 export interface GeneratedImage {
   id: string;
@@ -41,6 +40,37 @@ export interface ImageGenerationStatus {
   actualApiCallInfo?: ActualApiCallInfo;
 }
 
+/**
+ * The content group the images belong to, as drawn on the slides: text edited on an image replaces the
+ * generated text. Content that was never saved to a group is sent as an unsaved group.
+ */
+function slideGroup(images: GeneratedImage[]): ContentGroup {
+  const { items, contentGroups, selectedGroupId } = useContentStore.getState();
+  const group = contentGroups.find((g) => g.id === selectedGroupId);
+  const overlays = new Map(
+    images.filter((img) => img.textOverlay).map((img) => [img.contentId, img.textOverlay!])
+  );
+
+  return {
+    id: group?.id ?? "unsaved",
+    name: group?.name ?? "",
+    researchItemIds: group?.researchItemIds ?? [],
+    createdAt: group?.createdAt ?? new Date().toISOString(),
+    itemLayoutTemplates: group?.itemLayoutTemplates ?? {},
+    contents: items.map((item) => ({
+      id: item.id,
+      title: item.title,
+      characterName: item.characterName,
+      journalNumber: item.journalNumber,
+      content: item.content,
+      imageConcept: item.imageConcept,
+      status: item.status,
+      generatedImageId: item.generatedImageUrl,
+      ...overlays.get(item.id),
+    })),
+  };
+}
+
 interface ImageState {
   // State
   images: GeneratedImage[];
@@ -596,58 +626,19 @@ ${requests.map((r, i) => {
           const savePath = `${basePath}/carousel_${String(currentIndex + 1).padStart(2, "0")}.png`;
 
           if (withText) {
-            // Prioritize textOverlay (edited content) over contentItems
-            const contentItems = useContentStore.getState().items;
-            const originalContent = contentItems.find((item) => item.id === currentImage.contentId);
-            const content = currentImage.textOverlay || (originalContent ? {
-              characterName: originalContent.characterName,
-              journalNumber: originalContent.journalNumber,
-              title: originalContent.title,
-              content: originalContent.content,
-            } : null);
-
+            // The slide is composited in Rust with the item's layout template
+            const group = slideGroup(images);
+            const content = group.contents.find((item) => item.id === currentImage.contentId);
             if (!content) {
               throw new Error("콘텐츠를 찾을 수 없습니다");
             }
-
-            // Get layout preset
-            const layoutSettings = settings.layoutSettings;
-            const currentPreset = layoutSettings.presets.find(
-              (p) => p.id === layoutSettings.selectedPresetId
-            );
-
-            if (!currentPreset) {
-              throw new Error("레이아웃 프리셋을 찾을 수 없습니다");
-            }
-
-            // Get image size
-            const sizePreset = settings.imageSizePresets.find(
-              (p) => p.id === currentPreset.imageSizePresetId
-            );
-            const width = sizePreset?.width || 1080;
-            const height = sizePreset?.height || 1350;
-
-            // Get displayable image URL
-            let imageUrl = currentImage.url;
-            if (currentImage.localPath) {
-              try {
-                imageUrl = convertFileSrc(currentImage.localPath);
-              } catch {
-                console.warn("Failed to convert local path, using URL");
-              }
-            }
-
-            // Render layered image
-            const dataUrl = await renderLayeredImage({
-              imageUrl,
-              preset: currentPreset,
+            await tauriApi.downloadImage(
+              currentImage.localPath || currentImage.url,
+              savePath,
+              true,
               content,
-              width,
-              height,
-            });
-
-            // Save data URL directly (download_image handles data URLs)
-            await tauriApi.downloadImage(dataUrl, savePath, false);
+              group.itemLayoutTemplates?.[content.id]
+            );
           } else {
             // Download original image without text
             await tauriApi.downloadImage(currentImage.url, savePath, false);
@@ -679,92 +670,24 @@ ${requests.map((r, i) => {
             }
           }
 
-          if (withText) {
-            // Render each image with layers using canvas
-            const contentItems = useContentStore.getState().items;
-            const layoutSettings = settings.layoutSettings;
-            const currentPreset = layoutSettings.presets.find(
-              (p) => p.id === layoutSettings.selectedPresetId
-            );
-
-            if (!currentPreset) {
-              throw new Error("레이아웃 프리셋을 찾을 수 없습니다");
-            }
-
-            // Get image size
-            const sizePreset = settings.imageSizePresets.find(
-              (p) => p.id === currentPreset.imageSizePresetId
-            );
-            const width = sizePreset?.width || 1080;
-            const height = sizePreset?.height || 1350;
-
-            let savedCount = 0;
-            for (let i = 0; i < images.length; i++) {
-              const image = images[i];
-              const originalContent = contentItems.find((item) => item.id === image.contentId);
-
-              // Prioritize textOverlay (edited content) over contentItems
-              const content = image.textOverlay || (originalContent ? {
-                characterName: originalContent.characterName,
-                journalNumber: originalContent.journalNumber,
-                title: originalContent.title,
-                content: originalContent.content,
-              } : null);
-
-              if (!content) {
-                console.warn(`Content not found for image ${image.id}`);
-                continue;
-              }
-
-              // Get displayable image URL
-              let imageUrl = image.url;
-              if (image.localPath) {
-                try {
-                  imageUrl = convertFileSrc(image.localPath);
-                } catch {
-                  console.warn("Failed to convert local path, using URL");
-                }
-              }
-
-              try {
-                // Render layered image
-                const dataUrl = await renderLayeredImage({
-                  imageUrl,
-                  preset: currentPreset,
-                  content,
-                  width,
-                  height,
-                });
-
-                // Save data URL directly (download_image handles data URLs)
-                const savePath = `${basePath}/carousel_${String(i + 1).padStart(2, "0")}.png`;
-                await tauriApi.downloadImage(dataUrl, savePath, false);
-                savedCount++;
-              } catch (error) {
-                console.error(`Failed to render/save image ${i + 1}:`, error);
-              }
-            }
-
-            alert(`${savedCount}개의 이미지가 저장되었습니다.`);
-          } else {
-            // Download original images without text
-            const apiImages: tauriApi.GeneratedImage[] = images.map((img) => ({
-              id: img.id,
-              content_id: img.contentId,
-              url: img.url,
-              local_path: img.localPath ?? null,
-              width: 1024,
-              height: 1024,
-            }));
+          const apiImages: tauriApi.GeneratedImage[] = images.map((img) => ({
+            id: img.id,
+            content_id: img.contentId,
+            url: img.url,
+            local_path: img.localPath ?? null,
+            width: 1024,
+            height: 1024,
+          }));
 
-            const savedPaths = await tauriApi.downloadAllImages(
-              apiImages,
-              basePath,
-              false
-            );
+          // With text, each slide is composited in Rust with the layout template of its place in the group
+          const savedPaths = await tauriApi.downloadAllImages(
+            apiImages,
+            basePath,
+            withText,
+            withText ? slideGroup(images) : undefined
+          );
 
-            alert(`${savedPaths.length}개의 이미지가 저장되었습니다.`);
-          }
+          alert(`${savedPaths.length}개의 이미지가 저장되었습니다.`);
         } catch (error) {
           console.error("Failed to download images:", error);
           alert("이미지 다운로드에 실패했습니다. 설정에서 저장 경로를 확인해주세요.");
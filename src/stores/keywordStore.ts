@@ -6,6 +6,7 @@ import { useContentStore } from "./contentStore";
 import { useProjectStore } from "./projectStore";
 import { useApiPreviewStore } from "./apiPreviewStore";
 import type { ResearchItem, SourceReference } from "./projectStore";
+import type { RegulatoryFact } from "@/services/tauriApi";
 
 export interface KeywordSuggestion {
   id: string;
@@ -24,6 +25,24 @@ export interface PaperResult {
   citationCount?: number;
   doi?: string;
   url?: string;
+  openAccessUrl?: string;
+  evidence?: EvidenceAssessment;
+}
+
+export interface EvidenceAssessment {
+  grade: string; // "A" (strongest) .. "D"
+  studyDesign: string;
+  subject: string; // "human" | "animal" | "in_vitro" | "unknown"
+  sampleSize?: number;
+  year?: number;
+  weak: boolean;
+}
+
+export interface BenefitEvidence {
+  benefit: string;
+  paperIds: string[];
+  grade?: string;
+  weakEvidence: boolean;
 }
 
 export interface IngredientAnalysis {
@@ -34,8 +53,72 @@ export interface IngredientAnalysis {
   cautions: string[];
   recommendedConcentration?: string;
   relatedPapers: PaperResult[];
+  benefitEvidence?: BenefitEvidence[];
+  /** Sourced MFDS/EU/pregnancy list entries from the local regulatory data, not written by the LLM */
+  regulatoryFacts?: RegulatoryFact[];
 }
 
+// Backend sends snake_case; accept camelCase too
+const toEvidence = (e: any): EvidenceAssessment | undefined =>
+  e && typeof e === "object"
+    ? {
+        grade: e.grade || "D",
+        studyDesign: e.studyDesign || e.study_design || "unknown",
+        subject: e.subject || "unknown",
+        sampleSize: e.sampleSize ?? e.sample_size ?? undefined,
+        year: e.year ?? undefined,
+        weak: Boolean(e.weak),
+      }
+    : undefined;
+
+// 약한 근거(C/D 등급 또는 근거 논문 없음)만 있는 효능은 콘텐츠 생성 시 단정하지 않도록 표시
+const labelWeakBenefits = (analysis: {
+  benefits: string[];
+  benefitEvidence?: { benefit: string; weakEvidence: boolean }[];
+}): string[] =>
+  analysis.benefits.map((benefit) =>
+    analysis.benefitEvidence?.find((b) => b.benefit === benefit)?.weakEvidence ? `${benefit} (근거 약함)` : benefit
+  );
+
+const toRegulatoryFacts = (raw: any): RegulatoryFact[] =>
+  Array.isArray(raw?.regulatoryFacts) ? raw.regulatoryFacts : Array.isArray(raw?.regulatory_facts) ? raw.regulatory_facts : [];
+
+// "식품의약품안전처 별표 2: 최대 1% (보존제로서) [출처: 화장품 안전기준 등에 관한 규정]"
+export const regulatoryNote = (fact: RegulatoryFact): string => {
+  const detail = [
+    fact.max_concentration ? `최대 ${fact.max_concentration}` : null,
+    fact.conditions ? `(${fact.conditions})` : null,
+  ].filter(Boolean);
+  const head = [fact.source.issuer, fact.reference].filter(Boolean).join(" ");
+  return `${head}${detail.length > 0 ? `: ${detail.join(" ")}` : ""} [출처: ${fact.source.title}]`;
+};
+
+// 콘텐츠 생성용 연구 요약; 규제 정보는 있을 때만 붙입니다
+const analysisSummary = (analysis: IngredientAnalysis): string => {
+  const lines = [
+    `성분: ${analysis.ingredientName}`,
+    `EWG 등급: ${analysis.ewgScore ?? "N/A"}`,
+    `효능: ${labelWeakBenefits(analysis).join(", ")}`,
+    `주의사항: ${analysis.cautions.join(", ")}`,
+  ];
+  const notes = (analysis.regulatoryFacts ?? []).map(regulatoryNote);
+  if (notes.length > 0) {
+    lines.push(`규제 정보:\n${notes.map((n) => `- ${n}`).join("\n")}`);
+  }
+  return lines.join("\n");
+};
+
+const toBenefitEvidence = (raw: any): BenefitEvidence[] =>
+  (Array.isArray(raw?.benefitEvidence) ? raw.benefitEvidence : Array.isArray(raw?.benefit_evidence) ? raw.benefit_evidence : []).map(
+    (b: any) => ({
+      benefit: b?.benefit || "",
+      paperIds: Array.isArray(b?.paperIds) ? b.paperIds : Array.isArray(b?.paper_ids) ? b.paper_ids : [],
+      grade: b?.grade ?? undefined,
+      weakEvidence: Boolean(b?.weakEvidence ?? b?.weak_evidence),
+    })
+  );
+
+
 export interface ResearchProgress {
   isOpen: boolean;
   currentStep: string;
@@ -264,6 +347,9 @@ export const useKeywordStore = create<KeywordState>()(
             source: p?.source || "",
             citationCount: p?.citationCount ?? p?.citation_count ?? undefined,
             doi: p?.doi ?? undefined,
+            openAccessUrl: p?.openAccessUrl ?? p?.open_access_url ?? undefined,
+            evidence: toEvidence(p?.evidence),
+            url: p?.url ?? undefined,
           }));
 
           let analysis: IngredientAnalysis | null = null;
@@ -292,13 +378,17 @@ export const useKeywordStore = create<KeywordState>()(
                 source: p?.source || "",
                 citationCount: p?.citationCount ?? p?.citation_count ?? undefined,
                 doi: p?.doi ?? undefined,
+                url: p?.url ?? undefined,
+                evidence: toEvidence(p?.evidence),
               })),
+              benefitEvidence: toBenefitEvidence(analysisResult),
+              regulatoryFacts: toRegulatoryFacts(analysisResult),
             };
           }
 
           // Store research data for content generation
           const researchSummary = analysis
-            ? `성분: ${analysis.ingredientName}\nEWG 등급: ${analysis.ewgScore ?? "N/A"}\n효능: ${analysis.benefits.join(", ")}\n주의사항: ${analysis.cautions.join(", ")}`
+            ? analysisSummary(analysis)
             : "";
 
           useContentStore.getState().setResearchData(researchSummary);
@@ -549,12 +639,16 @@ JSON 형식으로 응답해주세요.`;
             source: p?.source || "",
             citationCount: p?.citationCount ?? p?.citation_count ?? undefined,
             doi: p?.doi ?? undefined,
-            // doi 필드가 이미 전체 URL인 경우 그대로 사용, 아니면 DOI URL 생성
-            url: p?.doi
-              ? p.doi.startsWith("http")
-                ? p.doi
-                : `https://doi.org/${p.doi}`
-              : undefined,
+            openAccessUrl: p?.openAccessUrl ?? p?.open_access_url ?? undefined,
+            evidence: toEvidence(p?.evidence),
+            // 원문 링크가 있으면 사용, 없으면 DOI로 URL 생성 (doi 필드가 이미 전체 URL인 경우 그대로)
+            url:
+              p?.url ??
+              (p?.doi
+                ? p.doi.startsWith("http")
+                  ? p.doi
+                  : `https://doi.org/${p.doi}`
+                : undefined),
           }));
 
           // Create sources from all results
@@ -615,7 +709,11 @@ JSON 형식으로 응답해주세요.`;
                 source: p?.source || "",
                 citationCount: p?.citationCount ?? p?.citation_count ?? undefined,
                 doi: p?.doi ?? undefined,
+                url: p?.url ?? undefined,
+                evidence: toEvidence(p?.evidence),
               })),
+              benefitEvidence: toBenefitEvidence(analysisResult),
+              regulatoryFacts: toRegulatoryFacts(analysisResult),
             };
           }
 
@@ -628,7 +726,7 @@ JSON 형식으로 응답해주세요.`;
           const resultCountsText = resultCounts.length > 0 ? resultCounts.join(", ") : "결과 없음";
 
           const summary = ingredientAnalysis
-            ? `${ingredientAnalysis.koreanName}(${ingredientAnalysis.ingredientName})은(는) ${ingredientAnalysis.benefits.slice(0, 3).join(", ")} 등의 효능이 있습니다. EWG 등급: ${ingredientAnalysis.ewgScore ?? "N/A"} (${resultCountsText})`
+            ? `${ingredientAnalysis.koreanName}(${ingredientAnalysis.ingredientName})은(는) ${labelWeakBenefits(ingredientAnalysis).slice(0, 3).join(", ")} 등의 효능이 있습니다. EWG 등급: ${ingredientAnalysis.ewgScore ?? "N/A"} (${resultCountsText})`
             : `${searchKeyword}에 대한 검색 결과: ${resultCountsText}`;
 
           // Create ResearchItem
@@ -646,6 +744,13 @@ JSON 형식으로 응답해주세요.`;
                 benefits: ingredientAnalysis.benefits,
                 cautions: ingredientAnalysis.cautions,
                 recommendedConcentration: ingredientAnalysis.recommendedConcentration ?? null,
+                benefitEvidence: (ingredientAnalysis.benefitEvidence ?? []).map((b) => ({
+                  benefit: b.benefit,
+                  paperIds: b.paperIds,
+                  grade: b.grade ?? null,
+                  weakEvidence: b.weakEvidence,
+                })),
+                regulatoryNotes: (ingredientAnalysis.regulatoryFacts ?? []).map(regulatoryNote),
               } : null,
               papers: papers.map((p) => ({
                 id: p.id,
@@ -657,6 +762,10 @@ JSON 형식으로 응답해주세요.`;
                 citationCount: p.citationCount ?? null,
                 doi: p.doi ?? null,
                 url: p.url,
+                openAccessUrl: p.openAccessUrl ?? null,
+                evidence: p.evidence
+                  ? { ...p.evidence, sampleSize: p.evidence.sampleSize ?? null, year: p.evidence.year ?? null }
+                  : null,
               })),
               conferences: conferenceResults.map((c) => ({
                 id: c.id,
@@ -687,7 +796,7 @@ JSON 형식으로 응답해주세요.`;
 
           // Store research data for content generation
           const researchSummary = ingredientAnalysis
-            ? `성분: ${ingredientAnalysis.ingredientName}\nEWG 등급: ${ingredientAnalysis.ewgScore ?? "N/A"}\n효능: ${ingredientAnalysis.benefits.join(", ")}\n주의사항: ${ingredientAnalysis.cautions.join(", ")}`
+            ? analysisSummary(ingredientAnalysis)
             : "";
 
           useContentStore.getState().setResearchData(researchSummary);
@@ -829,8 +938,12 @@ JSON 형식으로 응답해주세요.`;
         if (report.ingredientAnalysis) {
           parts.push(`성분: ${report.ingredientAnalysis.ingredientName}`);
           parts.push(`EWG 등급: ${report.ingredientAnalysis.ewgScore ?? "N/A"}`);
-          parts.push(`효능: ${report.ingredientAnalysis.benefits.join(", ")}`);
+          parts.push(`효능: ${labelWeakBenefits(report.ingredientAnalysis).join(", ")}`);
           parts.push(`주의사항: ${report.ingredientAnalysis.cautions.join(", ")}`);
+          const notes = report.ingredientAnalysis.regulatoryNotes ?? [];
+          if (notes.length > 0) {
+            parts.push(`규제 정보:\n${notes.map((n) => `- ${n}`).join("\n")}`);
+          }
         }
 
         // 논문 (제외되지 않은 것만)
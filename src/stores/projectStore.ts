@@ -23,6 +23,26 @@ export interface ResearchReport {
   webResults: WebResult[];
   news: NewsResult[];
   sources: SourceReference[];
+  labelAnalysis?: LabelAnalysis | null;
+}
+
+export interface LabelAnalysis {
+  productName: string | null;
+  entries: {
+    position: number;
+    name: string;
+    inciName: string | null;
+    koreanName: string | null;
+    flags: string[];
+    notes: string[];
+  }[];
+  combinations: {
+    ingredients: string[];
+    reason: string;
+    advice?: string | null;
+    source?: string | null;
+  }[];
+  unresolved: string[];
 }
 
 export interface ConferenceResult {
@@ -56,6 +76,14 @@ export interface IngredientAnalysis {
   benefits: string[];
   cautions: string[];
   recommendedConcentration: string | null;
+  benefitEvidence?: {
+    benefit: string;
+    paperIds: string[];
+    grade: string | null;
+    weakEvidence: boolean;
+  }[];
+  /** One line per sourced regulatory fact (MFDS/EU/pregnancy lists) */
+  regulatoryNotes?: string[];
 }
 
 export interface PaperResult {
@@ -68,6 +96,15 @@ export interface PaperResult {
   citationCount: number | null;
   doi: string | null;
   url?: string;
+  openAccessUrl?: string | null;
+  evidence?: {
+    grade: string;
+    studyDesign: string;
+    subject: string;
+    sampleSize: number | null;
+    year: number | null;
+    weak: boolean;
+  } | null;
 }
 
 export interface SourceReference {
@@ -84,6 +121,8 @@ export interface ContentGroup {
   researchItemIds: string[];
   contents: ContentItem[];
   createdAt: string;
+  /** Content item id -> layout template id; unassigned items use the slide-role template */
+  itemLayoutTemplates?: Record<string, string>;
 }
 
 export interface ContentItem {
@@ -307,10 +346,15 @@ export const useProjectStore = create<ProjectState>()(
                   generatedImageId: item.generatedImageUrl,
                 })),
                 createdAt: new Date().toISOString(),
+                itemLayoutTemplates: {},
               };
               contentGroups.push(newGroup);
             } else {
-              // Update existing group with current items
+              // Update existing group with current items, keeping template assignments of items that remain
+              const itemIds = new Set(currentItems.map((item) => item.id));
+              existingGroup.itemLayoutTemplates = Object.fromEntries(
+                Object.entries(existingGroup.itemLayoutTemplates ?? {}).filter(([id]) => itemIds.has(id))
+              );
               existingGroup.contents = currentItems.map((item) => ({
                 id: item.id,
                 title: item.title,